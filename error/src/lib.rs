@@ -54,6 +54,34 @@ pub mod panic {
     pub fn explicitly_call_panic_marco() {
         // panic!("crash and burn");
     }
+
+    /// Before unwinding starts, a panic calls the panic hook, which by default prints the
+    /// `thread '..' panicked at ..` message. `set_hook` replaces it for the whole process, to log
+    /// panics elsewhere or count them, and `take_hook` removes the custom one and puts the
+    /// default hook back.
+    ///
+    /// The hook may be called from any thread that panics, so it must be `Send + Sync`: the flag
+    /// it sets is an `AtomicBool` shared through an `Arc`, a `Rc<Cell<bool>>` would not compile.
+    /// The hook is process-wide, a panic on another thread while it is installed sets the flag
+    /// too.
+    pub fn with_custom_panic_hook() -> bool {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let fired: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        let flag: Arc<AtomicBool> = Arc::clone(&fired);
+        std::panic::set_hook(Box::new(move |_info| {
+            flag.store(true, Ordering::SeqCst);
+        }));
+
+        let result = std::panic::catch_unwind(|| {
+            panic!("caught by catch_unwind");
+        });
+        assert!(result.is_err());
+
+        let _ = std::panic::take_hook();
+        fired.load(Ordering::SeqCst)
+    }
 }
 
 pub mod result {
@@ -156,6 +184,835 @@ pub mod result {
     }
 }
 
+pub mod health {
+    //! An error budget: instead of reacting to every single failure, record the outcome of the last
+    //! N operations and decide from the aggregate whether the dependency is still healthy. This is
+    //! the core of a circuit breaker.
+
+    use std::collections::VecDeque;
+    use std::error::Error;
+    use std::fmt;
+
+    #[derive(Debug, PartialEq)]
+    pub enum Guarded<E> {
+        /// The success rate is below the threshold, the operation was not run.
+        CircuitOpen,
+        /// The operation ran and failed with its own error.
+        Inner(E),
+    }
+
+    impl<E> fmt::Display for Guarded<E> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Guarded::CircuitOpen => write!(f, "circuit open, the operation was not run"),
+                Guarded::Inner(_) => write!(f, "the guarded operation failed"),
+            }
+        }
+    }
+
+    impl<E: Error + 'static> Error for Guarded<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                Guarded::CircuitOpen => None,
+                Guarded::Inner(e) => Some(e),
+            }
+        }
+    }
+
+    pub struct HealthTracker {
+        window: VecDeque<bool>,
+        capacity: usize,
+    }
+
+    impl HealthTracker {
+        pub fn new(capacity: usize) -> Self {
+            Self {
+                window: VecDeque::with_capacity(capacity),
+                capacity,
+            }
+        }
+
+        /// Records whether `r` is `Ok`, evicting the oldest outcome once the window is full.
+        pub fn record<T, E>(&mut self, r: &Result<T, E>) {
+            if self.capacity == 0 {
+                return;
+            }
+            if self.window.len() == self.capacity {
+                self.window.pop_front();
+            }
+            self.window.push_back(r.is_ok());
+        }
+
+        /// The fraction of successful outcomes in the window. An empty window has seen no failure,
+        /// so its rate is defined as `1.0`.
+        pub fn success_rate(&self) -> f64 {
+            if self.window.is_empty() {
+                return 1.0;
+            }
+            let successes: usize = self.window.iter().filter(|&&ok| ok).count();
+            successes as f64 / self.window.len() as f64
+        }
+
+        /// The number of failures recorded since the last success.
+        pub fn consecutive_failures(&self) -> usize {
+            self.window.iter().rev().take_while(|&&ok| !ok).count()
+        }
+
+        pub fn is_healthy(&self, threshold: f64) -> bool {
+            self.success_rate() >= threshold
+        }
+
+        /// Forgets every recorded outcome, closing the circuit again.
+        pub fn reset(&mut self) {
+            self.window.clear();
+        }
+    }
+
+    /// Runs `op` and records its outcome, unless the tracker is unhealthy.
+    ///
+    /// When the circuit is open nothing is recorded: the operation didn't run, so there is no
+    /// outcome to report, and counting the rejection as a failure would keep the circuit open
+    /// forever. The circuit closes again after `HealthTracker::reset`.
+    pub fn guarded<T, E>(
+        tracker: &mut HealthTracker,
+        min_rate: f64,
+        op: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, Guarded<E>> {
+        if !tracker.is_healthy(min_rate) {
+            return Err(Guarded::CircuitOpen);
+        }
+        let result: Result<T, E> = op();
+        tracker.record(&result);
+        result.map_err(Guarded::Inner)
+    }
+}
+
+pub mod config {
+    use std::error::Error;
+    use std::fmt;
+    use std::io;
+
+    #[derive(Debug)]
+    pub enum ConfigError {
+        /// A required key is absent.
+        Missing(String),
+        /// A key is present but its value can't be used.
+        Invalid { key: String, value: String },
+        /// The configuration file couldn't be read.
+        Io(io::Error),
+    }
+
+    impl fmt::Display for ConfigError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                ConfigError::Missing(key) => write!(f, "missing key `{}`", key),
+                ConfigError::Invalid { key, value } => {
+                    write!(f, "invalid value `{}` for key `{}`", value, key)
+                }
+                ConfigError::Io(_) => write!(f, "can't read the configuration"),
+            }
+        }
+    }
+
+    impl Error for ConfigError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                ConfigError::Io(e) => Some(e),
+                _ => None,
+            }
+        }
+    }
+}
+
+pub mod downcast {
+    //! A `Box<dyn Error>` erases the concrete error type, but since `dyn Error + 'static` knows its
+    //! `TypeId`, the concrete type can be recovered at runtime:
+    //!
+    //! * `downcast_ref::<T>()` borrows the error as a `&T` if it is a `T`, the box is kept.
+    //! * `Box::downcast::<T>()` consumes the box and returns `Box<T>`, or gives the box back as
+    //!   `Err` if it is not a `T`, so the next type can be tried.
+
+    use crate::config::ConfigError;
+    use std::any::type_name;
+    use std::error::Error;
+    use std::io;
+    use std::num::{ParseFloatError, ParseIntError};
+
+    #[derive(Debug, PartialEq)]
+    pub enum Handled {
+        /// An interrupted io operation, worth trying again.
+        Retried,
+        /// A missing configuration key, reported to the user by name.
+        Reported { field: String },
+        /// Anything else, with its message.
+        Fallback(String),
+    }
+
+    pub fn handle(e: Box<dyn Error>) -> Handled {
+        // borrow to inspect: the box is still ours afterward
+        if let Some(io_error) = e.downcast_ref::<io::Error>() {
+            if io_error.kind() == io::ErrorKind::Interrupted {
+                return Handled::Retried;
+            }
+        }
+
+        // consume to take the fields out by value
+        match e.downcast::<ConfigError>() {
+            Ok(config_error) => match *config_error {
+                ConfigError::Missing(field) => Handled::Reported { field },
+                other => Handled::Fallback(other.to_string()),
+            },
+            Err(e) => Handled::Fallback(e.to_string()),
+        }
+    }
+
+    fn probe<T: Error + 'static>(e: &(dyn Error + 'static)) -> Option<&'static str> {
+        e.downcast_ref::<T>().map(|_| type_name::<T>())
+    }
+
+    /// `type_name` needs the type at compile time, so it can't name what is behind a `dyn Error`.
+    /// Instead, every error of the chain is probed against a registry of known types.
+    const REGISTRY: [fn(&(dyn Error + 'static)) -> Option<&'static str>; 4] = [
+        probe::<io::Error>,
+        probe::<ConfigError>,
+        probe::<ParseIntError>,
+        probe::<ParseFloatError>,
+    ];
+
+    /// Labels the error and each of its sources, `"unknown"` for types outside the registry. Only
+    /// `'static` errors carry a `TypeId`, hence the explicit bound.
+    pub fn error_chain_types(e: &(dyn Error + 'static)) -> Vec<&'static str> {
+        std::iter::successors(Some(e), |&e| e.source())
+            .map(|e| {
+                REGISTRY
+                    .iter()
+                    .find_map(|probe| probe(e))
+                    .unwrap_or("unknown")
+            })
+            .collect()
+    }
+}
+
+pub mod file_io {
+    //! End-to-end file processing, where every step that touches the file system can fail and
+    //! the `?` operator hands the `io::Error` back to the caller.
+
+    use std::fs::{self, File};
+    use std::io::{self, BufRead, BufReader, Write};
+
+    pub fn count_words_in_file(path: &str) -> Result<usize, io::Error> {
+        Ok(fs::read_to_string(path)?.split_whitespace().count())
+    }
+
+    /// `BufReader` reads the file in chunks (8 KiB by default) into an internal buffer, and
+    /// `lines()` yields one line at a time from it. Only one chunk and the current line are held in
+    /// memory, unlike `fs::read_to_string` which loads the whole file at once, so a file larger
+    /// than memory can still be processed line by line. Collecting into a `Vec`, as done here,
+    /// gives that advantage up, it is for demonstration.
+    ///
+    /// Each line comes as an `io::Result<String>` without its trailing `\n` or `\r\n`, and
+    /// collecting into `Result<Vec<_>, _>` stops at the first error.
+    pub fn read_lines(path: &str) -> Result<Vec<String>, io::Error> {
+        let reader: BufReader<File> = BufReader::new(File::open(path)?);
+        reader.lines().collect()
+    }
+
+    /// The `std::io::Write` trait is the counterpart of `Read` for byte sinks such as `File`,
+    /// `Vec<u8>` or `Stdout`. Its required methods are `write`, which may write only part of the
+    /// buffer, and `flush`; provided methods like `write_all` and `write_fmt` build on them. The
+    /// `writeln!` macro calls `write_fmt`, so the trait must be in scope, and it returns an
+    /// `io::Result<()>` that `?` can propagate.
+    ///
+    /// `File::create` truncates an existing file. The file is closed when it is dropped.
+    pub fn write_report(path: &str, lines: &[&str]) -> Result<(), io::Error> {
+        let mut file: File = File::create(path)?;
+        for line in lines {
+            writeln!(file, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+pub mod error_conformance {
+    //! An error type composes with `Box<dyn Error + Send + Sync>`, the usual "any error" type, only
+    //! if it implements `Debug`, `Display` and `Error`, holds no non-thread-safe data such as an
+    //! `Rc`, and borrows nothing so that it is `'static` (needed for downcasting too).
+    //!
+    //! `assert_error_type` has an empty body, calling it is a compile-time check: if a listed
+    //! type loses one of those impls, the crate's tests stop compiling.
+
+    use crate::closeable::CloseError;
+    use crate::config::ConfigError;
+    use crate::health::Guarded;
+    use std::error::Error;
+    use std::fs::File;
+    use std::io;
+
+    pub type BoxError = Box<dyn Error + Send + Sync>;
+
+    pub fn assert_error_type<E: Error + Send + Sync + 'static>() {}
+
+    pub fn boxed<E: Error + Send + Sync + 'static>(e: E) -> BoxError {
+        Box::new(e)
+    }
+
+    /// Every arm fails with a different concrete error, `?` converts each into `BoxError` through
+    /// the standard library's blanket `From<E>` impl for `Box<dyn Error + Send + Sync>`, which
+    /// covers every `E: Error + Send + Sync`.
+    pub fn any_step(which: u8) -> Result<(), BoxError> {
+        match which {
+            1 => {
+                File::open("not_exist")?;
+            }
+            2 => {
+                "forty-two".parse::<i32>()?;
+            }
+            3 => {
+                "pi".parse::<f64>()?;
+            }
+            4 => Err(ConfigError::Missing(String::from("port")))?,
+            5 => Err(Guarded::<io::Error>::CircuitOpen)?,
+            6 => Err(Guarded::Inner(ConfigError::Missing(String::from("host"))))?,
+            7 => Err(CloseError {
+                name: String::from("broken pipe"),
+            })?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+pub mod adhoc {
+    //! A one-off error, for when a function can fail in ways nobody matches on and a message is
+    //! all the caller needs, like `anyhow::Error` but without the dependency.
+    //!
+    //! `bail!` returns early with an `AdHoc` built from a format string, `ensure!` does so only
+    //! when its condition is false. Both expand to `return Err(From::from(..))`, the conversion
+    //! `?` applies, so they also work in a function returning `Result<_, BoxError>`.
+    //!
+    //! `AdHoc` can't convert from every error with `?` as `anyhow::Error` does: a blanket
+    //! `impl<E: Error> From<E> for AdHoc` overlaps with `From<AdHoc> for AdHoc`, since `AdHoc` is
+    //! an `Error` itself. `Context::context` wraps a foreign error instead, with a message saying
+    //! what was being done, and keeps the original as the `source`.
+
+    use crate::error_conformance::BoxError;
+    use std::error::Error;
+    use std::fmt;
+    use std::fs;
+
+    #[derive(Debug)]
+    pub struct AdHoc(String, Option<BoxError>);
+
+    pub fn adhoc(msg: impl Into<String>) -> AdHoc {
+        AdHoc(msg.into(), None)
+    }
+
+    impl fmt::Display for AdHoc {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl Error for AdHoc {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            self.1
+                .as_ref()
+                .map(|e| e.as_ref() as &(dyn Error + 'static))
+        }
+    }
+
+    pub trait Context<T> {
+        fn context(self, msg: impl Into<String>) -> Result<T, AdHoc>;
+    }
+
+    impl<T, E: Error + Send + Sync + 'static> Context<T> for Result<T, E> {
+        fn context(self, msg: impl Into<String>) -> Result<T, AdHoc> {
+            self.map_err(|e| AdHoc(msg.into(), Some(Box::new(e))))
+        }
+    }
+
+    #[macro_export]
+    macro_rules! bail {
+        ($($arg:tt)+) => {
+            return Err(::std::convert::From::from($crate::adhoc::adhoc(format!($($arg)+))))
+        };
+    }
+
+    #[macro_export]
+    macro_rules! ensure {
+        ($cond:expr, $($arg:tt)+) => {
+            if !$cond {
+                $crate::bail!($($arg)+);
+            }
+        };
+    }
+
+    /// `result::read_username_from_file` with a path, and a check of what was read: a single
+    /// line ending is allowed, the name itself must be non-empty and without whitespace.
+    pub fn read_username_from_file(path: &str) -> Result<String, AdHoc> {
+        let content: String =
+            fs::read_to_string(path).context(format!("can't read the username from {}", path))?;
+        let username: &str = content.strip_suffix('\n').map_or(content.as_str(), |line| {
+            line.strip_suffix('\r').unwrap_or(line)
+        });
+        crate::ensure!(!username.is_empty(), "the username in {} is empty", path);
+        crate::ensure!(
+            !username.contains(char::is_whitespace),
+            "the username {:?} contains whitespace",
+            username
+        );
+        Ok(username.to_string())
+    }
+}
+
+pub mod option_question_mark {
+    //! `?` works on `Option` as it does on `Result`: `Some(v)?` evaluates to `v`, and `None?`
+    //! returns `None` from the enclosing function, which must itself return an `Option`. A chain
+    //! of lookups that may each find nothing reads as a straight line instead of nested
+    //! `match`es. `?` does not convert between the two types, `ok_or` turns an `Option` into a
+    //! `Result` and `ok()` goes the other way.
+    //!
+    //! Returning early is only harmless before anything was changed. A function that mutates
+    //! in several steps has to check that every step will succeed first, otherwise a `None` in
+    //! the middle leaves the work half done, which is why `reserve` counts the stock before it
+    //! takes any.
+
+    use std::collections::HashMap;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Batch {
+        pub quantity: u32,
+        /// The tick after which the batch can't be used.
+        pub expiry: u64,
+    }
+
+    /// What `reserve` took, one `Batch` per batch it took from, with the quantity taken.
+    #[derive(Debug, PartialEq)]
+    pub struct Reservation {
+        pub item: String,
+        pub batches: Vec<Batch>,
+    }
+
+    impl Reservation {
+        pub fn quantity(&self) -> u32 {
+            self.batches.iter().map(|batch| batch.quantity).sum()
+        }
+    }
+
+    /// The batches of an item are kept in the order they were added, and used in that order.
+    #[derive(Debug, Default, Clone, PartialEq)]
+    pub struct Inventory {
+        items: HashMap<String, Vec<Batch>>,
+    }
+
+    impl Inventory {
+        pub fn new() -> Self {
+            Inventory {
+                items: HashMap::new(),
+            }
+        }
+
+        pub fn add(&mut self, item: &str, batch: Batch) {
+            self.items.entry(item.to_string()).or_default().push(batch);
+        }
+
+        /// Registers an item with no batches yet.
+        pub fn track(&mut self, item: &str) {
+            self.items.entry(item.to_string()).or_default();
+        }
+
+        pub fn first_available(&self, item: &str) -> Option<&Batch> {
+            self.items
+                .get(item)?
+                .iter()
+                .find(|batch| batch.quantity > 0)
+        }
+
+        /// The expiry of the batch that expires first among those not used up.
+        pub fn earliest_expiry(&self, item: &str) -> Option<u64> {
+            let batches: &Vec<Batch> = self.items.get(item)?;
+            batches
+                .iter()
+                .filter(|batch| batch.quantity > 0)
+                .map(|batch| batch.expiry)
+                .min()
+        }
+
+        pub fn stock(&self, item: &str) -> Option<u64> {
+            Some(
+                self.items
+                    .get(item)?
+                    .iter()
+                    .map(|batch| batch.quantity as u64)
+                    .sum(),
+            )
+        }
+
+        /// Takes `qty` from the batches of `item`, first batch first. `None` if the item is
+        /// unknown or the stock is insufficient, and in both cases nothing was taken.
+        pub fn reserve(&mut self, item: &str, qty: u32) -> Option<Reservation> {
+            // the check pass: every `?` is before the first mutation
+            if self.stock(item)? < qty as u64 {
+                return None;
+            }
+            let batches: &mut Vec<Batch> = self.items.get_mut(item)?;
+
+            let mut reservation: Reservation = Reservation {
+                item: item.to_string(),
+                batches: vec![],
+            };
+            let mut missing: u32 = qty;
+            for batch in batches.iter_mut().filter(|batch| batch.quantity > 0) {
+                if missing == 0 {
+                    break;
+                }
+                let taken: u32 = missing.min(batch.quantity);
+                batch.quantity -= taken;
+                missing -= taken;
+                reservation.batches.push(Batch {
+                    quantity: taken,
+                    expiry: batch.expiry,
+                });
+            }
+            Some(reservation)
+        }
+
+        /// Moves `qty` from one item to another, keeping the expiry of each batch. Both items
+        /// must exist, which is checked before anything is reserved.
+        pub fn transfer(&mut self, from_item: &str, to_item: &str, qty: u32) -> Option<()> {
+            self.items.get(to_item)?;
+            let reservation: Reservation = self.reserve(from_item, qty)?;
+            self.items.get_mut(to_item)?.extend(reservation.batches);
+            Some(())
+        }
+    }
+}
+
+pub mod fallible_iter {
+    //! An iterator of `Result`s, like the lines of a file being parsed, usually has to stop at
+    //! the first error. `collect::<Result<Vec<T>, E>>()` does that but throws the good items
+    //! away. `TryTake` yields the `T`s up to the first `Err` and keeps the error, with the index
+    //! of the item that failed, to be looked at afterwards:
+    //!
+    //! ```text
+    //! Ok(1) Ok(2) Err(e) Ok(4)   ->   yields 1 2, error (2, e), Ok(4) is never pulled
+    //! ```
+    //!
+    //! Iterators are lazy, so stopping early also means the items after the error are never
+    //! computed: a `map` closure behind the error does not run for them.
+
+    use std::fmt;
+    use std::marker::PhantomData;
+
+    pub struct TryTake<I, T, E> {
+        iter: I,
+        position: usize,
+        error: Option<(usize, E)>,
+        // `T` only appears in the bounds, `fn() -> T` marks it without owning a `T`
+        _item: PhantomData<fn() -> T>,
+    }
+
+    impl<I: Iterator<Item = Result<T, E>>, T, E> TryTake<I, T, E> {
+        pub fn new(iter: I) -> Self {
+            TryTake {
+                iter,
+                position: 0,
+                error: None,
+                _item: PhantomData,
+            }
+        }
+
+        /// The index of the failed item and its error, once it was reached.
+        pub fn error(&self) -> Option<&(usize, E)> {
+            self.error.as_ref()
+        }
+
+        pub fn into_error(self) -> Option<(usize, E)> {
+            self.error
+        }
+    }
+
+    impl<I: Iterator<Item = Result<T, E>>, T, E> Iterator for TryTake<I, T, E> {
+        type Item = T;
+
+        fn next(&mut self) -> Option<T> {
+            if self.error.is_some() {
+                return None;
+            }
+            match self.iter.next()? {
+                Ok(item) => {
+                    self.position += 1;
+                    Some(item)
+                }
+                Err(e) => {
+                    self.error = Some((self.position, e));
+                    None
+                }
+            }
+        }
+    }
+
+    /// Adds the methods to every iterator of `Result`s.
+    pub trait UntilError<T, E>: Iterator<Item = Result<T, E>> + Sized {
+        fn try_take(self) -> TryTake<Self, T, E> {
+            TryTake::new(self)
+        }
+
+        /// The items before the first error, and the error with its index if there was one.
+        fn until_error(self) -> (Vec<T>, Option<(usize, E)>) {
+            let mut items = self.try_take();
+            let values: Vec<T> = items.by_ref().collect();
+            (values, items.into_error())
+        }
+    }
+
+    impl<I: Iterator<Item = Result<T, E>>, T, E> UntilError<T, E> for I {}
+
+    pub fn all_or_nothing<I, T, E>(iter: I) -> Result<Vec<T>, (usize, E)>
+    where
+        I: IntoIterator<Item = Result<T, E>>,
+    {
+        match iter.into_iter().until_error() {
+            (values, None) => Ok(values),
+            (_, Some(error)) => Err(error),
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct LineError<E> {
+        /// Starting at 1, as an editor shows it.
+        pub line: usize,
+        pub error: E,
+    }
+
+    impl<E: fmt::Display> fmt::Display for LineError<E> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "line {}: {}", self.line, self.error)
+        }
+    }
+
+    impl<E: std::error::Error + 'static> std::error::Error for LineError<E> {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.error)
+        }
+    }
+
+    /// Maps every line with `f`, stopping at the first line it fails on.
+    pub fn process_lines<E, F: FnMut(&str) -> Result<String, E>>(
+        input: &str,
+        f: F,
+    ) -> Result<Vec<String>, LineError<E>> {
+        all_or_nothing(input.lines().map(f)).map_err(|(index, error)| LineError {
+            line: index + 1,
+            error,
+        })
+    }
+}
+
+pub mod result_option_convert {
+    //! `Result` and `Option` convert into each other, but going from `Result` to `Option` loses
+    //! something: one side of the `Result` is thrown away.
+    //!
+    //! * `ok()` keeps the `Ok` value as `Some` and drops the error, `err()` keeps the error as
+    //!   `Some` and drops the value;
+    //! * `ok_or(e)` turns `None` into `Err(e)`, `e` is evaluated even when the `Option` is
+    //!   `Some`, `ok_or_else(|| e)` only builds it when needed.
+    //!
+    //! ```text
+    //!              ok()                         ok_or(e)
+    //! Ok(v)   ---------> Some(v)      Some(v) ---------> Ok(v)
+    //! Err(e)  ---------> None         None    ---------> Err(e)
+    //! ```
+
+    pub fn result_to_option() -> Option<i32> {
+        let value: Option<i32> = Ok::<_, String>(5).ok();
+        assert_eq!(value, Some(5));
+        assert_eq!(Err::<i32, _>(String::from("e")).ok(), None);
+        value
+    }
+
+    pub fn error_to_option() -> Option<&'static str> {
+        let error: Option<&str> = Err::<i32, _>("e").err();
+        assert_eq!(error, Some("e"));
+        assert_eq!(Ok::<i32, &str>(5).err(), None);
+        error
+    }
+
+    pub fn option_to_result() -> Result<i32, &'static str> {
+        let value: Result<i32, &str> = Some(5).ok_or("missing");
+        assert_eq!(value, Ok(5));
+        assert_eq!(None::<i32>.ok_or("missing"), Err("missing"));
+        value
+    }
+
+    /// `transpose` swaps the two layers of an `Option<Result<T, E>>`, giving a
+    /// `Result<Option<T>, E>`, and back. Mapping a fallible operation over an optional value
+    /// gives the first one, `opt.map(|s| s.parse())`, but a caller usually wants the second,
+    /// so that `?` can return the error and leave the `Option`:
+    ///
+    /// ```text
+    /// Some(Ok(v))  <-->  Ok(Some(v))
+    /// Some(Err(e)) <-->  Err(e)
+    /// None         <-->  Ok(None)
+    /// ```
+    pub fn transpose_demo() {
+        let e: &str = "not a number";
+        assert_eq!(Some(Ok::<i32, &str>(5)).transpose(), Ok(Some(5)));
+        assert_eq!(Some(Err::<i32, &str>(e)).transpose(), Err(e));
+        assert_eq!(None::<Result<i32, &str>>.transpose(), Ok(None));
+        // and back
+        assert_eq!(Ok::<Option<i32>, &str>(Some(5)).transpose(), Some(Ok(5)));
+
+        let parse = |input: Option<&str>| -> Result<Option<i32>, std::num::ParseIntError> {
+            input.map(|s| s.parse::<i32>()).transpose()
+        };
+        assert_eq!(parse(Some("5")), Ok(Some(5)));
+        assert_eq!(parse(None), Ok(None));
+        assert!(parse(Some("five")).is_err());
+    }
+}
+
+pub mod assertions {
+    //! The assertion macros panic when a condition the code relies on does not hold. They are
+    //! for bugs, not for bad input: a missing file or an invalid number from a user is expected
+    //! to happen and is returned as an `Err`.
+    //!
+    //! * `assert!(cond, "fmt", args..)` checks an invariant in every build. The message after the
+    //!   condition is a `format!` string, it says which values broke the invariant.
+    //! * `assert_eq!(left, right)` and `assert_ne!` compare two values and print both with
+    //!   `Debug` on failure, so they are the usual choice in tests.
+    //! * `debug_assert!`, `debug_assert_eq!` and `debug_assert_ne!` are compiled out when
+    //!   `debug_assertions` is off, as in `--release` by default. They suit checks too costly to
+    //!   run in production, or ones in hot loops, and must have no side effect the code needs.
+
+    /// Panics with `x must be positive, got -1` for `-1`.
+    pub fn assert_with_message(x: i32) -> i32 {
+        assert!(x > 0, "x must be positive, got {}", x);
+        x
+    }
+
+    /// The message of a failed `assert_eq!`, caught with `catch_unwind`:
+    ///
+    /// ```text
+    /// assertion `left == right` failed: sums differ
+    ///   left: 3
+    ///  right: 4
+    /// ```
+    pub fn assert_eq_message() -> String {
+        let result = std::panic::catch_unwind(|| {
+            assert_eq!(1 + 2, 4, "sums differ");
+        });
+        let payload = result.unwrap_err();
+        match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(_) => String::from("not a formatted message"),
+        }
+    }
+
+    /// Checks that `v` is sorted only in debug builds, where the O(n) check is affordable,
+    /// before an O(log n) search that would give a wrong answer on unsorted input.
+    pub fn debug_assert_sorted(v: &[i32], target: i32) -> Option<usize> {
+        debug_assert!(v.windows(2).all(|w| w[0] <= w[1]), "{:?} is not sorted", v);
+        v.binary_search(&target).ok()
+    }
+}
+
+pub mod closeable {
+    //! `Drop::drop` returns `()`, so a destructor has no way to report that releasing a resource
+    //! failed: flushing a buffer, committing a transaction or closing a connection can all fail, and
+    //! by the time `drop` runs there is nobody left to tell.
+    //!
+    //! The standard pattern is to give the type an explicit `close(self) -> Result<..>` that the
+    //! caller is expected to call, and keep `Drop` only as a safety net for when they didn't. `close`
+    //! takes `self` by value, so a closed resource can't be used again, and it marks the resource as
+    //! closed so that the `Drop` that still runs at the end of `close` does nothing. `std::fs::File`
+    //! follows the same idea with `sync_all`, whose error a plain drop would silently ignore.
+
+    use std::cell::RefCell;
+    use std::fmt;
+    use std::rc::Rc;
+
+    /// Every resource whose name starts with this prefix fails to close.
+    pub const FAILING_PREFIX: &str = "broken";
+
+    #[derive(Debug, PartialEq)]
+    pub struct CloseError {
+        pub name: String,
+    }
+
+    impl fmt::Display for CloseError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "failed to close {}", self.name)
+        }
+    }
+
+    impl std::error::Error for CloseError {}
+
+    pub struct Resource {
+        name: String,
+        closed: bool,
+        sink: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Resource {
+        pub fn new(name: &str, sink: Rc<RefCell<Vec<String>>>) -> Self {
+            sink.borrow_mut().push(format!("opened {}", name));
+            Resource {
+                name: name.to_string(),
+                closed: false,
+                sink,
+            }
+        }
+
+        pub fn name(&self) -> &str {
+            &self.name
+        }
+
+        pub fn write(&mut self, data: &str) {
+            self.sink
+                .borrow_mut()
+                .push(format!("{} <- {}", self.name, data));
+        }
+
+        /// Closing is attempted once: a failure is returned to the caller instead of being reported
+        /// as a leak, since the caller now knows about it.
+        pub fn close(mut self) -> Result<(), CloseError> {
+            self.closed = true;
+            if self.name.starts_with(FAILING_PREFIX) {
+                return Err(CloseError {
+                    name: self.name.clone(),
+                });
+            }
+            self.sink.borrow_mut().push(format!("closed {}", self.name));
+            Ok(())
+        }
+    }
+
+    impl Drop for Resource {
+        fn drop(&mut self) {
+            if !self.closed {
+                self.sink
+                    .borrow_mut()
+                    .push(format!("{} leaked without close", self.name));
+            }
+        }
+    }
+
+    /// Opens a resource, lends it to `f`, and closes it afterwards. However `f` returns, early or
+    /// not, control comes back here and `close` is called, so its error reaches the caller. When
+    /// closing fails the value produced by `f` is discarded.
+    pub fn with_resource<R>(
+        name: &str,
+        sink: Rc<RefCell<Vec<String>>>,
+        f: impl FnOnce(&mut Resource) -> R,
+    ) -> Result<R, CloseError> {
+        let mut resource: Resource = Resource::new(name, sink);
+        let result: R = f(&mut resource);
+        resource.close()?;
+        Ok(result)
+    }
+}
+
 mod testing {
     #[test]
     #[should_panic]
@@ -178,4 +1035,659 @@ mod testing {
     fn run_result_shortcut_for_panic_on_error() {
         crate::result::shortcut_for_panic_on_error()
     }
+
+    #[test]
+    fn run_health_window_eviction() {
+        use crate::health::HealthTracker;
+        let mut tracker: HealthTracker = HealthTracker::new(3);
+        assert_eq!(tracker.success_rate(), 1.0);
+        assert_eq!(tracker.consecutive_failures(), 0);
+
+        tracker.record(&Err::<(), &str>("boom"));
+        tracker.record(&Ok::<i32, &str>(1));
+        tracker.record(&Ok::<i32, &str>(2));
+        assert!((tracker.success_rate() - 2.0 / 3.0).abs() < f64::EPSILON);
+
+        // the oldest failure is evicted at capacity
+        tracker.record(&Ok::<i32, &str>(3));
+        assert_eq!(tracker.success_rate(), 1.0);
+
+        tracker.record(&Err::<(), &str>("boom"));
+        tracker.record(&Err::<(), &str>("boom"));
+        assert_eq!(tracker.consecutive_failures(), 2);
+        assert!(!tracker.is_healthy(0.5));
+        assert!(tracker.is_healthy(0.3));
+    }
+
+    #[test]
+    fn run_health_guarded_circuit() {
+        use crate::health::{guarded, Guarded, HealthTracker};
+        use std::num::ParseIntError;
+
+        let mut tracker: HealthTracker = HealthTracker::new(4);
+        let parse = |s: &'static str| move || s.parse::<i32>();
+        assert_eq!(guarded(&mut tracker, 0.5, parse("1")), Ok(1));
+        assert!(matches!(
+            guarded(&mut tracker, 0.5, parse("x")),
+            Err(Guarded::Inner(ParseIntError { .. }))
+        ));
+        assert!(matches!(
+            guarded(&mut tracker, 0.5, parse("y")),
+            Err(Guarded::Inner(_))
+        ));
+        assert_eq!(tracker.consecutive_failures(), 2);
+
+        // 1 of 3 succeeded: the circuit opens and the operation is not even run
+        let mut ran: bool = false;
+        let result = guarded(&mut tracker, 0.5, || {
+            ran = true;
+            "2".parse::<i32>()
+        });
+        assert_eq!(result, Err(Guarded::CircuitOpen));
+        assert!(!ran);
+        assert_eq!(tracker.consecutive_failures(), 2);
+
+        // a manual reset closes the circuit again
+        tracker.reset();
+        assert_eq!(guarded(&mut tracker, 0.5, parse("2")), Ok(2));
+
+        // the same tracker works with a different error type
+        let result: Result<(), Guarded<std::io::Error>> =
+            guarded(&mut tracker, 0.5, || std::fs::read("not_exist").map(|_| ()));
+        assert!(
+            matches!(result, Err(Guarded::Inner(e)) if e.kind() == std::io::ErrorKind::NotFound)
+        );
+        assert!((tracker.success_rate() - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn run_downcast_handle() {
+        use crate::config::ConfigError;
+        use crate::downcast::{handle, Handled};
+        use std::error::Error;
+        use std::io;
+
+        let interrupted: Box<dyn Error> = Box::new(io::Error::from(io::ErrorKind::Interrupted));
+        assert_eq!(handle(interrupted), Handled::Retried);
+
+        let missing: Box<dyn Error> = Box::new(ConfigError::Missing(String::from("port")));
+        assert_eq!(
+            handle(missing),
+            Handled::Reported {
+                field: String::from("port")
+            }
+        );
+
+        let invalid: Box<dyn Error> = Box::new(ConfigError::Invalid {
+            key: String::from("port"),
+            value: String::from("eighty"),
+        });
+        assert_eq!(
+            handle(invalid),
+            Handled::Fallback(String::from("invalid value `eighty` for key `port`"))
+        );
+
+        let not_found: Box<dyn Error> = Box::new(io::Error::new(io::ErrorKind::NotFound, "gone"));
+        assert_eq!(handle(not_found), Handled::Fallback(String::from("gone")));
+
+        let parse: Box<dyn Error> = Box::new("x".parse::<i32>().unwrap_err());
+        assert_eq!(
+            handle(parse),
+            Handled::Fallback(String::from("invalid digit found in string"))
+        );
+    }
+
+    #[test]
+    fn run_downcast_error_chain_types() {
+        use crate::config::ConfigError;
+        use crate::downcast::error_chain_types;
+        use std::any::type_name;
+        use std::io;
+
+        let chained: ConfigError = ConfigError::Io(io::Error::from(io::ErrorKind::NotFound));
+        assert_eq!(
+            error_chain_types(&chained),
+            vec![type_name::<ConfigError>(), type_name::<io::Error>()]
+        );
+
+        let float: std::num::ParseFloatError = "x".parse::<f64>().unwrap_err();
+        assert_eq!(
+            error_chain_types(&float),
+            vec![type_name::<std::num::ParseFloatError>()]
+        );
+
+        assert_eq!(error_chain_types(&std::fmt::Error), vec!["unknown"]);
+    }
+
+    #[test]
+    fn run_file_io_count_words_in_file() {
+        let path: std::path::PathBuf = std::env::temp_dir().join("error_count_words_in_file.txt");
+        std::fs::write(&path, "hello rust\n  the  error crate\n").unwrap();
+        let count = crate::file_io::count_words_in_file(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(count.unwrap(), 5);
+    }
+
+    #[test]
+    fn run_file_io_count_words_in_missing_file() {
+        let count = crate::file_io::count_words_in_file("not_exist");
+        assert_eq!(count.unwrap_err().kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn run_file_io_read_lines() {
+        let path: std::path::PathBuf = std::env::temp_dir().join("error_read_lines.txt");
+        std::fs::write(&path, "first line\r\nsecond\n\nlast").unwrap();
+        let lines = crate::file_io::read_lines(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(lines.unwrap(), vec!["first line", "second", "", "last"]);
+    }
+
+    #[test]
+    fn run_file_io_write_report() {
+        let path: std::path::PathBuf = std::env::temp_dir().join("error_write_report.txt");
+        let lines: [&str; 3] = ["title", "", "total: 3"];
+        let written = crate::file_io::write_report(path.to_str().unwrap(), &lines);
+        let content = std::fs::read_to_string(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(written.is_ok());
+        assert_eq!(content.unwrap(), "title\n\ntotal: 3\n");
+    }
+
+    #[test]
+    fn run_error_conformance() {
+        use crate::adhoc::AdHoc;
+        use crate::closeable::CloseError;
+        use crate::config::ConfigError;
+        use crate::error_conformance::assert_error_type;
+        use crate::fallible_iter::LineError;
+        use crate::health::Guarded;
+        use std::io;
+        use std::num::{ParseFloatError, ParseIntError};
+
+        // every public error type of the crate, and the std errors its functions return
+        assert_error_type::<AdHoc>();
+        assert_error_type::<CloseError>();
+        assert_error_type::<ConfigError>();
+        assert_error_type::<Guarded<ConfigError>>();
+        assert_error_type::<Guarded<io::Error>>();
+        assert_error_type::<LineError<ParseIntError>>();
+        assert_error_type::<io::Error>();
+        assert_error_type::<ParseIntError>();
+        assert_error_type::<ParseFloatError>();
+    }
+
+    #[test]
+    fn run_error_conformance_boxed() {
+        use crate::closeable::CloseError;
+        use crate::config::ConfigError;
+        use crate::error_conformance::{any_step, boxed, BoxError};
+        use crate::health::Guarded;
+        use std::io;
+        use std::num::{ParseFloatError, ParseIntError};
+
+        let e: BoxError = boxed(ConfigError::Missing(String::from("port")));
+        assert_eq!(e.to_string(), "missing key `port`");
+
+        assert!(any_step(0).is_ok());
+        assert!(any_step(1).unwrap_err().is::<io::Error>());
+        assert!(any_step(2).unwrap_err().is::<ParseIntError>());
+        assert!(any_step(3).unwrap_err().is::<ParseFloatError>());
+        assert!(any_step(4).unwrap_err().is::<ConfigError>());
+        assert_eq!(
+            any_step(5).unwrap_err().to_string(),
+            "circuit open, the operation was not run"
+        );
+
+        assert_eq!(
+            any_step(7).unwrap_err().downcast_ref::<CloseError>(),
+            Some(&CloseError {
+                name: String::from("broken pipe")
+            })
+        );
+
+        let e: BoxError = any_step(6).unwrap_err();
+        assert!(e.is::<Guarded<ConfigError>>());
+        assert_eq!(e.source().unwrap().to_string(), "missing key `host`");
+
+        // the boxed error can cross threads
+        let message = std::thread::spawn(move || e.to_string()).join().unwrap();
+        assert_eq!(message, "the guarded operation failed");
+    }
+
+    #[test]
+    fn run_adhoc_macros() {
+        use std::error::Error;
+
+        fn first_negative(values: &[i32]) -> Result<usize, crate::adhoc::AdHoc> {
+            crate::ensure!(!values.is_empty(), "no values");
+            for (i, &value) in values.iter().enumerate() {
+                if value < 0 {
+                    return Ok(i);
+                }
+                if value > 100 {
+                    crate::bail!("value {} at {} is out of range", value, i);
+                }
+            }
+            crate::bail!("none of the {} values is negative", values.len())
+        }
+
+        assert_eq!(first_negative(&[3, -1, 500]).unwrap(), 1);
+        assert_eq!(first_negative(&[]).unwrap_err().to_string(), "no values");
+        assert_eq!(
+            first_negative(&[3, 500, -1]).unwrap_err().to_string(),
+            "value 500 at 1 is out of range"
+        );
+        let e = first_negative(&[1, 2]).unwrap_err();
+        assert_eq!(e.to_string(), "none of the 2 values is negative");
+        assert!(e.source().is_none());
+
+        // the macros convert into the return type's error, here through `From<AdHoc> for Box<..>`
+        fn positive(n: i32) -> Result<i32, crate::error_conformance::BoxError> {
+            let limit: i32 = 0;
+            crate::ensure!(n > limit, "{n} is not above {limit}");
+            Ok(n)
+        }
+        assert_eq!(positive(2).unwrap(), 2);
+        assert_eq!(positive(-2).unwrap_err().to_string(), "-2 is not above 0");
+    }
+
+    #[test]
+    fn run_adhoc_context_source() {
+        use crate::adhoc::{AdHoc, Context};
+        use std::error::Error;
+        use std::io;
+
+        let e: AdHoc = std::fs::read_to_string("not_exist")
+            .context("loading")
+            .unwrap_err();
+        assert_eq!(e.to_string(), "loading");
+        let source = e.source().unwrap();
+        assert_eq!(
+            source.downcast_ref::<io::Error>().unwrap().kind(),
+            io::ErrorKind::NotFound
+        );
+
+        let e: AdHoc = crate::adhoc::read_username_from_file("not_exist").unwrap_err();
+        assert_eq!(e.to_string(), "can't read the username from not_exist");
+        assert!(e.source().unwrap().is::<io::Error>());
+    }
+
+    #[test]
+    fn run_adhoc_read_username_from_file() {
+        let path: std::path::PathBuf = std::env::temp_dir().join("error_adhoc_username.txt");
+        let read = |content: &str| {
+            std::fs::write(&path, content).unwrap();
+            crate::adhoc::read_username_from_file(path.to_str().unwrap()).map_err(|e| e.to_string())
+        };
+        let valid = read("ferris\n");
+        let crlf = read("ferris\r\n");
+        let empty = read("");
+        let only_newline = read("\n");
+        let space = read("ferris crab");
+        let two_lines = read("ferris\ncrab\n");
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(valid.unwrap(), "ferris");
+        assert_eq!(crlf.unwrap(), "ferris");
+        assert!(empty.unwrap_err().ends_with("is empty"));
+        assert!(only_newline.unwrap_err().ends_with("is empty"));
+        assert_eq!(
+            space.unwrap_err(),
+            "the username \"ferris crab\" contains whitespace"
+        );
+        assert_eq!(
+            two_lines.unwrap_err(),
+            "the username \"ferris\\ncrab\" contains whitespace"
+        );
+    }
+
+    #[test]
+    fn run_option_question_mark_lookups() {
+        use crate::option_question_mark::{Batch, Inventory};
+        let mut inventory: Inventory = Inventory::new();
+        inventory.track("empty");
+        inventory.add(
+            "milk",
+            Batch {
+                quantity: 0,
+                expiry: 3,
+            },
+        );
+        inventory.add(
+            "milk",
+            Batch {
+                quantity: 2,
+                expiry: 9,
+            },
+        );
+        inventory.add(
+            "milk",
+            Batch {
+                quantity: 5,
+                expiry: 7,
+            },
+        );
+
+        // a missing item and an item without batches both give `None`
+        assert_eq!(inventory.first_available("bread"), None);
+        assert_eq!(inventory.earliest_expiry("bread"), None);
+        assert_eq!(inventory.first_available("empty"), None);
+        assert_eq!(inventory.earliest_expiry("empty"), None);
+        assert_eq!(inventory.stock("empty"), Some(0));
+        assert_eq!(inventory.stock("bread"), None);
+
+        // the used up batch is skipped
+        assert_eq!(
+            inventory.first_available("milk"),
+            Some(&Batch {
+                quantity: 2,
+                expiry: 9
+            })
+        );
+        assert_eq!(inventory.earliest_expiry("milk"), Some(7));
+    }
+
+    #[test]
+    fn run_option_question_mark_reserve() {
+        use crate::option_question_mark::{Batch, Inventory, Reservation};
+        let mut inventory: Inventory = Inventory::new();
+        inventory.track("empty");
+        inventory.add(
+            "milk",
+            Batch {
+                quantity: 2,
+                expiry: 9,
+            },
+        );
+        inventory.add(
+            "milk",
+            Batch {
+                quantity: 0,
+                expiry: 3,
+            },
+        );
+        inventory.add(
+            "milk",
+            Batch {
+                quantity: 5,
+                expiry: 7,
+            },
+        );
+        inventory.add(
+            "milk",
+            Batch {
+                quantity: 4,
+                expiry: 8,
+            },
+        );
+
+        assert_eq!(inventory.reserve("bread", 1), None);
+        assert_eq!(inventory.reserve("empty", 1), None);
+
+        // insufficient stock leaves every batch as it was
+        let before: Inventory = inventory.clone();
+        assert_eq!(inventory.reserve("milk", 12), None);
+        assert_eq!(inventory, before);
+
+        // split across batches, the empty one is not part of the reservation
+        let reservation: Reservation = inventory.reserve("milk", 4).unwrap();
+        assert_eq!(
+            reservation.batches,
+            vec![
+                Batch {
+                    quantity: 2,
+                    expiry: 9
+                },
+                Batch {
+                    quantity: 2,
+                    expiry: 7
+                }
+            ]
+        );
+        assert_eq!(reservation.quantity(), 4);
+        assert_eq!(
+            inventory.first_available("milk"),
+            Some(&Batch {
+                quantity: 3,
+                expiry: 7
+            })
+        );
+
+        // exactly what is left
+        let reservation: Reservation = inventory.reserve("milk", 7).unwrap();
+        assert_eq!(
+            reservation.batches,
+            vec![
+                Batch {
+                    quantity: 3,
+                    expiry: 7
+                },
+                Batch {
+                    quantity: 4,
+                    expiry: 8
+                }
+            ]
+        );
+        assert_eq!(inventory.stock("milk"), Some(0));
+        assert_eq!(inventory.first_available("milk"), None);
+        assert_eq!(inventory.reserve("milk", 1), None);
+    }
+
+    #[test]
+    fn run_option_question_mark_transfer() {
+        use crate::option_question_mark::{Batch, Inventory};
+        let mut inventory: Inventory = Inventory::new();
+        inventory.add(
+            "shelf",
+            Batch {
+                quantity: 3,
+                expiry: 5,
+            },
+        );
+        inventory.add(
+            "shelf",
+            Batch {
+                quantity: 3,
+                expiry: 6,
+            },
+        );
+        inventory.track("store");
+
+        // an unknown destination is found before the source is touched
+        let before: Inventory = inventory.clone();
+        assert_eq!(inventory.transfer("shelf", "bin", 2), None);
+        assert_eq!(inventory.transfer("shelf", "store", 7), None);
+        assert_eq!(inventory, before);
+
+        assert_eq!(inventory.transfer("shelf", "store", 4), Some(()));
+        assert_eq!(inventory.stock("shelf"), Some(2));
+        assert_eq!(inventory.stock("store"), Some(4));
+        assert_eq!(inventory.earliest_expiry("store"), Some(5));
+        assert_eq!(inventory.earliest_expiry("shelf"), Some(6));
+    }
+
+    #[test]
+    fn run_fallible_iter_until_error() {
+        use crate::fallible_iter::{all_or_nothing, UntilError};
+        use std::cell::Cell;
+
+        fn values<'a>(
+            items: &'a [Result<u8, &'static str>],
+        ) -> impl Iterator<Item = Result<u8, &'static str>> + 'a {
+            items.iter().copied()
+        }
+        assert_eq!(
+            values(&[Err("first"), Ok(2)]).until_error(),
+            (vec![], Some((0, "first")))
+        );
+        assert_eq!(
+            values(&[Ok(1), Ok(2), Err("last")]).until_error(),
+            (vec![1, 2], Some((2, "last")))
+        );
+        assert_eq!(values(&[Ok(1), Ok(2)]).until_error(), (vec![1, 2], None));
+        assert_eq!(values(&[]).until_error(), (vec![], None));
+
+        assert_eq!(all_or_nothing(values(&[Ok(1), Ok(2)])), Ok(vec![1, 2]));
+        assert_eq!(
+            all_or_nothing(values(&[Ok(1), Err("x"), Err("y")])),
+            Err((1, "x"))
+        );
+        assert_eq!(all_or_nothing(Vec::<Result<u8, ()>>::new()), Ok(vec![]));
+
+        // the probe runs for the items up to the error, not after
+        let calls: Cell<u32> = Cell::new(0);
+        let probe = |i: u32| {
+            calls.set(calls.get() + 1);
+            if i == 3 {
+                Err(format!("{} failed", i))
+            } else {
+                Ok(i * 10)
+            }
+        };
+        let mut items = (0..10).map(probe).try_take();
+        assert_eq!(items.by_ref().collect::<Vec<u32>>(), vec![0, 10, 20]);
+        assert_eq!(items.error(), Some(&(3, String::from("3 failed"))));
+        // once stopped it stays stopped
+        assert_eq!(items.next(), None);
+        assert_eq!(calls.get(), 4);
+    }
+
+    #[test]
+    fn run_fallible_iter_process_lines() {
+        use crate::fallible_iter::{process_lines, LineError};
+        use std::cell::Cell;
+        use std::num::ParseIntError;
+
+        let double = |line: &str| line.trim().parse::<i32>().map(|n| (n * 2).to_string());
+        assert_eq!(
+            process_lines("1\n 2\n3", double),
+            Ok(vec!["2".into(), "4".into(), "6".into()])
+        );
+        assert_eq!(process_lines("", double), Ok(vec![]));
+
+        let calls: Cell<usize> = Cell::new(0);
+        let counted = |line: &str| {
+            calls.set(calls.get() + 1);
+            double(line)
+        };
+        let error: LineError<ParseIntError> = process_lines("1\n2\nthree\n4", counted).unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(calls.get(), 3);
+        assert_eq!(error.to_string(), "line 3: invalid digit found in string");
+        assert!(std::error::Error::source(&error)
+            .unwrap()
+            .is::<ParseIntError>());
+        assert_eq!(process_lines("x", double).unwrap_err().line, 1);
+    }
+
+    #[test]
+    fn run_result_option_convert_result_to_option() {
+        assert_eq!(crate::result_option_convert::result_to_option(), Some(5));
+        assert_eq!(crate::result_option_convert::error_to_option(), Some("e"));
+    }
+
+    #[test]
+    fn run_result_option_convert_option_to_result() {
+        assert_eq!(crate::result_option_convert::option_to_result(), Ok(5));
+        // and back: the value survives the round trip, the error text does not
+        assert_eq!(Some(5).ok_or("missing").ok(), Some(5));
+        assert_eq!(None::<i32>.ok_or("missing").ok(), None);
+    }
+
+    #[test]
+    fn run_result_option_convert_transpose() {
+        crate::result_option_convert::transpose_demo();
+    }
+
+    #[test]
+    fn run_panic_with_custom_panic_hook() {
+        assert!(crate::panic::with_custom_panic_hook());
+    }
+
+    #[test]
+    #[should_panic(expected = "x must be positive, got -1")]
+    fn run_assertions_assert_with_message() {
+        assert_eq!(crate::assertions::assert_with_message(5), 5);
+        crate::assertions::assert_with_message(-1);
+    }
+
+    #[test]
+    fn run_assertions_assert_eq_message() {
+        let message: String = crate::assertions::assert_eq_message();
+        assert!(message.contains("sums differ"), "{}", message);
+        assert!(message.contains("left: 3"), "{}", message);
+        assert!(message.contains("right: 4"), "{}", message);
+    }
+
+    #[test]
+    fn run_assertions_debug_assert() {
+        use crate::assertions::debug_assert_sorted;
+        assert_eq!(debug_assert_sorted(&[1, 3, 5], 3), Some(1));
+        assert_eq!(debug_assert_sorted(&[1, 3, 5], 4), None);
+
+        let unsorted = std::panic::catch_unwind(|| debug_assert_sorted(&[5, 1, 3], 3));
+        // the check only exists when debug assertions are on
+        assert_eq!(unsorted.is_err(), cfg!(debug_assertions));
+    }
+
+    #[test]
+    fn run_closeable_close() {
+        use crate::closeable::{CloseError, Resource};
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        let sink: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(vec![]));
+        let mut resource: Resource = Resource::new("db", Rc::clone(&sink));
+        resource.write("row");
+        assert_eq!(resource.close(), Ok(()));
+        assert_eq!(*sink.borrow(), vec!["opened db", "db <- row", "closed db"]);
+
+        let broken: Resource = Resource::new("broken-db", Rc::clone(&sink));
+        let err: CloseError = broken.close().unwrap_err();
+        assert_eq!(err.to_string(), "failed to close broken-db");
+        assert!(!sink.borrow().iter().any(|m| m.contains("leaked")));
+    }
+
+    #[test]
+    fn run_closeable_leak_on_drop() {
+        use crate::closeable::Resource;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        let sink: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(vec![]));
+        {
+            let _resource: Resource = Resource::new("file", Rc::clone(&sink));
+        }
+        assert_eq!(
+            *sink.borrow(),
+            vec!["opened file", "file leaked without close"]
+        );
+    }
+
+    #[test]
+    fn run_closeable_with_resource() {
+        use crate::closeable::{with_resource, CloseError};
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        let sink: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(vec![]));
+        let written = with_resource("log", Rc::clone(&sink), |r| {
+            for line in ["a", "b", "c"] {
+                if line == "b" {
+                    return r.name().len();
+                }
+                r.write(line);
+            }
+            0
+        });
+        assert_eq!(written, Ok(3));
+        assert_eq!(*sink.borrow(), vec!["opened log", "log <- a", "closed log"]);
+
+        let failed = with_resource("broken-log", Rc::clone(&sink), |r| r.write("x"));
+        assert_eq!(
+            failed,
+            Err(CloseError {
+                name: String::from("broken-log")
+            })
+        );
+        assert!(!sink.borrow().iter().any(|m| m.contains("leaked")));
+    }
 }
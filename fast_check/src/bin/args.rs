@@ -0,0 +1,65 @@
+// run
+// cargo run --bin args -- build -v --release target
+
+/// Command-line arguments split into flags and positional arguments, each in the order given.
+#[derive(Debug, PartialEq)]
+struct ParsedArgs {
+    flags: Vec<String>,
+    positional: Vec<String>,
+}
+
+/// An argument starting with `-` is a flag, except a lone `-` which conventionally means stdin.
+fn parse_args(input: Vec<String>) -> ParsedArgs {
+    let (flags, positional): (Vec<String>, Vec<String>) = input
+        .into_iter()
+        .partition(|arg| arg.starts_with('-') && arg.len() > 1);
+    ParsedArgs { flags, positional }
+}
+
+fn main() {
+    // the first argument is the path of the program itself
+    let args: Vec<String> = std::env::args().skip(1).collect::<Vec<_>>();
+    println!("args: {:?}", args);
+
+    let parsed: ParsedArgs = parse_args(args);
+    println!("flags: {:?}", parsed.flags);
+    println!("positional: {:?}", parsed.positional);
+}
+
+#[cfg(test)]
+mod testing {
+    use super::{parse_args, ParsedArgs};
+
+    fn args(input: &[&str]) -> Vec<String> {
+        input.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn run_parse_args() {
+        assert_eq!(
+            parse_args(args(&["build", "-v", "--release", "target"])),
+            ParsedArgs {
+                flags: args(&["-v", "--release"]),
+                positional: args(&["build", "target"]),
+            }
+        );
+    }
+
+    #[test]
+    fn run_parse_args_empty_and_stdin() {
+        assert_eq!(
+            parse_args(vec![]),
+            ParsedArgs {
+                flags: vec![],
+                positional: vec![],
+            }
+        );
+        assert_eq!(
+            parse_args(args(&["-", "-n"])),
+            ParsedArgs {
+                flags: args(&["-n"]),
+                positional: args(&["-"]),
+            }
+        );
+    }
+}
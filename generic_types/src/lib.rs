@@ -264,6 +264,208 @@ pub mod advance {
     }
 }
 
+pub mod type_name {
+    //! `std::any::type_name::<T>()` gives the name of the type a generic parameter was
+    //! instantiated with, and taking a reference lets the compiler infer `T` from a value.
+
+    /// Returns the name of the type of the value, e.g. `"i32"` or `"alloc::string::String"`.
+    ///
+    /// This is for debugging only, not for dispatch: the exact string is not specified, may
+    /// differ between compiler versions, and two types can even share a name. To branch on a
+    /// type, use a trait or `std::any::TypeId`.
+    pub fn type_name_of<T>(_: &T) -> &'static str {
+        std::any::type_name::<T>()
+    }
+}
+
+pub mod bst {
+    //! A recursive enum needs indirection: `Node(Tree<T>, T, Tree<T>)` would contain itself and
+    //! have no finite size, `Box<Tree<T>>` is a pointer of known size. Every value in the left
+    //! subtree of a node is smaller than the node's value, every value in the right one larger,
+    //! so a search follows one path from the root and an in-order walk yields the values sorted.
+    //!
+    //! The cost of an operation is the height of the tree. Inserting in random order keeps it
+    //! around `2 log2 n` on average, but inserting sorted values builds a linked list of height
+    //! `n`, which is what `from_sorted` avoids by picking the middle value as root.
+    //!
+    //! `insert`, `contains`, `height` and the iterator loop with an explicit cursor or stack, so a
+    //! degenerate tree does not use stack space in proportion to its height; `in_order` is the
+    //! recursive version to compare with. Dropping a tree is still recursive, it is the compiler
+    //! generated drop of the boxes.
+
+    use std::cmp::Ordering;
+
+    #[derive(Debug)]
+    pub enum Tree<T: Ord> {
+        Leaf,
+        Node(Box<Tree<T>>, T, Box<Tree<T>>),
+    }
+
+    impl<T: Ord> Tree<T> {
+        pub fn new() -> Self {
+            Tree::Leaf
+        }
+
+        fn node(value: T) -> Self {
+            Tree::Node(Box::new(Tree::Leaf), value, Box::new(Tree::Leaf))
+        }
+
+        /// Builds a tree of height `ceil(log2(n + 1))`: the middle value becomes the root and
+        /// each half becomes a subtree, built the same way.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `sorted` is not strictly increasing.
+        pub fn from_sorted(sorted: Vec<T>) -> Tree<T> {
+            assert!(
+                sorted.windows(2).all(|pair| pair[0] < pair[1]),
+                "values must be sorted and unique"
+            );
+            Self::build(sorted)
+        }
+
+        fn build(mut values: Vec<T>) -> Tree<T> {
+            if values.is_empty() {
+                return Tree::Leaf;
+            }
+            let right: Vec<T> = values.split_off(values.len() / 2 + 1);
+            let middle: T = values.pop().unwrap();
+            Tree::Node(
+                Box::new(Self::build(values)),
+                middle,
+                Box::new(Self::build(right)),
+            )
+        }
+
+        /// Returns `false`, leaving the tree unchanged, if `value` is already there.
+        pub fn insert(&mut self, value: T) -> bool {
+            let mut cursor: &mut Tree<T> = self;
+            loop {
+                match cursor {
+                    Tree::Leaf => {
+                        *cursor = Tree::node(value);
+                        return true;
+                    }
+                    Tree::Node(left, v, right) => match value.cmp(v) {
+                        Ordering::Less => cursor = left,
+                        Ordering::Greater => cursor = right,
+                        Ordering::Equal => return false,
+                    },
+                }
+            }
+        }
+
+        pub fn contains(&self, value: &T) -> bool {
+            let mut cursor: &Tree<T> = self;
+            while let Tree::Node(left, v, right) = cursor {
+                match value.cmp(v) {
+                    Ordering::Less => cursor = left,
+                    Ordering::Greater => cursor = right,
+                    Ordering::Equal => return true,
+                }
+            }
+            false
+        }
+
+        pub fn len(&self) -> usize {
+            self.iter().count()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            matches!(self, Tree::Leaf)
+        }
+
+        /// The number of nodes on the longest path from the root, 0 for an empty tree.
+        pub fn height(&self) -> usize {
+            let mut height: usize = 0;
+            let mut stack: Vec<(&Tree<T>, usize)> = vec![(self, 0)];
+            while let Some((tree, depth)) = stack.pop() {
+                if let Tree::Node(left, _, right) = tree {
+                    height = height.max(depth + 1);
+                    stack.push((left, depth + 1));
+                    stack.push((right, depth + 1));
+                }
+            }
+            height
+        }
+
+        pub fn min(&self) -> Option<&T> {
+            let mut cursor: &Tree<T> = self;
+            let mut min: Option<&T> = None;
+            while let Tree::Node(left, v, _) = cursor {
+                min = Some(v);
+                cursor = left;
+            }
+            min
+        }
+
+        pub fn max(&self) -> Option<&T> {
+            let mut cursor: &Tree<T> = self;
+            let mut max: Option<&T> = None;
+            while let Tree::Node(_, v, right) = cursor {
+                max = Some(v);
+                cursor = right;
+            }
+            max
+        }
+
+        pub fn in_order(&self) -> Vec<&T> {
+            fn walk<'a, T: Ord>(tree: &'a Tree<T>, out: &mut Vec<&'a T>) {
+                if let Tree::Node(left, v, right) = tree {
+                    walk(left, out);
+                    out.push(v);
+                    walk(right, out);
+                }
+            }
+            let mut out: Vec<&T> = vec![];
+            walk(self, &mut out);
+            out
+        }
+
+        pub fn iter(&self) -> InOrderIter<'_, T> {
+            let mut iter: InOrderIter<T> = InOrderIter { stack: vec![] };
+            iter.push_left_spine(self);
+            iter
+        }
+    }
+
+    impl<T: Ord> Default for Tree<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    /// The stack holds the nodes whose value is still to come, the top one being the next. Taking
+    /// a node pushes the left spine of its right subtree, the values between it and the node
+    /// below it on the stack.
+    pub struct InOrderIter<'a, T: Ord> {
+        stack: Vec<&'a Tree<T>>,
+    }
+
+    impl<'a, T: Ord> InOrderIter<'a, T> {
+        fn push_left_spine(&mut self, mut tree: &'a Tree<T>) {
+            while let Tree::Node(left, _, _) = tree {
+                self.stack.push(tree);
+                tree = left;
+            }
+        }
+    }
+
+    impl<'a, T: Ord> Iterator for InOrderIter<'a, T> {
+        type Item = &'a T;
+
+        fn next(&mut self) -> Option<&'a T> {
+            match self.stack.pop()? {
+                Tree::Node(_, v, right) => {
+                    self.push_left_spine(right);
+                    Some(v)
+                }
+                Tree::Leaf => unreachable!("only nodes are pushed"),
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod testing {
 
@@ -298,4 +500,97 @@ mod testing {
         let p3: Point<i32, char> = p1.mix_up(p2);
         println!("p3 = {:?}", p3);
     }
+
+    #[test]
+    fn run_type_name_of() {
+        use crate::type_name::type_name_of;
+        assert!(type_name_of(&1_i32).contains("i32"));
+        assert!(type_name_of(&String::from("rust")).contains("String"));
+        assert!(type_name_of(&vec![1_u8]).contains("u8"));
+    }
+
+    fn shuffled(n: u32) -> Vec<u32> {
+        // a small linear congruential generator drives a Fisher-Yates shuffle
+        let mut values: Vec<u32> = (0..n).collect();
+        let mut state: u64 = 42;
+        for i in (1..values.len()).rev() {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1);
+            values.swap(i, (state >> 33) as usize % (i + 1));
+        }
+        values
+    }
+
+    #[test]
+    fn run_bst_random_inserts() {
+        use crate::bst::Tree;
+        let mut tree: Tree<u32> = Tree::new();
+        for value in shuffled(500) {
+            assert!(tree.insert(value));
+        }
+        assert_eq!(tree.len(), 500);
+        assert!(tree.in_order().into_iter().copied().eq(0..500));
+        assert!(tree.iter().eq(tree.in_order()));
+        assert_eq!((tree.min(), tree.max()), (Some(&0), Some(&499)));
+        assert!(tree.contains(&250) && !tree.contains(&500));
+        // far from the 500 of a degenerate tree
+        assert!(tree.height() < 40);
+    }
+
+    #[test]
+    fn run_bst_duplicates() {
+        use crate::bst::Tree;
+        let mut tree: Tree<&str> = Tree::new();
+        for word in ["rust", "go", "zig", "c"] {
+            assert!(tree.insert(word));
+        }
+        assert!(!tree.insert("go"));
+        assert!(!tree.insert("rust"));
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree.in_order(), vec![&"c", &"go", &"rust", &"zig"]);
+    }
+
+    #[test]
+    fn run_bst_height() {
+        use crate::bst::Tree;
+        let balanced: Tree<u32> = Tree::from_sorted((0..1000).collect());
+        let mut degenerate: Tree<u32> = Tree::new();
+        (0..1000).for_each(|value| {
+            degenerate.insert(value);
+        });
+        assert_eq!(balanced.height(), 10);
+        assert_eq!(degenerate.height(), 1000);
+        assert!(balanced.iter().eq(degenerate.iter()));
+        assert!(balanced.iter().eq(balanced.in_order()));
+        assert_eq!(degenerate.len(), 1000);
+
+        for n in [1, 2, 3, 7, 8] {
+            let tree: Tree<u32> = Tree::from_sorted((0..n).collect());
+            assert_eq!(
+                tree.height(),
+                (n + 1).next_power_of_two().trailing_zeros() as usize
+            );
+            assert!(tree.iter().copied().eq(0..n));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "values must be sorted and unique")]
+    fn run_bst_from_unsorted() {
+        crate::bst::Tree::from_sorted(vec![1, 3, 2]);
+    }
+
+    #[test]
+    fn run_bst_empty() {
+        use crate::bst::Tree;
+        let tree: Tree<i32> = Tree::from_sorted(vec![]);
+        assert!(tree.is_empty());
+        assert_eq!(tree.len(), 0);
+        assert_eq!(tree.height(), 0);
+        assert_eq!((tree.min(), tree.max()), (None, None));
+        assert!(!tree.contains(&0));
+        assert!(tree.in_order().is_empty());
+        assert_eq!(tree.iter().next(), None);
+    }
 }
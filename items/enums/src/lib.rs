@@ -14,8 +14,9 @@ mod defining_enum {
         V6(String),
     }
 
+    #[derive(Debug, PartialEq)]
     #[allow(dead_code)]
-    enum Message {
+    pub enum Message {
         Quit,
         Move { x: i32, y: i32 },
         Write(String),
@@ -66,6 +67,25 @@ mod option_enum {
     //!
     //! let absent_number: Option<i32> = None;
     //! ```
+
+    /// `get_or_insert_with` fills a `None` in place with the closure's value, leaves a `Some`
+    /// as it is, and returns `&mut T` to the value inside the `Option` either way. The
+    /// `Option` itself is changed, there is no new one to assign back, and the closure only
+    /// runs when the value is missing, which makes it a lazy initializer.
+    #[allow(dead_code, clippy::unnecessary_lazy_evaluations)]
+    pub fn option_get_or_insert() {
+        let mut opt: Option<i32> = None;
+        let v: &mut i32 = opt.get_or_insert_with(|| 5);
+        assert_eq!(*v, 5);
+        // the reference points into `opt`
+        *v += 1;
+        assert_eq!(opt, Some(6));
+
+        // already `Some`: the closure does not run
+        let v: &mut i32 = opt.get_or_insert_with(|| unreachable!());
+        assert_eq!(*v, 6);
+        assert_eq!(opt, Some(6));
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -248,6 +268,669 @@ mod custom_discriminant_values {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// Memory Layout
+////////////////////////////////////////////////////////////////////////////////
+pub mod memory_layout {
+    //! An enum value is stored as a tag (the discriminant) followed by enough space for the largest
+    //! variant, and the whole thing is padded to the alignment of its most aligned field.
+    //!
+    //! ```text
+    //! enum Shape {                      tag              payload
+    //!     Quit,                     |<-- 4 -->||<----------- 12 ----------->|
+    //!     Move { x: i32, y: i32 },  |    0    ||          unused            |
+    //!     Color(i32, i32, i32),     |    1    ||    x    |    y    | unused |
+    //! }                             |    2    ||    r    |    g    |    b   |
+    //! ```
+    //!
+    //! When a variant holds a type with invalid bit patterns (a "niche"), such as a non-null `Box`
+    //! or the capacity of a `String` which can never exceed `isize::MAX`, the compiler stores the
+    //! tag inside that niche instead of adding a separate field. This is why `Option<Box<i32>>` is
+    //! the same size as `Box<i32>`: `None` is represented by the null pointer.
+
+    use crate::defining_enum::Message;
+
+    #[allow(dead_code)]
+    enum Shape {
+        Quit,
+        Move { x: i32, y: i32 },
+        Color(i32, i32, i32),
+    }
+
+    pub fn enum_layout() {
+        // niche: the null pointer encodes `None`, no tag needed
+        assert_eq!(std::mem::size_of::<Box<i32>>(), 8);
+        assert_eq!(std::mem::size_of::<Option<Box<i32>>>(), 8);
+
+        // tag + largest variant: 4 bytes tag + 12 bytes `Color(i32, i32, i32)`
+        assert_eq!(std::mem::size_of::<Shape>(), 16);
+
+        // `Write(String)` is the largest variant. Today rustc encodes the other variants in the
+        // niche of the `String` capacity, so `Message` is exactly as large as a `String`, but that
+        // layout is unspecified: a separate tag would add at most one word
+        let string: usize = std::mem::size_of::<String>();
+        assert!(std::mem::size_of::<Message>() >= string);
+        assert!(std::mem::size_of::<Message>() <= string + std::mem::size_of::<usize>());
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Parsing Commands
+////////////////////////////////////////////////////////////////////////////////
+pub mod parsing_commands {
+    //! Turns lines of text into `Message` values, so the variants can be driven by real input
+    //! instead of being constructed by hand.
+    //!
+    //! ```text
+    //! quit
+    //! move <x> <y>
+    //! write <text...>
+    //! color <r> <g> <b>
+    //! ```
+
+    use crate::defining_enum::Message;
+
+    #[derive(Debug, PartialEq)]
+    pub enum CommandError {
+        UnknownCommand(String),
+        WrongArity { expected: usize, got: usize },
+        InvalidNumber { arg_index: usize },
+    }
+
+    /// The state of the canvas after every message of a script has been applied.
+    #[derive(Debug, Default, PartialEq)]
+    pub struct CanvasState {
+        pub position: (i32, i32),
+        pub color: (i32, i32, i32),
+        pub texts: Vec<String>,
+        pub quit: bool,
+    }
+
+    fn parse_numbers<const N: usize>(args: &[&str]) -> Result<[i32; N], CommandError> {
+        if args.len() != N {
+            return Err(CommandError::WrongArity {
+                expected: N,
+                got: args.len(),
+            });
+        }
+        let mut numbers: [i32; N] = [0; N];
+        for (arg_index, arg) in args.iter().enumerate() {
+            numbers[arg_index] = arg
+                .parse()
+                .map_err(|_| CommandError::InvalidNumber { arg_index })?;
+        }
+        Ok(numbers)
+    }
+
+    pub fn parse_command(line: &str) -> Result<Message, CommandError> {
+        let line: &str = line.trim();
+        let (word, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let args: Vec<&str> = rest.split_whitespace().collect();
+        match word {
+            "quit" => match args.len() {
+                0 => Ok(Message::Quit),
+                got => Err(CommandError::WrongArity { expected: 0, got }),
+            },
+            "move" => {
+                let [x, y] = parse_numbers(&args)?;
+                Ok(Message::Move { x, y })
+            }
+            // the payload is everything after the command word, so internal spaces are preserved
+            "write" => match rest.trim_start() {
+                "" => Err(CommandError::WrongArity {
+                    expected: 1,
+                    got: 0,
+                }),
+                text => Ok(Message::Write(text.to_string())),
+            },
+            "color" => {
+                let [r, g, b] = parse_numbers(&args)?;
+                Ok(Message::Color(r, g, b))
+            }
+            other => Err(CommandError::UnknownCommand(other.to_string())),
+        }
+    }
+
+    /// Parses one command per line, blank lines are skipped. On failure, the error is paired with
+    /// the 1-based number of the offending line.
+    pub fn parse_script(text: &str) -> Result<Vec<Message>, (usize, CommandError)> {
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| parse_command(line).map_err(|e| (i + 1, e)))
+            .collect()
+    }
+
+    /// Folds the messages into a `CanvasState`: moves are relative to the current position, the
+    /// last color wins, and nothing after a `Quit` is applied.
+    pub fn execute(messages: &[Message]) -> CanvasState {
+        let mut state: CanvasState = CanvasState::default();
+        for message in messages {
+            match message {
+                Message::Quit => {
+                    state.quit = true;
+                    break;
+                }
+                Message::Move { x, y } => {
+                    state.position.0 += x;
+                    state.position.1 += y;
+                }
+                Message::Write(text) => state.texts.push(text.clone()),
+                Message::Color(r, g, b) => state.color = (*r, *g, *b),
+            }
+        }
+        state
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Non Exhaustive
+////////////////////////////////////////////////////////////////////////////////
+pub mod non_exhaustive {
+    //! The `#[non_exhaustive]` attribute marks an enum as one that may gain more variants in the
+    //! future. Inside the defining crate it behaves like any other enum, but downstream crates must
+    //! include a catch-all arm when matching on it, so the library can add a variant in a minor
+    //! release without breaking every `match` written by its users.
+    //!
+    //! A downstream `match` that names every current variant still needs the `_` arm:
+    //! ```
+    //! use enums::non_exhaustive::status::Status;
+    //!
+    //! let label: &str = match Status::Active {
+    //!     Status::Active => "active",
+    //!     Status::Inactive => "inactive",
+    //!     _ => "unknown",
+    //! };
+    //! assert_eq!(label, "active");
+    //! ```
+    //!
+    //! Without it, the compiler rejects the match as non-exhaustive:
+    //! ```compile_fail,E0004
+    //! use enums::non_exhaustive::status::Status;
+    //!
+    //! let label: &str = match Status::Active {
+    //!     Status::Active => "active",
+    //!     Status::Inactive => "inactive",
+    //! };
+    //! ```
+
+    pub mod status {
+        #[derive(Debug)]
+        #[non_exhaustive]
+        pub enum Status {
+            Active,
+            Inactive,
+        }
+    }
+
+    use status::Status;
+
+    pub fn is_active(status: &Status) -> bool {
+        match status {
+            Status::Active => true,
+            // covers `Inactive` and any variant added in the future
+            _ => false,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Matches Macro
+////////////////////////////////////////////////////////////////////////////////
+pub mod matches_macro {
+    //! `matches!(expression, pattern)` returns `true` if the expression matches the pattern. It is
+    //! a concise way to test which variant a value is without destructuring its fields, `..` and
+    //! `_` stand in for the payload we don't care about.
+    //!
+    //! ```
+    //! let c: char = 'r';
+    //! assert!(matches!(c, 'a'..='z'));
+    //! assert!(matches!(Some(3), Some(x) if x > 2));
+    //! ```
+
+    use crate::defining_enum::Message;
+
+    /// Counts the messages whose variant is named `want`, an unknown name counts nothing.
+    pub fn count_variant(messages: &[Message], want: &str) -> usize {
+        messages
+            .iter()
+            .filter(|message| match want {
+                "Quit" => matches!(message, Message::Quit),
+                "Move" => matches!(message, Message::Move { .. }),
+                "Write" => matches!(message, Message::Write(_)),
+                "Color" => matches!(message, Message::Color(..)),
+                _ => false,
+            })
+            .count()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Expression Tree
+////////////////////////////////////////////////////////////////////////////////
+pub mod expr {
+    //! An arithmetic expression is a tree, and a recursive enum describes it directly: an `Add`
+    //! holds two more expressions. A variant can't hold an `Expr` by value, the type would have
+    //! an infinite size, so the children are boxed and `Expr` only stores two pointers.
+    //!
+    //! `parse` is a recursive-descent parser, one function per precedence level, each calling the
+    //! next tighter one:
+    //!
+    //! ```text
+    //! expr    := term (('+' | '-') term)*
+    //! term    := unary (('*' | '/') unary)*
+    //! unary   := '-' unary | primary
+    //! primary := number | name | '(' expr ')'
+    //! ```
+    //!
+    //! Names become `Var`, which is not part of the arithmetic itself but lets `simplify` show
+    //! what it does to the parts it can't fold, `eval` rejects them.
+    //!
+    //! ```
+    //! use enums::expr::parse;
+    //!
+    //! let e = parse("(2*3)+x-0").unwrap();
+    //! assert_eq!(e.to_string(), "2 * 3 + x - 0");
+    //! assert_eq!(e.simplify().to_string(), "6 + x");
+    //! ```
+
+    use std::fmt;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Expr {
+        Num(f64),
+        Var(String),
+        Neg(Box<Expr>),
+        Add(Box<Expr>, Box<Expr>),
+        Sub(Box<Expr>, Box<Expr>),
+        Mul(Box<Expr>, Box<Expr>),
+        Div(Box<Expr>, Box<Expr>),
+    }
+
+    /// Positions are byte offsets into the input.
+    #[derive(Debug, PartialEq)]
+    pub enum ParseError {
+        UnexpectedChar { position: usize, found: char },
+        UnexpectedEnd,
+        InvalidNumber { position: usize },
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum EvalError {
+        DivisionByZero,
+        UnboundVariable(String),
+    }
+
+    struct Parser<'a> {
+        input: &'a str,
+        position: usize,
+    }
+
+    impl<'a> Parser<'a> {
+        /// Skips whitespace and returns the next char without consuming it.
+        fn peek(&mut self) -> Option<char> {
+            let rest: &str = &self.input[self.position..];
+            let trimmed: &str = rest.trim_start();
+            self.position += rest.len() - trimmed.len();
+            trimmed.chars().next()
+        }
+
+        fn bump(&mut self, c: char) {
+            self.position += c.len_utf8();
+        }
+
+        fn unexpected(&mut self) -> ParseError {
+            match self.peek() {
+                Some(found) => ParseError::UnexpectedChar {
+                    position: self.position,
+                    found,
+                },
+                None => ParseError::UnexpectedEnd,
+            }
+        }
+
+        fn expr(&mut self) -> Result<Expr, ParseError> {
+            let mut left: Expr = self.term()?;
+            while let Some(op @ ('+' | '-')) = self.peek() {
+                self.bump(op);
+                let right: Box<Expr> = Box::new(self.term()?);
+                left = match op {
+                    '+' => Expr::Add(Box::new(left), right),
+                    _ => Expr::Sub(Box::new(left), right),
+                };
+            }
+            Ok(left)
+        }
+
+        fn term(&mut self) -> Result<Expr, ParseError> {
+            let mut left: Expr = self.unary()?;
+            while let Some(op @ ('*' | '/')) = self.peek() {
+                self.bump(op);
+                let right: Box<Expr> = Box::new(self.unary()?);
+                left = match op {
+                    '*' => Expr::Mul(Box::new(left), right),
+                    _ => Expr::Div(Box::new(left), right),
+                };
+            }
+            Ok(left)
+        }
+
+        fn unary(&mut self) -> Result<Expr, ParseError> {
+            if self.peek() == Some('-') {
+                self.bump('-');
+                return Ok(Expr::Neg(Box::new(self.unary()?)));
+            }
+            self.primary()
+        }
+
+        fn primary(&mut self) -> Result<Expr, ParseError> {
+            match self.peek() {
+                Some('(') => {
+                    self.bump('(');
+                    let inner: Expr = self.expr()?;
+                    match self.peek() {
+                        Some(')') => {
+                            self.bump(')');
+                            Ok(inner)
+                        }
+                        _ => Err(self.unexpected()),
+                    }
+                }
+                Some(c) if c.is_ascii_digit() || c == '.' => {
+                    let start: usize = self.position;
+                    let len: usize = self.input[start..]
+                        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                        .unwrap_or(self.input.len() - start);
+                    self.position += len;
+                    self.input[start..self.position]
+                        .parse()
+                        .map(Expr::Num)
+                        .map_err(|_| ParseError::InvalidNumber { position: start })
+                }
+                Some(c) if c.is_ascii_alphabetic() => {
+                    let start: usize = self.position;
+                    let len: usize = self.input[start..]
+                        .find(|c: char| !c.is_ascii_alphanumeric())
+                        .unwrap_or(self.input.len() - start);
+                    self.position += len;
+                    Ok(Expr::Var(self.input[start..self.position].to_string()))
+                }
+                _ => Err(self.unexpected()),
+            }
+        }
+    }
+
+    pub fn parse(input: &str) -> Result<Expr, ParseError> {
+        let mut parser: Parser = Parser { input, position: 0 };
+        let expr: Expr = parser.expr()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(_) => Err(parser.unexpected()),
+        }
+    }
+
+    fn fold(op: fn(f64, f64) -> f64, left: &Expr, right: &Expr) -> Result<f64, EvalError> {
+        Ok(op(left.eval()?, right.eval()?))
+    }
+
+    impl Expr {
+        pub fn eval(&self) -> Result<f64, EvalError> {
+            match self {
+                Expr::Num(n) => Ok(*n),
+                Expr::Var(name) => Err(EvalError::UnboundVariable(name.clone())),
+                Expr::Neg(e) => Ok(-e.eval()?),
+                Expr::Add(l, r) => fold(|a, b| a + b, l, r),
+                Expr::Sub(l, r) => fold(|a, b| a - b, l, r),
+                Expr::Mul(l, r) => fold(|a, b| a * b, l, r),
+                Expr::Div(l, r) => match r.eval()? {
+                    0.0 => Err(EvalError::DivisionByZero),
+                    d => Ok(l.eval()? / d),
+                },
+            }
+        }
+
+        /// Folds every subtree without a `Var` into a `Num`, then drops the neutral operands
+        /// (`+ 0`, `- 0`, `* 1`, `/ 1`) and turns `x * 0` into `0`. A division by a literal zero
+        /// is left as is, so that `eval` still reports it, and `0 * (1 / 0)` is not turned into
+        /// `0` either, since that would drop the division.
+        pub fn simplify(self) -> Expr {
+            use Expr::*;
+            match self {
+                Neg(e) => match e.simplify() {
+                    Num(n) => Num(-n),
+                    Neg(inner) => *inner,
+                    e => Neg(Box::new(e)),
+                },
+                Add(l, r) => match (l.simplify(), r.simplify()) {
+                    (Num(a), Num(b)) => Num(a + b),
+                    (Num(0.0), e) | (e, Num(0.0)) => e,
+                    (l, r) => Add(Box::new(l), Box::new(r)),
+                },
+                Sub(l, r) => match (l.simplify(), r.simplify()) {
+                    (Num(a), Num(b)) => Num(a - b),
+                    (e, Num(0.0)) => e,
+                    (Num(0.0), e) => Neg(Box::new(e)),
+                    (l, r) => Sub(Box::new(l), Box::new(r)),
+                },
+                Mul(l, r) => match (l.simplify(), r.simplify()) {
+                    (Num(a), Num(b)) => Num(a * b),
+                    (Num(0.0), e) | (e, Num(0.0)) if !e.divides_by_zero() => Num(0.0),
+                    (Num(1.0), e) | (e, Num(1.0)) => e,
+                    (l, r) => Mul(Box::new(l), Box::new(r)),
+                },
+                Div(l, r) => match (l.simplify(), r.simplify()) {
+                    (Num(a), Num(b)) if b != 0.0 => Num(a / b),
+                    (e, Num(1.0)) => e,
+                    (l, r) => Div(Box::new(l), Box::new(r)),
+                },
+                leaf => leaf,
+            }
+        }
+
+        /// Whether the tree contains a division by a literal zero. Called on simplified operands,
+        /// where any divisor without a `Var` has been folded to a `Num`.
+        fn divides_by_zero(&self) -> bool {
+            match self {
+                Expr::Num(_) | Expr::Var(_) => false,
+                Expr::Neg(e) => e.divides_by_zero(),
+                Expr::Div(_, r) if **r == Expr::Num(0.0) => true,
+                Expr::Add(l, r) | Expr::Sub(l, r) | Expr::Mul(l, r) | Expr::Div(l, r) => {
+                    l.divides_by_zero() || r.divides_by_zero()
+                }
+            }
+        }
+
+        /// Binding strength, used by `Display` to decide where parentheses are needed.
+        fn precedence(&self) -> u8 {
+            match self {
+                Expr::Add(..) | Expr::Sub(..) => 1,
+                Expr::Mul(..) | Expr::Div(..) => 2,
+                Expr::Neg(_) => 3,
+                Expr::Num(n) if *n < 0.0 => 3,
+                Expr::Num(_) | Expr::Var(_) => 4,
+            }
+        }
+    }
+
+    /// Prints the fewest parentheses that still parse back to the same tree. Operators are left
+    /// associative, so a left operand of the same precedence needs none, while a right one does:
+    /// `a - (b - c)` and `a + (b + c)` keep them.
+    impl fmt::Display for Expr {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let (symbol, left, right) = match self {
+                Expr::Num(n) => return write!(f, "{}", n),
+                Expr::Var(name) => return write!(f, "{}", name),
+                Expr::Neg(e) if e.precedence() < 3 => return write!(f, "-({})", e),
+                Expr::Neg(e) => return write!(f, "-{}", e),
+                Expr::Add(l, r) => ("+", l, r),
+                Expr::Sub(l, r) => ("-", l, r),
+                Expr::Mul(l, r) => ("*", l, r),
+                Expr::Div(l, r) => ("/", l, r),
+            };
+            if left.precedence() < self.precedence() {
+                write!(f, "({})", left)?;
+            } else {
+                write!(f, "{}", left)?;
+            }
+            if right.precedence() <= self.precedence() {
+                write!(f, " {} ({})", symbol, right)
+            } else {
+                write!(f, " {} {}", symbol, right)
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Flag Set
+////////////////////////////////////////////////////////////////////////////////
+pub mod flag_set {
+    //! With `#[repr(u32)]` and explicit discriminants that are powers of two, each variant is one
+    //! bit of a `u32`, and a set of variants fits in a single integer: union is `|`, intersection
+    //! is `&`, membership is a non-zero `&`. This is how C APIs pass options, and what the
+    //! `bitflags` crate generates.
+    //!
+    //! The `u32` inside a `PermissionSet` only ever has the four known bits set: the field is
+    //! private and every way in, `insert`, `FromIterator` and `TryFrom<u32>`, goes through the
+    //! variants or rejects unknown bits. `as u32` converts a variant to its bit, the other
+    //! direction has no cast, `iter` tests each known bit instead.
+
+    use std::fmt;
+
+    #[repr(u32)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Permission {
+        Read = 1,
+        Write = 2,
+        Execute = 4,
+        Delete = 8,
+    }
+
+    impl Permission {
+        pub const ALL: [Permission; 4] = [
+            Permission::Read,
+            Permission::Write,
+            Permission::Execute,
+            Permission::Delete,
+        ];
+    }
+
+    const KNOWN_BITS: u32 = 0b1111;
+
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub struct PermissionSet(u32);
+
+    impl PermissionSet {
+        pub fn empty() -> Self {
+            PermissionSet(0)
+        }
+
+        pub fn bits(&self) -> u32 {
+            self.0
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.0 == 0
+        }
+
+        pub fn insert(&mut self, permission: Permission) {
+            self.0 |= permission as u32;
+        }
+
+        pub fn remove(&mut self, permission: Permission) {
+            self.0 &= !(permission as u32);
+        }
+
+        pub fn contains(&self, permission: Permission) -> bool {
+            self.0 & permission as u32 != 0
+        }
+
+        pub fn union(&self, other: PermissionSet) -> PermissionSet {
+            PermissionSet(self.0 | other.0)
+        }
+
+        pub fn intersection(&self, other: PermissionSet) -> PermissionSet {
+            PermissionSet(self.0 & other.0)
+        }
+
+        pub fn is_subset(&self, other: PermissionSet) -> bool {
+            self.0 & !other.0 == 0
+        }
+
+        /// The permissions in the set, in the order of their bits.
+        pub fn iter(&self) -> impl Iterator<Item = Permission> {
+            let set: PermissionSet = *self;
+            Permission::ALL
+                .into_iter()
+                .filter(move |&p| set.contains(p))
+        }
+    }
+
+    impl FromIterator<Permission> for PermissionSet {
+        fn from_iter<I: IntoIterator<Item = Permission>>(iter: I) -> Self {
+            let mut set: PermissionSet = PermissionSet::empty();
+            for permission in iter {
+                set.insert(permission);
+            }
+            set
+        }
+    }
+
+    /// The names joined by `|`, like `Read|Write`, and `none` for the empty set.
+    impl fmt::Display for PermissionSet {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            if self.is_empty() {
+                return write!(f, "none");
+            }
+            let names: Vec<String> = self.iter().map(|p| format!("{:?}", p)).collect();
+            write!(f, "{}", names.join("|"))
+        }
+    }
+
+    /// The bits of a `u32` that are not a `Permission`.
+    #[derive(Debug, PartialEq)]
+    pub struct UnknownBits(pub u32);
+
+    impl fmt::Display for UnknownBits {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "unknown permission bits {:#b}", self.0)
+        }
+    }
+
+    impl std::error::Error for UnknownBits {}
+
+    impl TryFrom<u32> for PermissionSet {
+        type Error = UnknownBits;
+
+        fn try_from(bits: u32) -> Result<Self, UnknownBits> {
+            match bits & !KNOWN_BITS {
+                0 => Ok(PermissionSet(bits)),
+                unknown => Err(UnknownBits(unknown)),
+            }
+        }
+    }
+
+    /// The owner, group and other sets of a unix mode such as `0o754`. Each octal digit is
+    /// `r` = 4, `w` = 2, `x` = 1, mapped to `Read`, `Write` and `Execute`. Unix has no delete
+    /// bit, deleting a file depends on write access to its directory, so `Delete` is never set.
+    /// Bits above `0o777` (setuid, setgid, sticky) are ignored.
+    pub fn from_unix_mode(mode: u16) -> [PermissionSet; 3] {
+        let triple = |shift: u16| -> PermissionSet {
+            let digit: u16 = (mode >> shift) & 0o7;
+            [
+                (0o4, Permission::Read),
+                (0o2, Permission::Write),
+                (0o1, Permission::Execute),
+            ]
+            .into_iter()
+            .filter(|(bit, _)| digit & bit != 0)
+            .map(|(_, permission)| permission)
+            .collect()
+        };
+        [triple(6), triple(3), triple(0)]
+    }
+}
+
 #[cfg(test)]
 mod testing {
     #[test]
@@ -276,4 +959,304 @@ mod testing {
     fn run_discriminant() {
         crate::custom_discriminant_values::discriminant();
     }
+
+    #[test]
+    fn run_enum_layout() {
+        crate::memory_layout::enum_layout();
+    }
+
+    #[test]
+    fn run_parse_command() {
+        use crate::defining_enum::Message;
+        use crate::parsing_commands::parse_command;
+        assert_eq!(parse_command("quit"), Ok(Message::Quit));
+        assert_eq!(parse_command("move 3 4"), Ok(Message::Move { x: 3, y: 4 }));
+        assert_eq!(
+            parse_command("move -3 -4"),
+            Ok(Message::Move { x: -3, y: -4 })
+        );
+        assert_eq!(
+            parse_command("write hello,   rust world"),
+            Ok(Message::Write(String::from("hello,   rust world")))
+        );
+        assert_eq!(
+            parse_command("color 255 0 10"),
+            Ok(Message::Color(255, 0, 10))
+        );
+    }
+
+    #[test]
+    fn run_parse_command_errors() {
+        use crate::parsing_commands::{parse_command, CommandError};
+        assert_eq!(
+            parse_command("jump 1 2"),
+            Err(CommandError::UnknownCommand(String::from("jump")))
+        );
+        assert_eq!(
+            parse_command("quit now"),
+            Err(CommandError::WrongArity {
+                expected: 0,
+                got: 1
+            })
+        );
+        assert_eq!(
+            parse_command("move 1"),
+            Err(CommandError::WrongArity {
+                expected: 2,
+                got: 1
+            })
+        );
+        assert_eq!(
+            parse_command("write"),
+            Err(CommandError::WrongArity {
+                expected: 1,
+                got: 0
+            })
+        );
+        assert_eq!(
+            parse_command("color 1 x 3"),
+            Err(CommandError::InvalidNumber { arg_index: 1 })
+        );
+    }
+
+    #[test]
+    fn run_parse_script_and_execute() {
+        use crate::parsing_commands::{execute, parse_script, CanvasState, CommandError};
+        let script: &str =
+            "move 10 20\ncolor 1 2 3\n\nwrite hello world\nmove -4 -5\nquit\nmove 1 1";
+        let messages = parse_script(script).unwrap();
+        assert_eq!(messages.len(), 6);
+        assert_eq!(
+            execute(&messages),
+            CanvasState {
+                position: (6, 15),
+                color: (1, 2, 3),
+                texts: vec![String::from("hello world")],
+                quit: true,
+            }
+        );
+
+        assert_eq!(
+            parse_script("quit\nmove a 1"),
+            Err((2, CommandError::InvalidNumber { arg_index: 0 }))
+        );
+    }
+
+    #[test]
+    fn run_is_active() {
+        use crate::non_exhaustive::{is_active, status::Status};
+        assert!(is_active(&Status::Active));
+        assert!(!is_active(&Status::Inactive));
+    }
+
+    #[test]
+    fn run_count_variant() {
+        use crate::defining_enum::Message;
+        use crate::matches_macro::count_variant;
+        let messages: Vec<Message> = vec![
+            Message::Move { x: 1, y: 2 },
+            Message::Write(String::from("rust")),
+            Message::Color(0, 0, 0),
+            Message::Move { x: 3, y: 4 },
+            Message::Quit,
+            Message::Move { x: 5, y: 6 },
+        ];
+        assert_eq!(count_variant(&messages, "Quit"), 1);
+        assert_eq!(count_variant(&messages, "Move"), 3);
+        assert_eq!(count_variant(&messages, "Write"), 1);
+        assert_eq!(count_variant(&messages, "Color"), 1);
+        assert_eq!(count_variant(&messages, "Jump"), 0);
+    }
+
+    #[test]
+    fn run_expr_precedence() {
+        use crate::expr::{parse, Expr};
+        let e: Expr = parse("2+3*4").unwrap();
+        let expected: Expr = Expr::Add(
+            Box::new(Expr::Num(2.0)),
+            Box::new(Expr::Mul(
+                Box::new(Expr::Num(3.0)),
+                Box::new(Expr::Num(4.0)),
+            )),
+        );
+        assert_eq!(e, expected);
+        assert_eq!(e.eval(), Ok(14.0));
+        assert_eq!(parse("10 - 4 - 3").unwrap().eval(), Ok(3.0));
+        assert_eq!(parse("24 / 4 / 2").unwrap().eval(), Ok(3.0));
+    }
+
+    #[test]
+    fn run_expr_parens_and_unary_minus() {
+        use crate::expr::parse;
+        assert_eq!(parse("((1 + 2)) * (3 - (4 - 5))").unwrap().eval(), Ok(12.0));
+        assert_eq!(parse("-2 * -3").unwrap().eval(), Ok(6.0));
+        assert_eq!(parse("-(1 + 2)").unwrap().eval(), Ok(-3.0));
+        assert_eq!(parse("--4").unwrap().eval(), Ok(4.0));
+        assert_eq!(parse(" 1.5 ").unwrap().eval(), Ok(1.5));
+    }
+
+    #[test]
+    fn run_expr_parse_errors() {
+        use crate::expr::{parse, ParseError};
+        let unexpected = |position, found| ParseError::UnexpectedChar { position, found };
+        assert_eq!(parse("2 + * 3"), Err(unexpected(4, '*')));
+        assert_eq!(parse("1 + 2)"), Err(unexpected(5, ')')));
+        assert_eq!(parse("(1 + 2"), Err(ParseError::UnexpectedEnd));
+        assert_eq!(parse("(1 + 2 3)"), Err(unexpected(7, '3')));
+        assert_eq!(parse("2 $ 3"), Err(unexpected(2, '$')));
+        assert_eq!(
+            parse("1 + 1.2.3"),
+            Err(ParseError::InvalidNumber { position: 4 })
+        );
+        assert_eq!(parse(""), Err(ParseError::UnexpectedEnd));
+        assert_eq!(parse("   "), Err(ParseError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn run_expr_eval_errors() {
+        use crate::expr::{parse, EvalError};
+        assert_eq!(
+            parse("1 / (2 - 2)").unwrap().eval(),
+            Err(EvalError::DivisionByZero)
+        );
+        assert_eq!(parse("0 / 5").unwrap().eval(), Ok(0.0));
+        assert_eq!(
+            parse("x * 2").unwrap().eval(),
+            Err(EvalError::UnboundVariable(String::from("x")))
+        );
+    }
+
+    #[test]
+    fn run_expr_display_round_trip() {
+        use crate::expr::parse;
+        let cases: [(&str, &str); 8] = [
+            ("2+3*4", "2 + 3 * 4"),
+            ("(2+3)*4", "(2 + 3) * 4"),
+            ("a-(b-c)", "a - (b - c)"),
+            ("(a-b)-c", "a - b - c"),
+            ("a+(b+c)", "a + (b + c)"),
+            ("a/(b*c)", "a / (b * c)"),
+            ("-(x+1)*-y", "-(x + 1) * -y"),
+            ("--((0.5))", "--0.5"),
+        ];
+        for (input, printed) in cases {
+            let e = parse(input).unwrap();
+            assert_eq!(e.to_string(), printed);
+            assert_eq!(parse(&e.to_string()).unwrap(), e);
+        }
+    }
+
+    #[test]
+    fn run_expr_simplify() {
+        use crate::expr::{parse, EvalError, Expr};
+        let simplified = |input: &str| parse(input).unwrap().simplify().to_string();
+        assert_eq!(simplified("(2*3)+x-0"), "6 + x");
+        assert_eq!(simplified("x * (4 - 3) + 0 * y"), "x");
+        assert_eq!(simplified("0 - x / 1"), "-x");
+        assert_eq!(simplified("--x"), "x");
+        assert_eq!(simplified("-(1 + 2) * x"), "-3 * x");
+        assert_eq!(parse("1 + 2 * 3").unwrap().simplify(), Expr::Num(7.0));
+
+        // the division by zero survives, and eval still reports it
+        assert_eq!(simplified("x + 1 / (1 - 1)"), "x + 1 / 0");
+        // multiplying it by zero does not hide it
+        assert_eq!(simplified("0 * (1 / 0)"), "0 * (1 / 0)");
+        assert_eq!(simplified("(x / (2 - 2)) * 0"), "x / 0 * 0");
+        assert_eq!(
+            parse("0 * (1 / 0)").unwrap().simplify().eval(),
+            Err(EvalError::DivisionByZero)
+        );
+        assert_eq!(simplified("0 * (x / 2)"), "0");
+    }
+
+    #[test]
+    fn run_flag_set_round_trip() {
+        use crate::flag_set::{Permission, PermissionSet};
+        let mut set: PermissionSet = PermissionSet::empty();
+        set.insert(Permission::Delete);
+        set.insert(Permission::Read);
+        set.insert(Permission::Read);
+        assert_eq!(set.bits(), 0b1001);
+        assert!(set.contains(Permission::Read) && !set.contains(Permission::Write));
+        assert_eq!(
+            set.iter().collect::<Vec<Permission>>(),
+            [Permission::Read, Permission::Delete]
+        );
+        assert_eq!(set.iter().collect::<PermissionSet>(), set);
+        assert_eq!(set.to_string(), "Read|Delete");
+
+        set.remove(Permission::Read);
+        set.remove(Permission::Write);
+        assert_eq!(set.to_string(), "Delete");
+
+        for bits in 0..16 {
+            let set: PermissionSet = PermissionSet::try_from(bits).unwrap();
+            assert_eq!(set.iter().collect::<PermissionSet>(), set);
+            assert_eq!(set.iter().count(), bits.count_ones() as usize);
+        }
+        let all: PermissionSet = Permission::ALL.into_iter().collect();
+        assert_eq!(all.to_string(), "Read|Write|Execute|Delete");
+    }
+
+    #[test]
+    fn run_flag_set_unknown_bits() {
+        use crate::flag_set::{PermissionSet, UnknownBits};
+        assert_eq!(
+            PermissionSet::try_from(0b1_0011),
+            Err(UnknownBits(0b1_0000))
+        );
+        assert_eq!(PermissionSet::try_from(u32::MAX), Err(UnknownBits(!0b1111)));
+        assert_eq!(
+            UnknownBits(0b10_0000).to_string(),
+            "unknown permission bits 0b100000"
+        );
+        assert_eq!(
+            PermissionSet::try_from(0b0110).map(|s| s.bits()),
+            Ok(0b0110)
+        );
+    }
+
+    #[test]
+    fn run_flag_set_empty_display() {
+        use crate::flag_set::PermissionSet;
+        assert_eq!(PermissionSet::empty().to_string(), "none");
+        assert_eq!(PermissionSet::default(), PermissionSet::empty());
+        assert_eq!(PermissionSet::empty().iter().count(), 0);
+    }
+
+    #[test]
+    fn run_flag_set_unix_mode() {
+        use crate::flag_set::{from_unix_mode, Permission::*, PermissionSet};
+        let [owner, group, other]: [PermissionSet; 3] = from_unix_mode(0o754);
+        assert_eq!(owner, [Read, Write, Execute].into_iter().collect());
+        assert_eq!(group, [Read, Execute].into_iter().collect());
+        assert_eq!(other, [Read].into_iter().collect());
+        assert_eq!(owner.to_string(), "Read|Write|Execute");
+
+        assert_eq!(from_unix_mode(0o4754), from_unix_mode(0o754));
+        assert!(from_unix_mode(0).iter().all(|set| set.is_empty()));
+    }
+
+    #[test]
+    fn run_flag_set_algebra() {
+        use crate::flag_set::PermissionSet;
+        let sets: Vec<PermissionSet> = [0b0000, 0b0001, 0b0110, 0b1010, 0b1111]
+            .into_iter()
+            .map(|bits| PermissionSet::try_from(bits).unwrap())
+            .collect();
+        for &a in &sets {
+            for &b in &sets {
+                assert!(a.is_subset(a.union(b)));
+                assert!(a.intersection(b).is_subset(a));
+                assert_eq!(a.union(b), b.union(a));
+                assert_eq!(a.intersection(b), b.intersection(a));
+                assert_eq!(a.union(a.intersection(b)), a);
+            }
+        }
+    }
+
+    #[test]
+    fn run_option_get_or_insert() {
+        crate::option_enum::option_get_or_insert();
+    }
 }
@@ -29,3 +29,273 @@ fn function_body() {
 
 #[allow(dead_code)]
 fn function_return() -> () {}
+
+pub mod timing {
+    //! `std::time::Instant` is a monotonic clock: it never goes backwards, so the difference
+    //! between two instants is always a valid elapsed time. It is the right clock for measuring how
+    //! long something takes, whereas `SystemTime` may jump when the wall clock is adjusted.
+    //!
+    //! `Instant::now()` captures the current moment and `elapsed()` returns the `Duration` since.
+
+    use std::time::{Duration, Instant};
+
+    /// Runs `f` once and returns its result together with the time it took.
+    pub fn time_it<F: FnOnce() -> T, T>(f: F) -> (T, Duration) {
+        let start: Instant = Instant::now();
+        let result: T = f();
+        (result, start.elapsed())
+    }
+}
+
+pub mod control_flow {
+    //! Rust has three loops:
+    //!
+    //! * `loop` repeats until a `break`. It is an expression: `break value` makes the loop
+    //!   evaluate to `value`, which the other two loops can't do.
+    //! * `while condition` checks the condition before every iteration.
+    //! * `for pattern in iterable` calls `into_iter` on the value and runs once per item. There is
+    //!   no index to get wrong, so it is the usual way to walk a collection or a range.
+    //!
+    //! In any of them `continue` skips to the next iteration and `break` leaves the loop. Both act
+    //! on the innermost loop, unless a label like `'outer:` names another one.
+
+    pub fn sum_with_loop(n: u64) -> u64 {
+        let mut i: u64 = 1;
+        let mut sum: u64 = 0;
+        loop {
+            if i > n {
+                break sum;
+            }
+            sum += i;
+            i += 1;
+        }
+    }
+
+    pub fn sum_with_while(n: u64) -> u64 {
+        let mut i: u64 = 1;
+        let mut sum: u64 = 0;
+        while i <= n {
+            sum += i;
+            i += 1;
+        }
+        sum
+    }
+
+    pub fn sum_with_for(n: u64) -> u64 {
+        let mut sum: u64 = 0;
+        for i in 1..=n {
+            sum += i;
+        }
+        sum
+    }
+
+    /// Sums the odd numbers of `values` up to the first negative one.
+    pub fn break_and_continue(values: &[i32]) -> i32 {
+        let mut sum: i32 = 0;
+        for &value in values {
+            if value < 0 {
+                break;
+            }
+            if value % 2 == 0 {
+                continue;
+            }
+            sum += value;
+        }
+        sum
+    }
+
+    /// The position of the first `target`, row by row. A plain `break` would only leave the
+    /// inner loop and go on with the next row, `break 'outer` stops both.
+    pub fn find_in_grid(grid: &[Vec<i32>], target: i32) -> Option<(usize, usize)> {
+        let mut found: Option<(usize, usize)> = None;
+        'outer: for (row, values) in grid.iter().enumerate() {
+            for (column, &value) in values.iter().enumerate() {
+                if value == target {
+                    found = Some((row, column));
+                    break 'outer;
+                }
+            }
+        }
+        found
+    }
+
+    /// `if` is an expression: each branch is a block whose last expression is its value, and the
+    /// whole `if` evaluates to the value of the branch taken, so it can initialize a `let` like the
+    /// ternary operator of other languages. All branches must have the same type, here
+    /// `&'static str`, and an `if` used as a value needs an `else`, without one the missing
+    /// branch would be `()`.
+    #[allow(clippy::let_and_return)]
+    pub fn if_expression(x: i32) -> &'static str {
+        // bound to a variable on purpose, to show the `if` producing a value
+        let result = if x > 0 {
+            "pos"
+        } else if x < 0 {
+            "neg"
+        } else {
+            "zero"
+        };
+        result
+    }
+
+    /// `match` is an expression too: the value of the arm taken becomes the value of the whole
+    /// `match`, so every arm must have the same type, `String` here. The matches of the enums
+    /// crate mostly run code for its effect in each arm, here the `match` is the function's
+    /// return value. Range patterns such as `1..=9` cover many values in one arm, and the
+    /// compiler checks that together the arms cover every `i32`.
+    pub fn describe_number(n: i32) -> String {
+        match n {
+            i32::MIN..=-1 => format!("{} is negative", n),
+            0 => String::from("zero"),
+            1..=9 => format!("{} is a single digit", n),
+            10..=99 => format!("{} has two digits", n),
+            _ => format!("{} is large", n),
+        }
+    }
+}
+
+pub mod memoize {
+    //! Memoization trades memory for time: the result of a pure function is stored the first
+    //! time it is computed for an argument, and every later call with an equal argument returns
+    //! the stored copy instead of running the function again. It only pays off when the function
+    //! is expensive and the same arguments come back, and it is only correct when the function
+    //! has no side effects and always returns the same result for the same argument.
+    //!
+    //! `Memo` owns the function and a `HashMap` from argument to result. The argument is cloned
+    //! into the map as a key, so `A` must be `Eq + Hash + Clone`, and the result is cloned out of
+    //! it, so `R` must be `Clone`. `call` takes `&mut self` because a cache miss inserts.
+
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    pub struct Memo<A, R, F> {
+        cache: HashMap<A, R>,
+        f: F,
+    }
+
+    impl<A, R, F> Memo<A, R, F>
+    where
+        A: Eq + Hash + Clone,
+        R: Clone,
+        F: Fn(A) -> R,
+    {
+        pub fn new(f: F) -> Self {
+            Memo {
+                cache: HashMap::new(),
+                f,
+            }
+        }
+
+        pub fn call(&mut self, arg: A) -> R {
+            if let Some(result) = self.cache.get(&arg) {
+                return result.clone();
+            }
+            let result: R = (self.f)(arg.clone());
+            self.cache.insert(arg, result.clone());
+            result
+        }
+
+        /// The number of distinct arguments seen so far.
+        pub fn len(&self) -> usize {
+            self.cache.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.cache.is_empty()
+        }
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use std::time::Duration;
+
+    #[test]
+    fn run_timing_time_it() {
+        let (sum, elapsed): (u64, Duration) = crate::timing::time_it(|| (1..=1000u64).sum());
+        assert_eq!(sum, 500500);
+        assert!(elapsed >= Duration::ZERO);
+    }
+
+    #[test]
+    fn run_control_flow_sums_agree() {
+        use crate::control_flow::{sum_with_for, sum_with_loop, sum_with_while};
+        for n in [0, 1, 2, 10, 1000] {
+            let expected: u64 = n * (n + 1) / 2;
+            assert_eq!(sum_with_loop(n), expected);
+            assert_eq!(sum_with_while(n), expected);
+            assert_eq!(sum_with_for(n), expected);
+        }
+    }
+
+    #[test]
+    fn run_control_flow_break_and_continue() {
+        use crate::control_flow::break_and_continue;
+        assert_eq!(break_and_continue(&[1, 2, 3, 4, 5]), 9);
+        assert_eq!(break_and_continue(&[1, 2, 3, -1, 5]), 4);
+        assert_eq!(break_and_continue(&[-1, 1]), 0);
+        assert_eq!(break_and_continue(&[]), 0);
+    }
+
+    #[test]
+    fn run_control_flow_labeled_break() {
+        use crate::control_flow::find_in_grid;
+        let grid: Vec<Vec<i32>> = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 5, 9]];
+        assert_eq!(find_in_grid(&grid, 5), Some((1, 1)));
+        assert_eq!(find_in_grid(&grid, 1), Some((0, 0)));
+        assert_eq!(find_in_grid(&grid, 10), None);
+        assert_eq!(find_in_grid(&[], 1), None);
+    }
+
+    #[test]
+    fn run_control_flow_if_expression() {
+        use crate::control_flow::if_expression;
+        assert_eq!(if_expression(7), "pos");
+        assert_eq!(if_expression(-7), "neg");
+        assert_eq!(if_expression(0), "zero");
+        assert_eq!(if_expression(i32::MIN), "neg");
+    }
+
+    #[test]
+    fn run_control_flow_describe_number() {
+        use crate::control_flow::describe_number;
+        assert_eq!(describe_number(-5), "-5 is negative");
+        assert_eq!(describe_number(i32::MIN), "-2147483648 is negative");
+        assert_eq!(describe_number(0), "zero");
+        assert_eq!(describe_number(1), "1 is a single digit");
+        assert_eq!(describe_number(9), "9 is a single digit");
+        assert_eq!(describe_number(10), "10 has two digits");
+        assert_eq!(describe_number(99), "99 has two digits");
+        assert_eq!(describe_number(100), "100 is large");
+    }
+
+    #[test]
+    fn run_memoize_runs_once_per_argument() {
+        use crate::memoize::Memo;
+        use std::cell::Cell;
+
+        // `Fn` can't mutate a captured counter directly, a `Cell` counts through `&`
+        let runs: Cell<u32> = Cell::new(0);
+        let mut square = Memo::new(|n: u64| {
+            runs.set(runs.get() + 1);
+            n * n
+        });
+        assert!(square.is_empty());
+        assert_eq!(square.call(3), 9);
+        assert_eq!(square.call(3), 9);
+        assert_eq!(square.call(4), 16);
+        assert_eq!(square.call(3), 9);
+        assert_eq!(square.call(4), 16);
+        assert_eq!(square.len(), 2);
+        assert_eq!(runs.get(), 2);
+
+        let runs: Cell<u32> = Cell::new(0);
+        let mut shout = Memo::new(|s: String| {
+            runs.set(runs.get() + 1);
+            s.to_uppercase()
+        });
+        for word in ["rust", "memo", "rust", "rust", "memo"] {
+            assert_eq!(shout.call(word.to_string()), word.to_uppercase());
+        }
+        assert_eq!(runs.get(), 2);
+    }
+}
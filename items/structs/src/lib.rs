@@ -387,6 +387,1027 @@ pub mod memory_layout {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// Session Types
+////////////////////////////////////////////////////////////////////////////////
+pub mod session_types {
+    //! A struct can carry a type parameter that is never stored, only marked with `PhantomData`,
+    //! to encode which state a value is in. Each method is implemented only for the state where it
+    //! is valid, and transitions consume `self` and return the struct in its next state, so calling
+    //! methods in the wrong order is a compile error instead of a runtime check. `PhantomData` is
+    //! zero-sized, the state costs nothing at runtime.
+    //!
+    //! ```text
+    //! Connection<Uninitialized> --open--> Connection<Ready> --close--> Connection<Closed>
+    //!                                       |          ^
+    //!                                       +---send---+
+    //! ```
+    //!
+    //! `close` consumes the ready connection, and a closed one has no `send` method:
+    //! ```compile_fail,E0599
+    //! use structs::session_types::{Closed, Connection};
+    //!
+    //! let mut connection = Connection::new().open();
+    //! connection.send("hello");
+    //! let mut closed: Connection<Closed> = connection.close();
+    //! closed.send("too late");
+    //! ```
+    //!
+    //! For comparison, `RuntimeConnection` stores its state in an enum field: it is more flexible
+    //! (the state can be decided at runtime, e.g. stored in a collection), but every method must
+    //! check the state and the caller must handle `WrongState`.
+
+    use std::marker::PhantomData;
+
+    pub struct Uninitialized;
+    pub struct Ready;
+    pub struct Closed;
+
+    pub struct Connection<State> {
+        log: Vec<String>,
+        _state: PhantomData<State>,
+    }
+
+    impl<State> Connection<State> {
+        fn transition<Next>(mut self, event: &str) -> Connection<Next> {
+            self.log.push(event.to_string());
+            Connection {
+                log: self.log,
+                _state: PhantomData,
+            }
+        }
+    }
+
+    impl Connection<Uninitialized> {
+        #[allow(clippy::new_without_default)]
+        pub fn new() -> Self {
+            Self {
+                log: vec![],
+                _state: PhantomData,
+            }
+        }
+
+        pub fn open(self) -> Connection<Ready> {
+            self.transition("open")
+        }
+    }
+
+    impl Connection<Ready> {
+        pub fn send(&mut self, msg: &str) {
+            self.log.push(format!("send {}", msg));
+        }
+
+        pub fn close(self) -> Connection<Closed> {
+            self.transition("close")
+        }
+    }
+
+    impl Connection<Closed> {
+        pub fn report(&self) -> &[String] {
+            &self.log
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum ConnectionState {
+        Uninitialized,
+        Ready,
+        Closed,
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct WrongState {
+        pub expected: ConnectionState,
+        pub actual: ConnectionState,
+    }
+
+    pub struct RuntimeConnection {
+        state: ConnectionState,
+        log: Vec<String>,
+    }
+
+    impl RuntimeConnection {
+        #[allow(clippy::new_without_default)]
+        pub fn new() -> Self {
+            Self {
+                state: ConnectionState::Uninitialized,
+                log: vec![],
+            }
+        }
+
+        fn expect(&self, expected: ConnectionState) -> Result<(), WrongState> {
+            if self.state == expected {
+                Ok(())
+            } else {
+                Err(WrongState {
+                    expected,
+                    actual: self.state,
+                })
+            }
+        }
+
+        pub fn open(&mut self) -> Result<(), WrongState> {
+            self.expect(ConnectionState::Uninitialized)?;
+            self.log.push(String::from("open"));
+            self.state = ConnectionState::Ready;
+            Ok(())
+        }
+
+        pub fn send(&mut self, msg: &str) -> Result<(), WrongState> {
+            self.expect(ConnectionState::Ready)?;
+            self.log.push(format!("send {}", msg));
+            Ok(())
+        }
+
+        pub fn close(&mut self) -> Result<(), WrongState> {
+            self.expect(ConnectionState::Ready)?;
+            self.log.push(String::from("close"));
+            self.state = ConnectionState::Closed;
+            Ok(())
+        }
+
+        pub fn report(&self) -> Result<&[String], WrongState> {
+            self.expect(ConnectionState::Closed)?;
+            Ok(&self.log)
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Compile Errors
+////////////////////////////////////////////////////////////////////////////////
+pub mod compile_errors {
+    //! The cases `struct_update_syntax` and `make_struct_field_public` describe as rejected, plus
+    //! assigning through an immutable binding, as `compile_fail` doc tests: `cargo test` fails if
+    //! one of them starts compiling. Each function body is the accepted version.
+
+    #[derive(Debug, PartialEq)]
+    pub struct Laptop {
+        name: String,
+        size: usize,
+    }
+
+    /// Moving a `String` field out leaves the struct partially moved, error[E0382]: borrow of
+    /// partially moved value: `laptop`. Fields that are `Copy`, like `size`, are copied instead.
+    ///
+    /// ```compile_fail,E0382
+    /// #[derive(Debug)]
+    /// struct Laptop {
+    ///     name: String,
+    ///     size: usize,
+    /// }
+    ///
+    /// let laptop = Laptop {
+    ///     name: "mac".to_string(),
+    ///     size: 13,
+    /// };
+    /// let _new_laptop = Laptop {
+    ///     name: laptop.name, // value partially moved here
+    ///     size: 16,
+    /// };
+    /// println!("{:#?}", laptop);
+    /// ```
+    pub fn use_after_partial_move() -> (Laptop, Laptop) {
+        let laptop = Laptop {
+            name: "mac".to_string(),
+            size: 13,
+        };
+        let new_laptop = Laptop {
+            name: laptop.name.clone(),
+            size: 16,
+        };
+        (laptop, new_laptop)
+    }
+
+    /// `..laptop` moves every remaining field that is not `Copy`, here `name`, error[E0382]:
+    /// borrow of partially moved value: `laptop`.
+    ///
+    /// ```compile_fail,E0382
+    /// #[derive(Debug)]
+    /// struct Laptop {
+    ///     name: String,
+    ///     size: usize,
+    /// }
+    ///
+    /// let laptop = Laptop {
+    ///     name: "mac".to_string(),
+    ///     size: 13,
+    /// };
+    /// let _new_laptop = Laptop { size: 16, ..laptop };
+    /// println!("{:#?}", laptop);
+    /// ```
+    pub fn use_after_update_syntax() -> (usize, Laptop) {
+        let laptop = Laptop {
+            name: "mac".to_string(),
+            size: 13,
+        };
+        let new_laptop = Laptop { size: 16, ..laptop };
+        // `size` was not moved, it can still be read
+        (laptop.size, new_laptop)
+    }
+
+    /// Fields are private to the module that defines the struct, error[E0616]: field `rank` of
+    /// struct `Player` is private.
+    ///
+    /// ```compile_fail,E0616
+    /// mod inner {
+    ///     pub struct Player {
+    ///         pub name: String,
+    ///         rank: usize,
+    ///     }
+    ///
+    ///     impl Player {
+    ///         pub fn new(name: &str) -> Self {
+    ///             Self {
+    ///                 name: name.to_string(),
+    ///                 rank: 1,
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let rank = inner::Player::new("rust").rank;
+    /// ```
+    pub fn read_private_field() -> usize {
+        mod inner {
+            pub struct Player {
+                rank: usize,
+            }
+
+            impl Player {
+                pub fn new() -> Self {
+                    Self { rank: 1 }
+                }
+
+                pub fn rank(&self) -> usize {
+                    self.rank
+                }
+            }
+        }
+
+        inner::Player::new().rank()
+    }
+
+    /// Mutability belongs to the binding, there is no way to mark a single field `mut`,
+    /// error[E0594]: cannot assign to `laptop.size`, as `laptop` is not declared as mutable.
+    ///
+    /// ```compile_fail,E0594
+    /// struct Laptop {
+    ///     name: String,
+    ///     size: usize,
+    /// }
+    ///
+    /// let laptop = Laptop {
+    ///     name: "mac".to_string(),
+    ///     size: 13,
+    /// };
+    /// laptop.size = 16;
+    /// ```
+    pub fn assign_to_immutable() -> Laptop {
+        let mut laptop = Laptop {
+            name: "mac".to_string(),
+            size: 13,
+        };
+        laptop.size = 16;
+        laptop
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Struct Of Arrays
+////////////////////////////////////////////////////////////////////////////////
+pub mod soa_aos {
+    //! Array of structs (AoS) stores whole particles one after another, struct of arrays (SoA)
+    //! stores one vector per field:
+    //!
+    //! ```text
+    //! AoS  [x y vx vy][x y vx vy][x y vx vy]
+    //! SoA  xs  [x  x  x ]
+    //!      ys  [y  y  y ]
+    //!      vxs [vx vx vx]
+    //!      vys [vy vy vy]
+    //! ```
+    //!
+    //! A loop that touches only some fields reads only those vectors in SoA, every byte fetched is
+    //! used and the compiler can vectorize `xs[i] += vxs[i] * dt` easily. AoS keeps each particle
+    //! together, which suits code that works on one whole particle at a time.
+    //!
+    //! The SoA fields are private: nothing but `push` can grow them, so the four vectors always
+    //! have the same length and index `i` is the same particle in each.
+
+    use std::time::{Duration, Instant};
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct ParticleAos {
+        pub x: f32,
+        pub y: f32,
+        pub vx: f32,
+        pub vy: f32,
+    }
+
+    #[derive(Debug, Default)]
+    pub struct ParticlesSoa {
+        xs: Vec<f32>,
+        ys: Vec<f32>,
+        vxs: Vec<f32>,
+        vys: Vec<f32>,
+    }
+
+    /// `(min_x, min_y, max_x, max_y)`.
+    pub type BoundingBox = (f32, f32, f32, f32);
+
+    /// The operations both layouts share, `push` and `len` are inherent on `Vec` already.
+    pub trait Simulate {
+        /// Moves every particle by its velocity times `dt`.
+        fn step(&mut self, dt: f32);
+        /// `None` when there is no particle.
+        fn bounding_box(&self) -> Option<BoundingBox>;
+    }
+
+    fn extend(bbox: Option<BoundingBox>, x: f32, y: f32) -> Option<BoundingBox> {
+        Some(match bbox {
+            None => (x, y, x, y),
+            Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+        })
+    }
+
+    impl Simulate for Vec<ParticleAos> {
+        fn step(&mut self, dt: f32) {
+            for p in self.iter_mut() {
+                p.x += p.vx * dt;
+                p.y += p.vy * dt;
+            }
+        }
+
+        fn bounding_box(&self) -> Option<BoundingBox> {
+            self.iter().fold(None, |bbox, p| extend(bbox, p.x, p.y))
+        }
+    }
+
+    impl ParticlesSoa {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn push(&mut self, p: ParticleAos) {
+            self.xs.push(p.x);
+            self.ys.push(p.y);
+            self.vxs.push(p.vx);
+            self.vys.push(p.vy);
+        }
+
+        pub fn len(&self) -> usize {
+            self.xs.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.xs.is_empty()
+        }
+
+        /// Gathers the fields of particle `i` back into a struct.
+        pub fn get(&self, i: usize) -> Option<ParticleAos> {
+            Some(ParticleAos {
+                x: *self.xs.get(i)?,
+                y: self.ys[i],
+                vx: self.vxs[i],
+                vy: self.vys[i],
+            })
+        }
+
+        /// The length of every field vector, for checking the invariant.
+        pub fn field_lens(&self) -> [usize; 4] {
+            [self.xs.len(), self.ys.len(), self.vxs.len(), self.vys.len()]
+        }
+    }
+
+    impl Simulate for ParticlesSoa {
+        fn step(&mut self, dt: f32) {
+            for (x, vx) in self.xs.iter_mut().zip(&self.vxs) {
+                *x += vx * dt;
+            }
+            for (y, vy) in self.ys.iter_mut().zip(&self.vys) {
+                *y += vy * dt;
+            }
+        }
+
+        fn bounding_box(&self) -> Option<BoundingBox> {
+            self.xs
+                .iter()
+                .zip(&self.ys)
+                .fold(None, |bbox, (&x, &y)| extend(bbox, x, y))
+        }
+    }
+
+    /// Both layouts run the same float operations in the same order, so the results are compared
+    /// exactly rather than with a tolerance.
+    #[allow(clippy::ptr_arg)]
+    pub fn equivalent(a: &Vec<ParticleAos>, b: &ParticlesSoa) -> bool {
+        a.len() == b.len() && a.iter().enumerate().all(|(i, p)| b.get(i) == Some(*p))
+    }
+
+    fn particle(i: usize) -> ParticleAos {
+        let i: f32 = i as f32;
+        ParticleAos {
+            x: i,
+            y: -i,
+            vx: (i * 0.37).sin(),
+            vy: (i * 0.11).cos(),
+        }
+    }
+
+    /// Times `iterations` steps over `n` particles in each layout, `(aos, soa)`. Only meaningful
+    /// in a release build.
+    pub fn bench_step(n: usize, iterations: usize) -> (Duration, Duration) {
+        let mut aos: Vec<ParticleAos> = (0..n).map(particle).collect();
+        let mut soa: ParticlesSoa = ParticlesSoa::new();
+        (0..n).for_each(|i| soa.push(particle(i)));
+
+        let start: Instant = Instant::now();
+        for _ in 0..iterations {
+            aos.step(std::hint::black_box(0.01));
+        }
+        let aos_time: Duration = start.elapsed();
+
+        let start: Instant = Instant::now();
+        for _ in 0..iterations {
+            soa.step(std::hint::black_box(0.01));
+        }
+        let soa_time: Duration = start.elapsed();
+
+        assert!(equivalent(&aos, &soa));
+        (aos_time, soa_time)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Ids
+////////////////////////////////////////////////////////////////////////////////
+pub mod ids {
+    //! A 128 bit identifier in a tuple struct around `[u8; 16]`, written like a UUID:
+    //!
+    //! ```text
+    //! 8 - 4 - 4 - 4 - 12 hex digits      e.g. 5a3f09c1-7be2-40d8-9f11-02c4a6e8b3d7
+    //! ```
+    //!
+    //! The bytes come from a caller supplied `RngLike`, so the struct needs no dependency on a
+    //! random number crate and tests can use a seeded generator and get the same ids every run.
+    //! The derived `Ord` compares the bytes in order, which is the order of the hex strings, and
+    //! the derived `Hash` lets a `Uid` be a `HashMap` key.
+
+    use std::fmt;
+    use std::str::FromStr;
+
+    pub trait RngLike {
+        fn next_u64(&mut self) -> u64;
+    }
+
+    /// Marsaglia's xorshift generator: fast, deterministic, not for anything secret. The state
+    /// must not be 0, which it would never leave.
+    pub struct XorShift64(pub u64);
+
+    impl RngLike for XorShift64 {
+        fn next_u64(&mut self) -> u64 {
+            let mut x: u64 = self.0;
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            self.0 = x;
+            x
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct Uid([u8; 16]);
+
+    /// The char indices of the hyphens in the text form.
+    const HYPHENS: [usize; 4] = [8, 13, 18, 23];
+    const TEXT_LEN: usize = 36;
+
+    impl Uid {
+        pub fn generate(rng: &mut impl RngLike) -> Uid {
+            let mut bytes: [u8; 16] = [0; 16];
+            bytes[..8].copy_from_slice(&rng.next_u64().to_be_bytes());
+            bytes[8..].copy_from_slice(&rng.next_u64().to_be_bytes());
+            Uid(bytes)
+        }
+
+        pub fn as_bytes(&self) -> &[u8; 16] {
+            &self.0
+        }
+
+        /// The first 8 hex digits, enough to tell ids apart in a log.
+        pub fn short(&self) -> String {
+            self.0[..4]
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect()
+        }
+    }
+
+    impl fmt::Display for Uid {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            for (i, byte) in self.0.iter().enumerate() {
+                if matches!(i, 4 | 6 | 8 | 10) {
+                    write!(f, "-")?;
+                }
+                write!(f, "{:02x}", byte)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Positions are char indices in the parsed string.
+    #[derive(Debug, PartialEq)]
+    pub enum ParseUidError {
+        WrongLength { len: usize },
+        MissingHyphen { position: usize },
+        InvalidHexDigit { position: usize, found: char },
+    }
+
+    impl fmt::Display for ParseUidError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                ParseUidError::WrongLength { len } => {
+                    write!(f, "expected {} characters, found {}", TEXT_LEN, len)
+                }
+                ParseUidError::MissingHyphen { position } => {
+                    write!(f, "expected `-` at position {}", position)
+                }
+                ParseUidError::InvalidHexDigit { position, found } => {
+                    write!(f, "invalid hex digit {:?} at position {}", found, position)
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for ParseUidError {}
+
+    impl FromStr for Uid {
+        type Err = ParseUidError;
+
+        /// Accepts upper and lower case hex digits.
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let len: usize = s.chars().count();
+            if len != TEXT_LEN {
+                return Err(ParseUidError::WrongLength { len });
+            }
+            let mut bytes: [u8; 16] = [0; 16];
+            let mut digits: usize = 0;
+            for (position, c) in s.chars().enumerate() {
+                if HYPHENS.contains(&position) {
+                    if c != '-' {
+                        return Err(ParseUidError::MissingHyphen { position });
+                    }
+                    continue;
+                }
+                let digit: u32 = c
+                    .to_digit(16)
+                    .ok_or(ParseUidError::InvalidHexDigit { position, found: c })?;
+                bytes[digits / 2] = bytes[digits / 2] << 4 | digit as u8;
+                digits += 1;
+            }
+            Ok(Uid(bytes))
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Migrations
+////////////////////////////////////////////////////////////////////////////////
+pub mod migrations {
+    //! Records written by an older version of a program have an older shape. Instead of every
+    //! reader handling every version, each version gets its own struct, and a `From` impl turns
+    //! a version into the next one. Migrating from any version is then a chain of single steps,
+    //! so adding a version means writing one new conversion, not one per older version.
+    //!
+    //! ```text
+    //! LaptopV1 --From--> LaptopV2 --From--> LaptopV3 (latest)
+    //! ```
+    //!
+    //! The text format starts with the version, then the fields separated by `|`, the tags of a
+    //! V3 record separated by `,`:
+    //!
+    //! ```text
+    //! v1|name|size
+    //! v2|name|size_inches|ram_gb
+    //! v3|name|size_inches|ram_gb|tag,tag
+    //! ```
+
+    use std::fmt;
+
+    /// The RAM a V1 record is assumed to have, V1 did not record it.
+    pub const DEFAULT_RAM_GB: u16 = 8;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct LaptopV1 {
+        pub name: String,
+        /// The screen size in whole inches.
+        pub size: usize,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct LaptopV2 {
+        pub name: String,
+        pub size_inches: f32,
+        pub ram_gb: u16,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct LaptopV3 {
+        pub name: String,
+        pub size_inches: f32,
+        pub ram_gb: u16,
+        pub tags: Vec<String>,
+    }
+
+    /// The name is kept, the size in whole inches becomes a fractional size, and the RAM, which
+    /// V1 did not record, is `DEFAULT_RAM_GB`.
+    impl From<LaptopV1> for LaptopV2 {
+        fn from(v1: LaptopV1) -> Self {
+            LaptopV2 {
+                name: v1.name,
+                size_inches: v1.size as f32,
+                ram_gb: DEFAULT_RAM_GB,
+            }
+        }
+    }
+
+    /// Every field is kept, and the laptop has no tags.
+    impl From<LaptopV2> for LaptopV3 {
+        fn from(v2: LaptopV2) -> Self {
+            LaptopV3 {
+                name: v2.name,
+                size_inches: v2.size_inches,
+                ram_gb: v2.ram_gb,
+                tags: vec![],
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum AnyVersion {
+        V1(LaptopV1),
+        V2(LaptopV2),
+        V3(LaptopV3),
+    }
+
+    /// Applies the single steps from the record's version up, a V3 record is returned as is.
+    pub fn migrate_to_latest(any: AnyVersion) -> LaptopV3 {
+        match any {
+            AnyVersion::V1(v1) => LaptopV2::from(v1).into(),
+            AnyVersion::V2(v2) => v2.into(),
+            AnyVersion::V3(v3) => v3,
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum MigrateError {
+        /// The line does not start with a `v<number>|` prefix.
+        MissingVersion,
+        UnknownVersion(String),
+        WrongFieldCount {
+            expected: usize,
+            got: usize,
+        },
+        InvalidField {
+            field: &'static str,
+            value: String,
+        },
+    }
+
+    impl fmt::Display for MigrateError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                MigrateError::MissingVersion => write!(f, "the line has no version prefix"),
+                MigrateError::UnknownVersion(version) => write!(f, "unknown version {}", version),
+                MigrateError::WrongFieldCount { expected, got } => {
+                    write!(f, "expected {} fields, found {}", expected, got)
+                }
+                MigrateError::InvalidField { field, value } => {
+                    write!(f, "invalid {}: {:?}", field, value)
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for MigrateError {}
+
+    fn field<T: std::str::FromStr>(field: &'static str, value: &str) -> Result<T, MigrateError> {
+        value.parse().map_err(|_| MigrateError::InvalidField {
+            field,
+            value: value.to_string(),
+        })
+    }
+
+    pub fn parse_versioned(line: &str) -> Result<AnyVersion, MigrateError> {
+        let (version, rest) = line.split_once('|').ok_or(MigrateError::MissingVersion)?;
+        let number: &str = version
+            .strip_prefix('v')
+            .ok_or(MigrateError::MissingVersion)?;
+        if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(MigrateError::MissingVersion);
+        }
+
+        let fields: Vec<&str> = rest.split('|').collect();
+        let expected: usize = match number {
+            "1" => 2,
+            "2" => 3,
+            "3" => 4,
+            _ => return Err(MigrateError::UnknownVersion(version.to_string())),
+        };
+        if fields.len() != expected {
+            return Err(MigrateError::WrongFieldCount {
+                expected,
+                got: fields.len(),
+            });
+        }
+
+        let name: String = fields[0].to_string();
+        Ok(match number {
+            "1" => AnyVersion::V1(LaptopV1 {
+                name,
+                size: field("size", fields[1])?,
+            }),
+            "2" => AnyVersion::V2(LaptopV2 {
+                name,
+                size_inches: field("size_inches", fields[1])?,
+                ram_gb: field("ram_gb", fields[2])?,
+            }),
+            _ => AnyVersion::V3(LaptopV3 {
+                name,
+                size_inches: field("size_inches", fields[1])?,
+                ram_gb: field("ram_gb", fields[2])?,
+                tags: fields[3]
+                    .split(',')
+                    .filter(|tag| !tag.is_empty())
+                    .map(String::from)
+                    .collect(),
+            }),
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Memory Estimate
+////////////////////////////////////////////////////////////////////////////////
+pub mod mem_estimate {
+    //! `size_of::<T>()` is only the inline part of a value, the bytes it takes in a local
+    //! variable or inside another struct: 24 for any `String` or `Vec` on a 64 bit target, the
+    //! pointer, length and capacity. What they own on the heap is not included. `DeepSize` adds
+    //! it up:
+    //!
+    //! ```text
+    //! deep_size = size_of::<Self>()            the inline part
+    //!           + the heap buffers it owns     whole capacity, not just the length in use
+    //!           + the heap part of whatever is stored in those buffers
+    //! ```
+    //!
+    //! The inline part of an element of a `Vec` is already counted in the buffer, so a
+    //! container adds only the `heap_size` of its elements. For a `HashMap` it is an estimate:
+    //! its table also has a control byte per bucket, and more buckets than `capacity()`.
+
+    use crate::builder::Player;
+    use crate::ids::Uid;
+    use crate::migrations::{LaptopV1, LaptopV3};
+    use std::collections::HashMap;
+    use std::mem::size_of;
+
+    pub trait DeepSize {
+        fn deep_size(&self) -> usize;
+
+        /// The bytes owned on the heap, `deep_size` without the inline part.
+        fn heap_size(&self) -> usize
+        where
+            Self: Sized,
+        {
+            self.deep_size() - size_of::<Self>()
+        }
+    }
+
+    /// Scalars own nothing, they are their inline part.
+    macro_rules! impl_deep_size_for_scalars {
+        ($($t:ty),*) => {
+            $(
+                impl DeepSize for $t {
+                    fn deep_size(&self) -> usize {
+                        size_of::<$t>()
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_deep_size_for_scalars!(
+        u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, bool, char
+    );
+
+    impl DeepSize for String {
+        fn deep_size(&self) -> usize {
+            size_of::<String>() + self.capacity()
+        }
+    }
+
+    impl<T: DeepSize> DeepSize for Vec<T> {
+        fn deep_size(&self) -> usize {
+            size_of::<Vec<T>>()
+                + self.capacity() * size_of::<T>()
+                + self.iter().map(T::heap_size).sum::<usize>()
+        }
+    }
+
+    impl<K: DeepSize, V: DeepSize> DeepSize for HashMap<K, V> {
+        fn deep_size(&self) -> usize {
+            size_of::<HashMap<K, V>>()
+                + self.capacity() * size_of::<(K, V)>()
+                + self
+                    .iter()
+                    .map(|(k, v)| k.heap_size() + v.heap_size())
+                    .sum::<usize>()
+        }
+    }
+
+    impl<T: DeepSize> DeepSize for Option<T> {
+        fn deep_size(&self) -> usize {
+            size_of::<Option<T>>() + self.as_ref().map_or(0, T::heap_size)
+        }
+    }
+
+    /// The boxed value is on the heap as a whole, inline part included.
+    impl<T: DeepSize> DeepSize for Box<T> {
+        fn deep_size(&self) -> usize {
+            size_of::<Box<T>>() + self.as_ref().deep_size()
+        }
+    }
+
+    impl DeepSize for Uid {
+        fn deep_size(&self) -> usize {
+            size_of::<Uid>()
+        }
+    }
+
+    impl DeepSize for LaptopV1 {
+        fn deep_size(&self) -> usize {
+            size_of::<LaptopV1>() + self.name.heap_size()
+        }
+    }
+
+    impl DeepSize for Player {
+        fn deep_size(&self) -> usize {
+            size_of::<Player>() + self.name.heap_size()
+        }
+    }
+
+    impl DeepSize for LaptopV3 {
+        fn deep_size(&self) -> usize {
+            size_of::<LaptopV3>() + self.name.heap_size() + self.tags.heap_size()
+        }
+    }
+
+    pub fn size_report<T: DeepSize>(label: &str, value: &T) -> String {
+        format!(
+            "{}: {} bytes ({} inline, {} on the heap)",
+            label,
+            value.deep_size(),
+            size_of::<T>(),
+            value.heap_size()
+        )
+    }
+
+    /// `(deep size if the buffer held only len elements, deep size)`. The spare capacity is
+    /// allocated all the same, so it is counted: the two differ by exactly its bytes.
+    pub fn len_vs_capacity<T: DeepSize>(v: &Vec<T>) -> (usize, usize) {
+        let spare: usize = (v.capacity() - v.len()) * size_of::<T>();
+        let reported: usize = v.deep_size();
+        assert!(reported >= spare);
+        (reported - spare, reported)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Builder
+////////////////////////////////////////////////////////////////////////////////
+pub mod builder {
+    //! A builder sets the fields of a struct one call at a time and creates it at the end:
+    //! `PlayerBuilder::new().name("rust").rank(1).build()`. Each setter consumes the builder and
+    //! returns it, so the calls chain.
+    //!
+    //! `RuntimePlayerBuilder` keeps the fields in `Option`s and `build` checks them, so a missing
+    //! field is an `Err` the caller has to handle. `PlayerBuilder` tracks the same thing in two
+    //! type parameters instead, like `session_types` tracks the state of a connection: each is
+    //! either `Set` or `Unset`, `name` turns the first one into `Set` and `rank` the second one,
+    //! and `build` is only implemented for `PlayerBuilder<Set, Set>`.
+    //!
+    //! ```text
+    //!                   +--name--> <Set, Unset> --rank--+
+    //! <Unset, Unset> ---+                               +--> <Set, Set> --build--> Player
+    //!                   +--rank--> <Unset, Set> --name--+
+    //! ```
+    //!
+    //! So `build` always succeeds, and forgetting a field is a compile error, error[E0599]: no
+    //! method named `build` found for struct `PlayerBuilder<Unset, Set>`:
+    //! ```compile_fail,E0599
+    //! use structs::builder::PlayerBuilder;
+    //!
+    //! let player = PlayerBuilder::new().rank(1).build();
+    //! ```
+    //!
+    //! The markers are zero-sized and only appear in `PhantomData`, the typestate builder is
+    //! exactly as big as its fields.
+
+    use std::marker::PhantomData;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Player {
+        pub name: String,
+        pub rank: usize,
+    }
+
+    pub struct Set;
+    pub struct Unset;
+
+    pub struct PlayerBuilder<NameSet, RankSet> {
+        // hold a placeholder until the matching parameter is `Set`, `build` never sees it
+        name: String,
+        rank: usize,
+        _state: PhantomData<(NameSet, RankSet)>,
+    }
+
+    impl PlayerBuilder<Unset, Unset> {
+        #[allow(clippy::new_without_default)]
+        pub fn new() -> Self {
+            PlayerBuilder {
+                name: String::new(),
+                rank: 0,
+                _state: PhantomData,
+            }
+        }
+    }
+
+    /// The setters exist in every state, so setting a field twice compiles: the last value wins.
+    impl<NameSet, RankSet> PlayerBuilder<NameSet, RankSet> {
+        pub fn name(self, name: &str) -> PlayerBuilder<Set, RankSet> {
+            PlayerBuilder {
+                name: name.to_string(),
+                rank: self.rank,
+                _state: PhantomData,
+            }
+        }
+
+        pub fn rank(self, rank: usize) -> PlayerBuilder<NameSet, Set> {
+            PlayerBuilder {
+                name: self.name,
+                rank,
+                _state: PhantomData,
+            }
+        }
+    }
+
+    impl PlayerBuilder<Set, Set> {
+        pub fn build(self) -> Player {
+            Player {
+                name: self.name,
+                rank: self.rank,
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum MissingField {
+        Name,
+        Rank,
+    }
+
+    #[derive(Default)]
+    pub struct RuntimePlayerBuilder {
+        name: Option<String>,
+        rank: Option<usize>,
+    }
+
+    impl RuntimePlayerBuilder {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn name(mut self, name: &str) -> Self {
+            self.name = Some(name.to_string());
+            self
+        }
+
+        pub fn rank(mut self, rank: usize) -> Self {
+            self.rank = Some(rank);
+            self
+        }
+
+        /// The name is checked first.
+        pub fn build(self) -> Result<Player, MissingField> {
+            Ok(Player {
+                name: self.name.ok_or(MissingField::Name)?,
+                rank: self.rank.ok_or(MissingField::Rank)?,
+            })
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod testing {
 
@@ -399,4 +1420,568 @@ pub mod testing {
         crate::memory_layout::size_of_struct_in_twenty_four_bytes();
         crate::memory_layout::size_of_struct_in_mixed_bytes();
     }
+
+    #[test]
+    fn run_session_types_typestate() {
+        use crate::session_types::{Closed, Connection, Ready};
+        assert_eq!(std::mem::size_of::<Connection<Ready>>(), 24);
+
+        let mut connection: Connection<Ready> = Connection::new().open();
+        connection.send("hello");
+        connection.send("rust");
+        let closed: Connection<Closed> = connection.close();
+        assert_eq!(
+            closed.report(),
+            &["open", "send hello", "send rust", "close"].map(String::from)
+        );
+    }
+
+    #[test]
+    fn run_session_types_runtime_checked() {
+        use crate::session_types::{ConnectionState, RuntimeConnection, WrongState};
+        let mut connection: RuntimeConnection = RuntimeConnection::new();
+        assert_eq!(
+            connection.send("early"),
+            Err(WrongState {
+                expected: ConnectionState::Ready,
+                actual: ConnectionState::Uninitialized,
+            })
+        );
+        connection.open().unwrap();
+        connection.send("hello").unwrap();
+        assert!(connection.report().is_err());
+        connection.close().unwrap();
+        assert_eq!(
+            connection.send("too late"),
+            Err(WrongState {
+                expected: ConnectionState::Ready,
+                actual: ConnectionState::Closed,
+            })
+        );
+        assert_eq!(
+            connection.report().unwrap(),
+            &["open", "send hello", "close"].map(String::from)
+        );
+    }
+
+    #[test]
+    fn run_compile_errors_accepted_versions() {
+        use crate::compile_errors::*;
+        let (laptop, new_laptop) = use_after_partial_move();
+        assert_ne!(laptop, new_laptop);
+        let (size, new_laptop) = use_after_update_syntax();
+        assert_eq!(size, 13);
+        assert_eq!(new_laptop, assign_to_immutable());
+        assert_eq!(read_private_field(), 1);
+    }
+
+    #[test]
+    fn run_soa_aos_equivalent() {
+        use crate::soa_aos::{equivalent, ParticleAos, ParticlesSoa, Simulate};
+        let mut aos: Vec<ParticleAos> = vec![];
+        let mut soa: ParticlesSoa = ParticlesSoa::new();
+        assert!(equivalent(&aos, &soa));
+
+        let particles: [ParticleAos; 3] = [
+            ParticleAos {
+                x: 0.0,
+                y: 0.0,
+                vx: 1.0,
+                vy: 2.0,
+            },
+            ParticleAos {
+                x: -1.5,
+                y: 4.0,
+                vx: 0.5,
+                vy: -3.0,
+            },
+            ParticleAos {
+                x: 2.0,
+                y: 1.0,
+                vx: -0.25,
+                vy: 0.1,
+            },
+        ];
+        for p in particles {
+            aos.push(p);
+            soa.push(p);
+        }
+        for dt in [0.1, 0.5, 1.0 / 3.0] {
+            aos.step(dt);
+            soa.step(dt);
+            assert!(equivalent(&aos, &soa));
+        }
+        assert_eq!(aos.bounding_box(), soa.bounding_box());
+
+        soa.push(particles[0]);
+        assert!(!equivalent(&aos, &soa));
+    }
+
+    #[test]
+    fn run_soa_aos_bounding_box() {
+        use crate::soa_aos::{ParticleAos, ParticlesSoa, Simulate};
+        let mut soa: ParticlesSoa = ParticlesSoa::new();
+        assert_eq!(soa.bounding_box(), None);
+        assert_eq!(Vec::<ParticleAos>::new().bounding_box(), None);
+
+        soa.push(ParticleAos {
+            x: 1.0,
+            y: -2.0,
+            vx: 2.0,
+            vy: 0.0,
+        });
+        assert_eq!(soa.bounding_box(), Some((1.0, -2.0, 1.0, -2.0)));
+        soa.push(ParticleAos {
+            x: -3.0,
+            y: 5.0,
+            vx: 0.0,
+            vy: -1.0,
+        });
+        soa.step(1.0);
+        assert_eq!(soa.bounding_box(), Some((-3.0, -2.0, 3.0, 4.0)));
+    }
+
+    #[test]
+    fn run_soa_aos_length_invariant() {
+        use crate::soa_aos::{bench_step, ParticleAos, ParticlesSoa};
+        let mut soa: ParticlesSoa = ParticlesSoa::new();
+        assert!(soa.is_empty());
+        for i in 0..10 {
+            soa.push(ParticleAos {
+                x: i as f32,
+                y: 0.0,
+                vx: 0.0,
+                vy: 0.0,
+            });
+            assert_eq!(soa.field_lens(), [i + 1; 4]);
+        }
+        assert_eq!(soa.len(), 10);
+        assert_eq!(soa.get(9).map(|p| p.x), Some(9.0));
+        assert_eq!(soa.get(10), None);
+
+        // bench_step asserts the two layouts agree after stepping
+        bench_step(100, 10);
+    }
+
+    #[test]
+    fn run_ids_display_from_str_round_trip() {
+        use crate::ids::{Uid, XorShift64};
+        let mut rng: XorShift64 = XorShift64(0x2545_f491_4f6c_dd1d);
+        for _ in 0..100 {
+            let uid: Uid = Uid::generate(&mut rng);
+            let text: String = uid.to_string();
+            assert_eq!(text.len(), 36);
+            assert_eq!(text.parse::<Uid>(), Ok(uid));
+            assert_eq!(text.to_uppercase().parse::<Uid>(), Ok(uid));
+            assert!(text.starts_with(&uid.short()));
+        }
+
+        let uid: Uid = "00112233-4455-6677-8899-aabbccddeeff".parse().unwrap();
+        assert_eq!(uid.as_bytes()[..3], [0x00, 0x11, 0x22]);
+        assert_eq!(uid.as_bytes()[15], 0xff);
+        assert_eq!(uid.short(), "00112233");
+    }
+
+    #[test]
+    fn run_ids_parse_errors() {
+        use crate::ids::{ParseUidError, Uid};
+        let parse = |s: &str| s.parse::<Uid>();
+        assert_eq!(parse(""), Err(ParseUidError::WrongLength { len: 0 }));
+        assert_eq!(
+            parse("00112233-4455-6677-8899-aabbccddeef"),
+            Err(ParseUidError::WrongLength { len: 35 })
+        );
+        // the length is counted in chars, not bytes
+        assert_eq!(
+            parse("00112233-4455-6677-8899-aabbccddeeé"),
+            Err(ParseUidError::WrongLength { len: 35 })
+        );
+        assert_eq!(
+            parse("00112233-4455-6677-8899aaabbccddeeff"),
+            Err(ParseUidError::MissingHyphen { position: 23 })
+        );
+        assert_eq!(
+            parse("0011223-34455-6677-8899-aabbccddeeff"),
+            Err(ParseUidError::InvalidHexDigit {
+                position: 7,
+                found: '-'
+            })
+        );
+        assert_eq!(
+            parse("00112233-4455-6677-8899-aabbccddeefg"),
+            Err(ParseUidError::InvalidHexDigit {
+                position: 35,
+                found: 'g'
+            })
+        );
+        assert_eq!(
+            parse("00112233-4455-6677-8899-aabbccddeefé")
+                .unwrap_err()
+                .to_string(),
+            "invalid hex digit 'é' at position 35"
+        );
+    }
+
+    #[test]
+    fn run_ids_deterministic_generation() {
+        use crate::ids::{RngLike, Uid, XorShift64};
+        use std::collections::HashSet;
+
+        let first: Vec<Uid> = {
+            let mut rng: XorShift64 = XorShift64(42);
+            (0..3).map(|_| Uid::generate(&mut rng)).collect()
+        };
+        let mut rng: XorShift64 = XorShift64(42);
+        let again: Vec<Uid> = (0..3).map(|_| Uid::generate(&mut rng)).collect();
+        assert_eq!(first, again);
+
+        // the bytes are the two next numbers of the generator, big endian
+        let mut rng: XorShift64 = XorShift64(42);
+        let (high, low): (u64, u64) = (rng.next_u64(), rng.next_u64());
+        assert_eq!(
+            first[0].to_string().replace('-', ""),
+            format!("{:016x}{:016x}", high, low)
+        );
+
+        let mut rng: XorShift64 = XorShift64(7);
+        let unique: HashSet<Uid> = (0..10_000).map(|_| Uid::generate(&mut rng)).collect();
+        assert_eq!(unique.len(), 10_000);
+    }
+
+    #[test]
+    fn run_ids_as_map_key() {
+        use crate::ids::{Uid, XorShift64};
+        use std::collections::HashMap;
+
+        let mut rng: XorShift64 = XorShift64(1);
+        let mut owners: HashMap<Uid, &str> = HashMap::new();
+        let mut uids: Vec<Uid> = vec![];
+        for name in ["ann", "bob", "cid"] {
+            let uid: Uid = Uid::generate(&mut rng);
+            owners.insert(uid, name);
+            uids.push(uid);
+        }
+        let parsed: Uid = uids[1].to_string().parse().unwrap();
+        assert_eq!(owners.get(&parsed), Some(&"bob"));
+        assert_eq!(owners.len(), 3);
+
+        // sorting the ids sorts their text form
+        let mut texts: Vec<String> = uids.iter().map(Uid::to_string).collect();
+        uids.sort();
+        texts.sort();
+        assert_eq!(
+            uids.iter().map(Uid::to_string).collect::<Vec<String>>(),
+            texts
+        );
+    }
+
+    #[test]
+    fn run_migrations_single_steps() {
+        use crate::migrations::{LaptopV1, LaptopV2, LaptopV3, DEFAULT_RAM_GB};
+        let v1: LaptopV1 = LaptopV1 {
+            name: String::from("air"),
+            size: 13,
+        };
+        let v2: LaptopV2 = v1.into();
+        assert_eq!(
+            v2,
+            LaptopV2 {
+                name: String::from("air"),
+                size_inches: 13.0,
+                ram_gb: DEFAULT_RAM_GB
+            }
+        );
+
+        let v2: LaptopV2 = LaptopV2 {
+            name: String::from("pro"),
+            size_inches: 14.2,
+            ram_gb: 32,
+        };
+        let v3: LaptopV3 = v2.into();
+        assert_eq!(
+            v3,
+            LaptopV3 {
+                name: String::from("pro"),
+                size_inches: 14.2,
+                ram_gb: 32,
+                tags: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn run_migrations_to_latest() {
+        use crate::migrations::{migrate_to_latest, AnyVersion, LaptopV1, LaptopV3};
+        let v1: LaptopV1 = LaptopV1 {
+            name: String::from("air"),
+            size: 13,
+        };
+        let latest: LaptopV3 = migrate_to_latest(AnyVersion::V1(v1));
+        assert_eq!(latest.name, "air");
+        assert_eq!(latest.size_inches, 13.0);
+        assert_eq!(latest.ram_gb, 8);
+        assert!(latest.tags.is_empty());
+
+        // migrating a record that is already the latest changes nothing
+        let v3: LaptopV3 = LaptopV3 {
+            name: String::from("pro"),
+            size_inches: 16.0,
+            ram_gb: 64,
+            tags: vec![String::from("work")],
+        };
+        assert_eq!(migrate_to_latest(AnyVersion::V3(v3.clone())), v3);
+        assert_eq!(migrate_to_latest(AnyVersion::V3(latest.clone())), latest);
+    }
+
+    #[test]
+    fn run_migrations_parse_versioned() {
+        use crate::migrations::{
+            migrate_to_latest, parse_versioned, AnyVersion, LaptopV1, LaptopV2, MigrateError,
+        };
+        assert_eq!(
+            parse_versioned("v1|air|13"),
+            Ok(AnyVersion::V1(LaptopV1 {
+                name: String::from("air"),
+                size: 13
+            }))
+        );
+        assert_eq!(
+            parse_versioned("v2|pro|14.2|32"),
+            Ok(AnyVersion::V2(LaptopV2 {
+                name: String::from("pro"),
+                size_inches: 14.2,
+                ram_gb: 32,
+            }))
+        );
+        let v3 = migrate_to_latest(parse_versioned("v3|pro|16|64|work,travel").unwrap());
+        assert_eq!(v3.tags, vec!["work", "travel"]);
+        let v3 = migrate_to_latest(parse_versioned("v3|pro|16|64|").unwrap());
+        assert!(v3.tags.is_empty());
+
+        assert_eq!(parse_versioned("air|13"), Err(MigrateError::MissingVersion));
+        assert_eq!(parse_versioned("v1"), Err(MigrateError::MissingVersion));
+        assert_eq!(
+            parse_versioned("v|air|13"),
+            Err(MigrateError::MissingVersion)
+        );
+        assert_eq!(
+            parse_versioned("V1|air|13"),
+            Err(MigrateError::MissingVersion)
+        );
+        assert_eq!(
+            parse_versioned("v4|air|13"),
+            Err(MigrateError::UnknownVersion(String::from("v4")))
+        );
+        assert_eq!(
+            parse_versioned("v2|air|13"),
+            Err(MigrateError::WrongFieldCount {
+                expected: 3,
+                got: 2
+            })
+        );
+        assert_eq!(
+            parse_versioned("v1|air|13.3"),
+            Err(MigrateError::InvalidField {
+                field: "size",
+                value: String::from("13.3")
+            })
+        );
+        assert_eq!(
+            parse_versioned("v2|air|13|lots").unwrap_err().to_string(),
+            "invalid ram_gb: \"lots\""
+        );
+    }
+
+    #[test]
+    fn run_mem_estimate_strings_and_vecs() {
+        use crate::mem_estimate::{len_vs_capacity, size_report, DeepSize};
+        use std::mem::size_of;
+
+        let mut name: String = String::with_capacity(100);
+        name.push_str("rust");
+        assert_eq!(name.deep_size(), size_of::<String>() + 100);
+        assert_eq!(name.heap_size(), 100);
+        assert_eq!(
+            size_report("name", &name),
+            format!(
+                "name: {} bytes ({} inline, 100 on the heap)",
+                100 + size_of::<String>(),
+                size_of::<String>()
+            )
+        );
+
+        // the vector's own buffer holds the inline parts, plus the heap part of each string
+        let words: Vec<String> = vec![String::from("a"), String::with_capacity(10), name];
+        let parts: usize = words.iter().map(DeepSize::heap_size).sum();
+        assert_eq!(parts, 1 + 10 + 100);
+        assert_eq!(
+            words.deep_size(),
+            size_of::<Vec<String>>() + words.capacity() * size_of::<String>() + parts
+        );
+
+        let mut numbers: Vec<u64> = Vec::with_capacity(10);
+        numbers.extend([1, 2, 3]);
+        assert_eq!(
+            len_vs_capacity(&numbers),
+            (size_of::<Vec<u64>>() + 24, size_of::<Vec<u64>>() + 80)
+        );
+        numbers.shrink_to_fit();
+        let (len_only, reported) = len_vs_capacity(&numbers);
+        assert_eq!(len_only, reported);
+    }
+
+    #[test]
+    fn run_mem_estimate_nested() {
+        use crate::mem_estimate::DeepSize;
+        use std::collections::HashMap;
+        use std::mem::size_of;
+
+        let none: Option<String> = None;
+        assert_eq!(none.deep_size(), size_of::<Option<String>>());
+        let some: Option<String> = Some(String::with_capacity(8));
+        assert_eq!(some.deep_size(), size_of::<Option<String>>() + 8);
+
+        let boxed: Box<String> = Box::new(String::with_capacity(8));
+        assert_eq!(
+            boxed.deep_size(),
+            size_of::<Box<String>>() + size_of::<String>() + 8
+        );
+
+        let mut inner: HashMap<u32, String> = HashMap::new();
+        inner.insert(1, String::with_capacity(16));
+        let mut outer: HashMap<String, HashMap<u32, String>> = HashMap::new();
+        // a clone allocates only what is used, it does not keep the spare capacity
+        outer.insert(String::with_capacity(4), inner.clone());
+        let cloned: &HashMap<u32, String> = &outer[""];
+        assert_eq!(cloned[&1].capacity(), 0);
+        assert_eq!(
+            inner.deep_size(),
+            size_of::<HashMap<u32, String>>() + inner.capacity() * size_of::<(u32, String)>() + 16
+        );
+        assert_eq!(
+            outer.deep_size(),
+            size_of::<HashMap<String, HashMap<u32, String>>>()
+                + outer.capacity() * size_of::<(String, HashMap<u32, String>)>()
+                + 4
+                + cloned.heap_size()
+        );
+    }
+
+    #[test]
+    fn run_mem_estimate_copy_types_and_structs() {
+        use crate::ids::{Uid, XorShift64};
+        use crate::mem_estimate::DeepSize;
+        use crate::migrations::{LaptopV1, LaptopV3};
+        use std::mem::size_of;
+
+        assert_eq!(7_u8.deep_size(), size_of::<u8>());
+        assert_eq!(7_u128.deep_size(), size_of::<u128>());
+        assert_eq!(1.5_f64.deep_size(), size_of::<f64>());
+        assert_eq!('c'.deep_size(), size_of::<char>());
+        assert_eq!(true.deep_size(), size_of::<bool>());
+        assert_eq!(Some(1_u32).deep_size(), size_of::<Option<u32>>());
+        assert_eq!(Uid::generate(&mut XorShift64(1)).deep_size(), 16);
+
+        let name: String = String::from("air");
+        let v1: LaptopV1 = LaptopV1 {
+            name: name.clone(),
+            size: 13,
+        };
+        assert_eq!(v1.deep_size(), size_of::<LaptopV1>() + name.capacity());
+        let v3: LaptopV3 = LaptopV3 {
+            name: name.clone(),
+            size_inches: 13.0,
+            ram_gb: 8,
+            tags: vec![String::from("work")],
+        };
+        assert_eq!(v3.heap_size(), name.capacity() + v3.tags.heap_size());
+        assert_eq!(
+            v3.tags.heap_size(),
+            v3.tags.capacity() * size_of::<String>() + 4
+        );
+    }
+
+    #[test]
+    fn run_mem_estimate_player() {
+        use crate::builder::{Player, PlayerBuilder};
+        use crate::mem_estimate::DeepSize;
+        use std::mem::size_of;
+
+        let player: Player = PlayerBuilder::new().name("rust").rank(1).build();
+        assert_eq!(
+            player.deep_size(),
+            size_of::<Player>() + player.name.capacity()
+        );
+    }
+
+    #[test]
+    fn run_builder_typestate() {
+        use crate::builder::{Player, PlayerBuilder, Set, Unset};
+        use std::mem::size_of;
+
+        let expected: Player = Player {
+            name: String::from("rust"),
+            rank: 1,
+        };
+        let half: PlayerBuilder<Set, Unset> = PlayerBuilder::new().name("rust");
+        assert_eq!(half.rank(1).build(), expected);
+        let half: PlayerBuilder<Unset, Set> = PlayerBuilder::new().rank(1);
+        assert_eq!(half.name("rust").build(), expected);
+
+        // the markers take no space
+        assert_eq!(
+            size_of::<PlayerBuilder<Set, Set>>(),
+            size_of::<String>() + size_of::<usize>()
+        );
+    }
+
+    #[test]
+    fn run_builder_setting_twice() {
+        use crate::builder::{Player, PlayerBuilder, RuntimePlayerBuilder};
+
+        let player: Player = PlayerBuilder::new()
+            .name("go")
+            .rank(2)
+            .name("rust")
+            .rank(1)
+            .build();
+        assert_eq!(player.name, "rust");
+        assert_eq!(player.rank, 1);
+
+        let runtime: Player = RuntimePlayerBuilder::new()
+            .name("go")
+            .rank(2)
+            .name("rust")
+            .rank(1)
+            .build()
+            .unwrap();
+        assert_eq!(runtime, player);
+    }
+
+    #[test]
+    fn run_builder_matches_runtime_builder() {
+        use crate::builder::{MissingField, PlayerBuilder, RuntimePlayerBuilder};
+
+        for (name, rank) in [("rust", 1), ("", 0), ("go", usize::MAX)] {
+            assert_eq!(
+                RuntimePlayerBuilder::new().name(name).rank(rank).build(),
+                Ok(PlayerBuilder::new().name(name).rank(rank).build())
+            );
+            assert_eq!(
+                RuntimePlayerBuilder::new().rank(rank).name(name).build(),
+                Ok(PlayerBuilder::new().rank(rank).name(name).build())
+            );
+        }
+
+        // what the typestate builder rejects at compile time
+        assert_eq!(
+            RuntimePlayerBuilder::new().rank(1).build(),
+            Err(MissingField::Name)
+        );
+        assert_eq!(
+            RuntimePlayerBuilder::new().name("rust").build(),
+            Err(MissingField::Rank)
+        );
+        assert_eq!(RuntimePlayerBuilder::new().build(), Err(MissingField::Name));
+    }
 }
@@ -214,6 +214,7 @@ pub mod understanding_of_valid_reference {
 }
 
 pub mod thinking_in_terms_of_lifetime {
+    use std::collections::HashSet;
 
     /// If we changed the implementation of the `longest` function to always return the first
     /// parameter rather than the longest string slice, we do not need to specify a lifetime on the
@@ -221,6 +222,43 @@ pub mod thinking_in_terms_of_lifetime {
     pub fn longest<'a>(x: &'a str, _y: &str) -> &'a str {
         x
     }
+
+    /// The shared prefix of `a` and `b`, measured in whole chars and returned as a slice of `a`.
+    /// Both strings are only read to compare them, but the result points into `a` alone, so only
+    /// `a`'s lifetime appears in the output and `b` may be dropped while the prefix is still in
+    /// use. Tying `b` to `'a` as well would compile too, it would just ask more of the caller.
+    pub fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+        let end: usize = a
+            .char_indices()
+            .zip(b.chars())
+            .find(|((_, ca), cb)| ca != cb)
+            .map_or(a.len().min(b.len()), |((i, _), _)| i);
+        // `end` may be `b.len()` when `b` is shorter, it is on a char boundary of `a` because the
+        // chars before it are the same in both
+        &a[..end]
+    }
+
+    /// The lines of `a` that also occur in `b`, in the order of `a`, a line repeated in `a` is
+    /// kept each time. `b` is only looked up, the result borrows from `a`, so `b` needs no
+    /// lifetime either.
+    pub fn overlapping_lines<'a>(a: &'a str, b: &str) -> Vec<&'a str> {
+        let in_b: HashSet<&str> = b.lines().collect();
+        a.lines().filter(|line| in_b.contains(line)).collect()
+    }
+
+    /// Words taken alternately from `a` and `b`, then the rest of the longer one. The result
+    /// holds words of both strings, which is only possible if both live for the same `'a`.
+    pub fn interleave_words<'a>(a: &'a str, b: &'a str) -> Vec<&'a str> {
+        let mut words_a = a.split_whitespace();
+        let mut words_b = b.split_whitespace();
+        let mut words: Vec<&'a str> = vec![];
+        loop {
+            match (words_a.next(), words_b.next()) {
+                (None, None) => return words,
+                (x, y) => words.extend(x.into_iter().chain(y)),
+            }
+        }
+    }
 }
 
 pub mod lifetime_annotation_in_struct_definitions {
@@ -417,6 +455,216 @@ pub mod implicit_lifetime_parameter_of_self {
     }
 }
 
+pub mod splitting_lifetimes {
+    //! When a function may return a reference into either of two inputs, the signature has to
+    //! tie both inputs to the same lifetime `'a`. The caller then has to keep *both* containers
+    //! alive for as long as the result is used, even when the match actually came from the
+    //! longer-lived one.
+    //!
+    //! ```compile_fail,E0597
+    //! use lifetime::splitting_lifetimes::find_in_either;
+    //!
+    //! let primary: Vec<String> = vec![String::from("rust")];
+    //! let found: Option<&str>;
+    //! {
+    //!     let fallback: Vec<String> = vec![String::from("go")];
+    //!     // error[E0597]: `fallback` does not live long enough
+    //!     found = find_in_either(&primary, &fallback, "rust");
+    //! }
+    //! println!("{:?}", found);
+    //! ```
+    //!
+    //! If the caller only needs to *know* that the fallback matched, the API can be split so that
+    //! the returned reference borrows from `primary` alone, and `fallback` gets its own, unrelated
+    //! lifetime. The same call shape now compiles.
+    //!
+    //! ```
+    //! use lifetime::splitting_lifetimes::find_primary_only;
+    //!
+    //! let primary: Vec<String> = vec![String::from("rust")];
+    //! let found: Option<&str>;
+    //! {
+    //!     let fallback: Vec<String> = vec![String::from("go")];
+    //!     let (hit, in_fallback) = find_primary_only(&primary, &fallback, "rust");
+    //!     assert!(!in_fallback);
+    //!     found = hit;
+    //! }
+    //! assert_eq!(found, Some("rust"));
+    //! ```
+
+    /// Search `primary` first, then `fallback`. Because the result may point into either slice,
+    /// both share the lifetime `'a`.
+    pub fn find_in_either<'a>(
+        primary: &'a [String],
+        fallback: &'a [String],
+        needle: &str,
+    ) -> Option<&'a str> {
+        primary
+            .iter()
+            .chain(fallback.iter())
+            .find(|s| s.as_str() == needle)
+            .map(|s| s.as_str())
+    }
+
+    /// Only the reference into `primary` is returned, so `fallback` needs no named lifetime. The
+    /// boolean reports whether `fallback` would have matched, without borrowing from it.
+    pub fn find_primary_only<'p>(
+        primary: &'p [String],
+        fallback: &[String],
+        needle: &str,
+    ) -> (Option<&'p str>, bool) {
+        let hit = primary
+            .iter()
+            .find(|s| s.as_str() == needle)
+            .map(|s| s.as_str());
+        let in_fallback = fallback.iter().any(|s| s == needle);
+        (hit, in_fallback)
+    }
+}
+
+pub mod compile_errors {
+    //! The cases left commented out in `borrow_checker`, the `error_nth1` and `error_case`
+    //! functions, and `implicit_lifetime_parameter_of_self`, as `compile_fail` doc tests:
+    //! `cargo test` fails if one of them starts compiling. Each function body is the accepted
+    //! version, where the reference is used while the value it points to is still alive.
+
+    use super::lifetime_annotation_in_function_signature::longest;
+
+    /// From `borrow_checker`, error[E0597]: `x` does not live long enough.
+    ///
+    /// ```compile_fail,E0597
+    /// let r;
+    /// {
+    ///     let x = 5;
+    ///     r = &x;
+    /// }
+    /// println!("r: {}", r);
+    /// ```
+    pub fn outlived_local() -> i32 {
+        let x = 5;
+        let r = &x;
+        *r
+    }
+
+    /// From `understanding_of_valid_reference::error_nth1`, error[E0597]: `s2` does not live long
+    /// enough. The result of `longest` may be `r2`, so it can't be used after `s2` is dropped.
+    ///
+    /// ```compile_fail,E0597
+    /// use lifetime::lifetime_annotation_in_function_signature::longest;
+    ///
+    /// let r: &str;
+    /// let s1: String = String::from("rust");
+    /// {
+    ///     let s2: String = String::from("c++");
+    ///     let r1: &str = s1.as_str();
+    ///     let r2: &str = s2.as_str();
+    ///     r = longest(r1, r2);
+    /// }
+    /// println!("{}", r);
+    /// ```
+    pub fn longest_outlives_argument() -> usize {
+        let s1: String = String::from("rust");
+        let s2: String = String::from("c++");
+        let r: &str = longest(s1.as_str(), s2.as_str());
+        r.len()
+    }
+
+    /// From `lifetime_annotation_in_struct_definitions::error_nth1`, error[E0597]: `novel` does not
+    /// live long enough.
+    ///
+    /// ```compile_fail,E0597
+    /// struct ImportantExcerpt<'a> {
+    ///     part: &'a str,
+    /// }
+    ///
+    /// let ie: ImportantExcerpt;
+    /// {
+    ///     let novel: String = String::from("rust will be the best language in the future.");
+    ///     ie = ImportantExcerpt { part: &novel[..4] };
+    /// }
+    /// println!("{}", ie.part);
+    /// ```
+    pub fn struct_outlives_field() -> String {
+        struct ImportantExcerpt<'a> {
+            part: &'a str,
+        }
+
+        let novel: String = String::from("rust will be the best language in the future.");
+        let ie: ImportantExcerpt = ImportantExcerpt { part: &novel[..4] };
+        ie.part.to_string()
+    }
+
+    /// From `dynamic_inferred_lifetime::error_case`, error[E0597]: `s` does not live long enough.
+    /// Pushing `&s` narrows the inferred lifetime of the whole vector to the inner scope.
+    ///
+    /// ```compile_fail,E0597
+    /// struct Context<'a> {
+    ///     var: Vec<&'a str>,
+    /// }
+    ///
+    /// let mut v = Context { var: vec![] };
+    /// v.var.push("rust");
+    /// {
+    ///     let s: String = String::from("go");
+    ///     v.var.push(&s);
+    /// }
+    /// println!("{:?}", v.var);
+    /// ```
+    pub fn inferred_lifetime_narrowed() -> Vec<String> {
+        struct Context<'a> {
+            var: Vec<&'a str>,
+        }
+
+        let mut v = Context { var: vec![] };
+        v.var.push("rust");
+        let s: String = String::from("go");
+        v.var.push(&s);
+        v.var.iter().map(|s| s.to_string()).collect()
+    }
+
+    /// From `implicit_lifetime_parameter_of_self`, error[E0597]: `ctx` does not live long enough.
+    /// By elision the result of `danger_mode` borrows from `&self`, not from the `'a` data.
+    ///
+    /// ```compile_fail,E0597
+    /// struct Context<'a> {
+    ///     name: &'a str,
+    /// }
+    ///
+    /// impl<'a> Context<'a> {
+    ///     fn danger_mode(&self) -> &str {
+    ///         self.name
+    ///     }
+    /// }
+    ///
+    /// let s: String = String::from("rust");
+    /// let danger_ref: &str;
+    /// {
+    ///     let ctx = Context { name: &s };
+    ///     danger_ref = ctx.danger_mode();
+    /// }
+    /// println!("danger: {}", danger_ref);
+    /// ```
+    pub fn elided_self_lifetime() -> String {
+        struct Context<'a> {
+            name: &'a str,
+        }
+
+        impl<'a> Context<'a> {
+            fn safety_mode(&self) -> &'a str {
+                self.name
+            }
+        }
+
+        let s: String = String::from("rust");
+        let safety_ref: &str;
+        {
+            let ctx = Context { name: &s };
+            safety_ref = ctx.safety_mode();
+        }
+        safety_ref.to_string()
+    }
+}
+
 struct Context<'a>(&'a str);
 
 struct Parser<'a> {
@@ -432,3 +680,132 @@ impl<'a> Parser<'a> {
 fn parse_context<'a>(context: &'a Context<'a>) -> Result<(), &'a str> {
     Parser { context }.parse()
 }
+
+#[cfg(test)]
+mod testing {
+    use super::splitting_lifetimes::*;
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn run_find_in_primary() {
+        let primary = words(&["rust", "go"]);
+        let fallback = words(&["c++"]);
+        assert_eq!(find_in_either(&primary, &fallback, "go"), Some("go"));
+        assert_eq!(
+            find_primary_only(&primary, &fallback, "go"),
+            (Some("go"), false)
+        );
+    }
+
+    #[test]
+    fn run_find_only_in_fallback() {
+        let primary = words(&["rust"]);
+        let fallback = words(&["c++", "java"]);
+        assert_eq!(find_in_either(&primary, &fallback, "java"), Some("java"));
+        assert_eq!(find_primary_only(&primary, &fallback, "java"), (None, true));
+    }
+
+    #[test]
+    fn run_find_in_neither() {
+        let primary = words(&["rust"]);
+        let fallback = words(&["c++"]);
+        assert_eq!(find_in_either(&primary, &fallback, "python"), None);
+        assert_eq!(
+            find_primary_only(&primary, &fallback, "python"),
+            (None, false)
+        );
+    }
+
+    #[test]
+    fn run_find_in_both_primary_wins() {
+        let primary = words(&["rust"]);
+        let fallback = words(&["rust"]);
+
+        let found = find_in_either(&primary, &fallback, "rust").unwrap();
+        assert!(std::ptr::eq(found, primary[0].as_str()));
+        assert!(!std::ptr::eq(found, fallback[0].as_str()));
+
+        let (hit, in_fallback) = find_primary_only(&primary, &fallback, "rust");
+        assert!(std::ptr::eq(hit.unwrap(), primary[0].as_str()));
+        assert!(in_fallback);
+    }
+
+    #[test]
+    fn run_compile_errors_accepted_versions() {
+        use crate::compile_errors::*;
+        assert_eq!(outlived_local(), 5);
+        assert_eq!(longest_outlives_argument(), 4);
+        assert_eq!(struct_outlives_field(), "rust");
+        assert_eq!(inferred_lifetime_narrowed(), vec!["rust", "go"]);
+        assert_eq!(elided_self_lifetime(), "rust");
+    }
+
+    #[test]
+    fn run_common_prefix_borrows_from_a() {
+        use crate::thinking_in_terms_of_lifetime::common_prefix;
+        let a: String = String::from("rustacean");
+        let prefix: &str;
+        {
+            let b: String = String::from("rusty");
+            prefix = common_prefix(&a, &b);
+        }
+        // `b` is gone, the prefix still points into `a`
+        assert_eq!(prefix, "rust");
+        assert!(std::ptr::eq(prefix.as_ptr(), a.as_ptr()));
+
+        assert_eq!(common_prefix("rust", "rustacean"), "rust");
+        assert_eq!(common_prefix("rust", "go"), "");
+        assert_eq!(common_prefix("", "rust"), "");
+        assert_eq!(common_prefix("rust", ""), "");
+    }
+
+    #[test]
+    fn run_common_prefix_multibyte() {
+        use crate::thinking_in_terms_of_lifetime::common_prefix;
+        assert_eq!(common_prefix("héllo", "hélp"), "hél");
+        // 'é' and 'ê' share their first UTF-8 byte, the prefix stops before the whole char
+        assert_eq!("é".as_bytes()[0], "ê".as_bytes()[0]);
+        assert_eq!(common_prefix("thé", "thê"), "th");
+        assert_eq!(common_prefix("中国人", "中国"), "中国");
+        assert_eq!(common_prefix("🦀🦀", "🦀"), "🦀");
+    }
+
+    #[test]
+    fn run_overlapping_lines() {
+        use crate::thinking_in_terms_of_lifetime::overlapping_lines;
+        let a: String = String::from("rust\ngo\nzig\nrust");
+        let lines: Vec<&str>;
+        {
+            let b: String = String::from("zig\nrust\nc");
+            lines = overlapping_lines(&a, &b);
+        }
+        assert_eq!(lines, vec!["rust", "zig", "rust"]);
+        assert!(lines
+            .iter()
+            .all(|line| a.as_bytes().as_ptr_range().contains(&line.as_ptr())));
+
+        assert!(overlapping_lines("rust", "").is_empty());
+        assert!(overlapping_lines("", "rust").is_empty());
+    }
+
+    #[test]
+    fn run_interleave_words() {
+        use crate::thinking_in_terms_of_lifetime::interleave_words;
+        assert_eq!(
+            interleave_words("a b c d", "1 2"),
+            vec!["a", "1", "b", "2", "c", "d"]
+        );
+        assert_eq!(interleave_words("a", " 1  2 3 "), vec!["a", "1", "2", "3"]);
+        assert_eq!(interleave_words("", "1"), vec!["1"]);
+        assert!(interleave_words("", "  ").is_empty());
+
+        let a: String = String::from("x y");
+        let b: String = String::from("z");
+        let words: Vec<&str> = interleave_words(&a, &b);
+        assert!(std::ptr::eq(words[0], &a[..1]));
+        assert!(std::ptr::eq(words[1], &b[..]));
+    }
+}
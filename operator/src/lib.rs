@@ -1,7 +1,275 @@
 //! # Numeric Operations
 //! `rust` supports the basic mathematical operations you’d expect for all of the number types:
-//! addition, subtraction, multiplication, division, and remainder. Integer division rounds down to
-//! the nearest integer.
+//! addition, subtraction, multiplication, division, and remainder. Integer division truncates
+//! toward zero to the nearest integer.
+
+pub mod euclidean {
+    //! `/` and `%` on integers truncate: the quotient is rounded toward zero, and the remainder
+    //! takes the sign of the dividend, so that `a == (a / b) * b + a % b`:
+    //!
+    //! ```text
+    //! -5 / 4 == -1      -5 % 4 == -1
+    //! ```
+    //!
+    //! Euclidean division keeps the same identity but chooses the remainder in `0..|b|`, never
+    //! negative. For a negative dividend the quotient is then one lower (for a positive divisor):
+    //!
+    //! ```text
+    //! (-5).div_euclid(4) == -2      (-5).rem_euclid(4) == 3
+    //! ```
+    //!
+    //! `rem_euclid` is what "modulo" usually means, e.g. to wrap an index or an angle into a range,
+    //! where `%` would give a negative result. For non-negative operands both agree.
+    //!
+    //! Dividing by zero panics with either, the `checked_` variants return `None` instead, as they
+    //! do for the one overflowing case `i32::MIN / -1`.
+
+    pub fn truncating() {
+        let (a, b): (i32, i32) = (-5, 4);
+        assert_eq!(a / b, -1);
+        assert_eq!(a % b, -1);
+        assert_eq!((a / b) * b + a % b, a);
+        assert_eq!(-a % -b, 1);
+    }
+
+    pub fn euclid() {
+        assert_eq!((-5_i32).div_euclid(4), -2);
+        assert_eq!((-5_i32).rem_euclid(4), 3);
+        assert_eq!((-5_i32).div_euclid(4) * 4 + (-5_i32).rem_euclid(4), -5);
+        assert_eq!(5_i32.rem_euclid(-4), 1);
+        assert_eq!(5_i32.div_euclid(4), 5 / 4);
+    }
+
+    pub fn checked() {
+        assert_eq!(5_i32.checked_div(0), None);
+        assert_eq!(5_i32.checked_rem_euclid(0), None);
+        assert_eq!(i32::MIN.checked_div(-1), None);
+        assert_eq!(5_i32.checked_div(2), Some(2));
+    }
+}
+
+pub mod compound_assign {
+    //! `a op= b` updates `a` in place. For the primitive types it means `a = a op b`, evaluated
+    //! once, and for other types each operator is a trait of `std::ops` taking `&mut self`:
+    //!
+    //! ```text
+    //! +=  AddAssign    -=  SubAssign    *=  MulAssign    /=  DivAssign    %=  RemAssign
+    //! &=  BitAndAssign |=  BitOrAssign  ^=  BitXorAssign <<= ShlAssign    >>= ShrAssign
+    //! ```
+    //!
+    //! The `*Assign` traits are independent from `Add` and friends: implementing `Add` does not
+    //! give `+=`, and the right-hand side type is a generic parameter, `Rhs = Self` by default, so
+    //! a type can accept something other than itself. A compound assignment evaluates to `()`.
+
+    use std::ops::AddAssign;
+
+    pub fn arithmetic() {
+        let mut n: i32 = 10;
+        n += 5;
+        assert_eq!(n, 15);
+        n -= 3;
+        assert_eq!(n, 12);
+        n *= 4;
+        assert_eq!(n, 48);
+        n /= 5;
+        assert_eq!(n, 9);
+        n %= 4;
+        assert_eq!(n, 1);
+    }
+
+    pub fn bitwise() {
+        let mut flags: u8 = 0b1100;
+        flags &= 0b1010;
+        assert_eq!(flags, 0b1000);
+        flags |= 0b0011;
+        assert_eq!(flags, 0b1011);
+        flags ^= 0b0001;
+        assert_eq!(flags, 0b1010);
+        flags <<= 2;
+        assert_eq!(flags, 0b10_1000);
+        flags >>= 3;
+        assert_eq!(flags, 0b101);
+    }
+
+    #[derive(Debug, Default, PartialEq)]
+    pub struct Counter {
+        pub count: u64,
+    }
+
+    /// `Rhs` is `u64` here, so `counter += 1` adds a plain number.
+    impl AddAssign<u64> for Counter {
+        fn add_assign(&mut self, rhs: u64) {
+            self.count += rhs;
+        }
+    }
+
+    /// With `Rhs = Self`, two counters merge with `+=`.
+    impl AddAssign for Counter {
+        fn add_assign(&mut self, rhs: Counter) {
+            self.count += rhs.count;
+        }
+    }
+
+    pub fn counter() {
+        let mut counter: Counter = Counter::default();
+        counter += 1;
+        assert_eq!(counter.count, 1);
+        counter += 41;
+        assert_eq!(counter.count, 42);
+        counter += Counter { count: 8 };
+        assert_eq!(counter, Counter { count: 50 });
+    }
+}
+
+pub mod unary_operators {
+    //! The two overloadable unary operators are `-x`, the trait `std::ops::Neg`, and `!x`,
+    //! `std::ops::Not`. Both take the operand by value and name their result type `Output`, which
+    //! need not be `Self`. `-x` on a type without `Neg` does not compile, unsigned integers
+    //! included.
+    //!
+    //! Taking `self` by value is free for a `Copy` type, for others `impl Neg for &T` lets `-&x`
+    //! work without giving `x` up.
+
+    use std::ops::Neg;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Vector2 {
+        pub x: f64,
+        pub y: f64,
+    }
+
+    impl Neg for Vector2 {
+        type Output = Vector2;
+
+        fn neg(self) -> Vector2 {
+            Vector2 {
+                x: -self.x,
+                y: -self.y,
+            }
+        }
+    }
+}
+
+pub mod index_operators {
+    //! `container[index]` is backed by two traits of `std::ops`:
+    //!
+    //! * `Index<Idx>` with `fn index(&self, index: Idx) -> &Self::Output`, used when the place is
+    //!   read, and
+    //! * `IndexMut<Idx>` with `fn index_mut(&mut self, index: Idx) -> &mut Self::Output`, used
+    //!   when it is written or mutably borrowed. It requires `Index`, the two share `Output`.
+    //!
+    //! Both return references, `m[i]` is sugar for `*m.index(i)`, so reading a non-`Copy` element
+    //! by value moves out of a borrow and does not compile. `Idx` is a type parameter, a type can
+    //! be indexed by several index types, here a `(row, column)` tuple. There is no fallible
+    //! form: an out of range index has to panic, as it does for slices.
+
+    use std::ops::{Index, IndexMut};
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Matrix2x2 {
+        data: [[f64; 2]; 2],
+    }
+
+    impl Matrix2x2 {
+        pub fn new(data: [[f64; 2]; 2]) -> Self {
+            Matrix2x2 { data }
+        }
+    }
+
+    impl Index<(usize, usize)> for Matrix2x2 {
+        type Output = f64;
+
+        fn index(&self, (row, column): (usize, usize)) -> &f64 {
+            &self.data[row][column]
+        }
+    }
+
+    impl IndexMut<(usize, usize)> for Matrix2x2 {
+        fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut f64 {
+            &mut self.data[row][column]
+        }
+    }
+}
+
+pub mod approx_eq {
+    //! `0.1 + 0.2 == 0.3` is `false`: none of the three has an exact binary representation, and
+    //! the rounded sum lands one ulp above the rounded `0.3`. Comparing floats usually means
+    //! comparing within a tolerance, and `PartialEq` lets `==` do that for a wrapper type.
+    //!
+    //! Such an equality is not an equivalence relation. It is reflexive and symmetric, but not
+    //! transitive: `a` may be close to `b` and `b` close to `c` while `a` is not close to `c`. So
+    //! `Approx` must not implement `Eq`, which promises all three. Without `Eq` it can't be a
+    //! `HashMap` key either, and no `Hash` could be consistent with it anyway: equal values must
+    //! hash equally, and following the chain of close values, that would force every value to the
+    //! same hash.
+
+    /// The absolute tolerance `Approx` compares with.
+    pub const EPSILON: f64 = 1e-9;
+
+    #[derive(Debug, Clone, Copy)]
+    pub struct Approx(pub f64);
+
+    impl PartialEq for Approx {
+        fn eq(&self, other: &Approx) -> bool {
+            (self.0 - other.0).abs() <= EPSILON
+        }
+    }
+}
+
+pub mod ranges {
+    //! `..` and `..=` build values of six `std::ops` types:
+    //!
+    //! ```text
+    //! 1..5    Range<i32>             1 <= x < 5
+    //! 1..=5   RangeInclusive<i32>    1 <= x <= 5
+    //! ..5     RangeTo<i32>                x < 5
+    //! ..=5    RangeToInclusive<i32>       x <= 5
+    //! 5..     RangeFrom<i32>         5 <= x
+    //! ..      RangeFull              everything
+    //! ```
+    //!
+    //! All but `RangeFull` have `contains`, and all can index slices and strings. Only the ones
+    //! with a start are iterators, since iteration needs somewhere to begin: `Range` and
+    //! `RangeInclusive` are also `DoubleEndedIterator`, so they can be reversed, while `RangeFrom`
+    //! is endless and has to be cut with `take` or a `break`.
+    //!
+    //! `a..b` with `a >= b` is empty, and so is `a..=b` with `a > b`: a range never counts down,
+    //! a descending sequence is written `(1..5).rev()`.
+
+    pub fn contains() {
+        assert!((1..5).contains(&3));
+        assert!(!(1..5).contains(&5));
+        assert!((1..=5).contains(&5));
+        assert!((..5).contains(&-100));
+        assert!(!(..5).contains(&5));
+        assert!((5..).contains(&i32::MAX));
+    }
+
+    pub fn iterate() {
+        assert_eq!((1..=5).sum::<i32>(), 15);
+        assert_eq!((1..5).sum::<i32>(), 10);
+        assert_eq!((1..5).rev().collect::<Vec<i32>>(), vec![4, 3, 2, 1]);
+        assert_eq!((1..=5).rev().collect::<Vec<i32>>(), vec![5, 4, 3, 2, 1]);
+        assert_eq!((5..).take(3).collect::<Vec<i32>>(), vec![5, 6, 7]);
+        assert_eq!((1..5).len(), 4);
+    }
+
+    pub fn empty() {
+        let start: i32 = 1;
+        assert!((start..1).is_empty());
+        assert_eq!((start..1).count(), 0);
+        assert!(!(start..=1).is_empty());
+        assert_eq!((start + 4..start).count(), 0);
+    }
+
+    pub fn slicing() {
+        let s: &str = "operator";
+        assert_eq!(&s[..4], "oper");
+        assert_eq!(&s[4..], "ator");
+        assert_eq!(&s[2..=3], "er");
+        assert_eq!(&s.to_string()[..], s);
+    }
+}
 
 #[cfg(test)]
 mod testing {
@@ -15,11 +283,103 @@ mod testing {
 
         // division
         assert_eq!(2 / 3, 0);
-        assert_eq!(-5 / 4, -0);
+        assert_eq!(-5 / 4, -1);
 
         // remainder
         assert_eq!(43 % 5, 3);
     }
+
+    #[test]
+    fn run_euclidean_truncating() {
+        crate::euclidean::truncating();
+    }
+
+    #[test]
+    fn run_euclidean_euclid() {
+        crate::euclidean::euclid();
+    }
+
+    #[test]
+    fn run_euclidean_checked() {
+        crate::euclidean::checked();
+    }
+
+    #[test]
+    fn run_compound_assign_arithmetic() {
+        crate::compound_assign::arithmetic();
+    }
+
+    #[test]
+    fn run_compound_assign_bitwise() {
+        crate::compound_assign::bitwise();
+    }
+
+    #[test]
+    fn run_compound_assign_counter() {
+        crate::compound_assign::counter();
+    }
+
+    #[test]
+    fn run_unary_operators_neg() {
+        use crate::unary_operators::Vector2;
+        let v: Vector2 = Vector2 { x: 1.0, y: -2.0 };
+        assert_eq!(-v, Vector2 { x: -1.0, y: 2.0 });
+        assert_eq!(-(-v), v);
+    }
+
+    #[test]
+    fn run_index_operators_matrix() {
+        use crate::index_operators::Matrix2x2;
+        let mut m: Matrix2x2 = Matrix2x2::new([[1.0, 2.0], [3.0, 4.0]]);
+        assert_eq!(m[(0, 1)], 2.0);
+        assert_eq!(m[(1, 0)], 3.0);
+
+        m[(0, 1)] = 5.0;
+        m[(1, 1)] *= 2.0;
+        assert_eq!(m[(0, 1)], 5.0);
+        assert_eq!(m, Matrix2x2::new([[1.0, 5.0], [3.0, 8.0]]));
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn run_index_operators_out_of_range() {
+        let m = crate::index_operators::Matrix2x2::new([[0.0; 2]; 2]);
+        let _ = m[(2, 0)];
+    }
+
+    #[test]
+    fn run_approx_eq() {
+        use crate::approx_eq::{Approx, EPSILON};
+        let (a, b): (f64, f64) = (0.1, 0.2);
+        assert_ne!(a + b, 0.3);
+        assert_eq!(Approx(a + b), Approx(0.3));
+        assert_ne!(Approx(1.0), Approx(1.0 + 1e-6));
+
+        // not transitive
+        let (x, y, z) = (Approx(0.0), Approx(0.6 * EPSILON), Approx(1.2 * EPSILON));
+        assert!(x == y && y == z);
+        assert!(x != z);
+    }
+
+    #[test]
+    fn run_ranges_contains() {
+        crate::ranges::contains();
+    }
+
+    #[test]
+    fn run_ranges_iterate() {
+        crate::ranges::iterate();
+    }
+
+    #[test]
+    fn run_ranges_empty() {
+        crate::ranges::empty();
+    }
+
+    #[test]
+    fn run_ranges_slicing() {
+        crate::ranges::slicing();
+    }
 }
 
 // TODO
@@ -132,6 +132,80 @@ mod dangling_reference {
     //! ```
 }
 
+pub mod compile_errors {
+    //! The cases `mutable_reference::strong_restriction` and `dangling_reference` describe as
+    //! rejected, as `compile_fail` doc tests: `cargo test` fails if one of them starts compiling.
+    //! Each function body is the accepted version.
+
+    /// Two mutable borrows alive at the same time, error[E0499]: cannot borrow `s` as mutable more
+    /// than once at a time.
+    ///
+    /// ```compile_fail,E0499
+    /// let mut s: String = String::from("rust");
+    /// let r1: &mut String = &mut s;
+    /// let r2: &mut String = &mut s;
+    /// println!("{}", r2);
+    /// println!("{}", r1);
+    /// ```
+    pub fn two_mutable_borrows() {
+        let mut s: String = String::from("rust");
+        let r1: &mut String = &mut s;
+        println!("{}", r1);
+        let r2: &mut String = &mut s;
+        println!("{}", r2);
+    }
+
+    /// A mutable borrow while a shared one is still used, error[E0502]: cannot borrow `s` as
+    /// mutable because it is also borrowed as immutable.
+    ///
+    /// ```compile_fail,E0502
+    /// let mut s: String = String::from("rust");
+    /// let r1: &String = &s;
+    /// let r2: &mut String = &mut s;
+    /// println!("{}", r2);
+    /// println!("{}", r1);
+    /// ```
+    pub fn mutable_while_shared() {
+        let mut s: String = String::from("rust");
+        let r1: &String = &s;
+        println!("{}", r1);
+        let r2: &mut String = &mut s;
+        println!("{}", r2);
+    }
+
+    /// A shared borrow while a mutable one is still used, error[E0502]: cannot borrow `s` as
+    /// immutable because it is also borrowed as mutable.
+    ///
+    /// ```compile_fail,E0502
+    /// let mut s: String = String::from("rust");
+    /// let r1: &mut String = &mut s;
+    /// let r2: &String = &s;
+    /// println!("{}", r2);
+    /// println!("{}", r1);
+    /// ```
+    pub fn shared_while_mutable() {
+        let mut s: String = String::from("rust");
+        let r1: &mut String = &mut s;
+        println!("{}", r1);
+        let r2: &String = &s;
+        println!("{}", r2);
+    }
+
+    /// Returning a reference to a local, error[E0106]: missing lifetime specifier. There is no
+    /// input to borrow from, and the local is dropped at the end of the function, so the fix is to
+    /// return the `String` itself.
+    ///
+    /// ```compile_fail,E0106
+    /// fn dangle() -> &String {
+    ///     let s = "rust".to_string();
+    ///     &s
+    /// }
+    /// ```
+    pub fn no_dangle() -> String {
+        "rust".to_string()
+    }
+}
+
 #[cfg(test)]
 mod testing {
     #[test]
@@ -139,4 +213,12 @@ mod testing {
         crate::mutable_reference::weak_restriction();
         crate::mutable_reference::strong_restriction();
     }
+
+    #[test]
+    fn run_compile_errors_accepted_versions() {
+        crate::compile_errors::two_mutable_borrows();
+        crate::compile_errors::mutable_while_shared();
+        crate::compile_errors::shared_while_mutable();
+        assert_eq!(crate::compile_errors::no_dangle(), "rust");
+    }
 }
@@ -0,0 +1,415 @@
+//! A command-line parser driven by a list of `ArgSpec`s, and a trait to build a typed struct
+//! from what it parsed.
+//!
+//! The accepted syntax:
+//!
+//! ```text
+//! --name           a flag, or an option whose value is the next argument
+//! --name=value     an option with its value in the same argument
+//! -abc             the one-letter flags a, b and c; the last may be an option taking the next
+//!                  argument as its value
+//! --               every later argument is positional, even if it starts with `-`
+//! -                positional, by convention it means stdin
+//! ```
+//!
+//! A spec named with one letter is given as `-x` or `--x`, a longer name only as `--name`.
+//!
+//! Without a derive macro, a struct registers its fields by hand: `FromArgs::specs` lists them
+//! and `FromArgs::from_parsed` reads each one back and converts it, so the name of a field is
+//! written twice, in the spec and in the lookup. The provided method `from_argv` does the rest.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArgSpec {
+    pub name: &'static str,
+    /// An option takes a value, a flag does not.
+    pub takes_value: bool,
+    pub required: bool,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Parsed {
+    pub flags: HashSet<String>,
+    pub values: HashMap<String, String>,
+    pub positionals: Vec<String>,
+}
+
+impl Parsed {
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.contains(name)
+    }
+
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ArgError {
+    /// The argument as written, with its dashes.
+    UnknownFlag(String),
+    MissingValue(&'static str),
+    /// A flag written as `--name=value`.
+    UnexpectedValue(&'static str),
+    MissingRequired(&'static str),
+    Duplicate(&'static str),
+    // the errors of `FromArgs::from_parsed`
+    InvalidValue {
+        name: &'static str,
+        value: String,
+    },
+    MissingPositional(&'static str),
+    UnexpectedPositional(String),
+}
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArgError::UnknownFlag(arg) => write!(f, "unknown argument {}", arg),
+            ArgError::MissingValue(name) => write!(f, "--{} needs a value", name),
+            ArgError::UnexpectedValue(name) => write!(f, "--{} does not take a value", name),
+            ArgError::MissingRequired(name) => write!(f, "--{} is required", name),
+            ArgError::Duplicate(name) => write!(f, "--{} is given more than once", name),
+            ArgError::InvalidValue { name, value } => {
+                write!(f, "invalid value {:?} for --{}", value, name)
+            }
+            ArgError::MissingPositional(name) => write!(f, "missing <{}>", name),
+            ArgError::UnexpectedPositional(arg) => write!(f, "unexpected argument {}", arg),
+        }
+    }
+}
+
+impl std::error::Error for ArgError {}
+
+/// Records one flag or option. `inline` is the value of `--name=value`, otherwise an option
+/// takes the next argument.
+fn apply<'a>(
+    parsed: &mut Parsed,
+    spec: &ArgSpec,
+    inline: Option<&str>,
+    rest: &mut impl Iterator<Item = &'a str>,
+) -> Result<(), ArgError> {
+    if parsed.flags.contains(spec.name) || parsed.values.contains_key(spec.name) {
+        return Err(ArgError::Duplicate(spec.name));
+    }
+    if !spec.takes_value {
+        if inline.is_some() {
+            return Err(ArgError::UnexpectedValue(spec.name));
+        }
+        parsed.flags.insert(spec.name.to_string());
+        return Ok(());
+    }
+    let value: &str = match inline {
+        Some(value) => value,
+        None => rest.next().ok_or(ArgError::MissingValue(spec.name))?,
+    };
+    parsed
+        .values
+        .insert(spec.name.to_string(), value.to_string());
+    Ok(())
+}
+
+pub fn parse(specs: &[ArgSpec], argv: &[&str]) -> Result<Parsed, ArgError> {
+    let find = |name: &str, arg: &str| {
+        specs
+            .iter()
+            .find(|spec| spec.name == name)
+            .ok_or_else(|| ArgError::UnknownFlag(arg.to_string()))
+    };
+
+    let mut parsed: Parsed = Parsed::default();
+    let mut rest = argv.iter().copied();
+    while let Some(arg) = rest.next() {
+        if arg == "--" {
+            parsed.positionals.extend(rest.by_ref().map(String::from));
+        } else if let Some(long) = arg.strip_prefix("--") {
+            let (name, inline) = match long.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (long, None),
+            };
+            apply(&mut parsed, find(name, arg)?, inline, &mut rest)?;
+        } else if let Some(shorts) = arg.strip_prefix('-').filter(|s| !s.is_empty()) {
+            let mut chars = shorts.char_indices().peekable();
+            while let Some((i, c)) = chars.next() {
+                let spec: &ArgSpec = find(&shorts[i..i + c.len_utf8()], &format!("-{}", c))?;
+                // an option in the middle of `-abc` would have no value
+                if spec.takes_value && chars.peek().is_some() {
+                    return Err(ArgError::MissingValue(spec.name));
+                }
+                apply(&mut parsed, spec, None, &mut rest)?;
+            }
+        } else {
+            parsed.positionals.push(arg.to_string());
+        }
+    }
+
+    for spec in specs.iter().filter(|spec| spec.required) {
+        if !parsed.flag(spec.name) && parsed.value(spec.name).is_none() {
+            return Err(ArgError::MissingRequired(spec.name));
+        }
+    }
+    Ok(parsed)
+}
+
+pub trait FromArgs: Sized {
+    fn specs() -> Vec<ArgSpec>;
+
+    fn from_parsed(p: &Parsed) -> Result<Self, ArgError>;
+
+    fn from_argv(argv: &[&str]) -> Result<Self, ArgError> {
+        Self::from_parsed(&parse(&Self::specs(), argv)?)
+    }
+}
+
+/// The options of a small grep: `grep [-i] [-n] [--max-count N] <query> <path>`.
+#[derive(Debug, PartialEq)]
+pub struct GrepOpts {
+    pub query: String,
+    pub path: String,
+    pub ignore_case: bool,
+    pub line_numbers: bool,
+    pub max_count: Option<usize>,
+}
+
+impl FromArgs for GrepOpts {
+    fn specs() -> Vec<ArgSpec> {
+        vec![
+            ArgSpec {
+                name: "i",
+                takes_value: false,
+                required: false,
+            },
+            ArgSpec {
+                name: "n",
+                takes_value: false,
+                required: false,
+            },
+            ArgSpec {
+                name: "max-count",
+                takes_value: true,
+                required: false,
+            },
+        ]
+    }
+
+    fn from_parsed(p: &Parsed) -> Result<Self, ArgError> {
+        let max_count: Option<usize> = match p.value("max-count") {
+            Some(value) => Some(value.parse().map_err(|_| ArgError::InvalidValue {
+                name: "max-count",
+                value: value.to_string(),
+            })?),
+            None => None,
+        };
+        let mut positionals = p.positionals.iter();
+        let query: String = positionals
+            .next()
+            .ok_or(ArgError::MissingPositional("query"))?
+            .clone();
+        let path: String = positionals
+            .next()
+            .ok_or(ArgError::MissingPositional("path"))?
+            .clone();
+        if let Some(extra) = positionals.next() {
+            return Err(ArgError::UnexpectedPositional(extra.clone()));
+        }
+        Ok(GrepOpts {
+            query,
+            path,
+            ignore_case: p.flag("i"),
+            line_numbers: p.flag("n"),
+            max_count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    fn specs() -> Vec<ArgSpec> {
+        vec![
+            ArgSpec {
+                name: "verbose",
+                takes_value: false,
+                required: false,
+            },
+            ArgSpec {
+                name: "output",
+                takes_value: true,
+                required: false,
+            },
+            ArgSpec {
+                name: "a",
+                takes_value: false,
+                required: false,
+            },
+            ArgSpec {
+                name: "b",
+                takes_value: false,
+                required: false,
+            },
+            ArgSpec {
+                name: "o",
+                takes_value: true,
+                required: false,
+            },
+        ]
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn run_args_syntax() {
+        let parsed: Parsed = parse(
+            &specs(),
+            &[
+                "build",
+                "--verbose",
+                "--output",
+                "out.txt",
+                "-ab",
+                "-",
+                "target",
+            ],
+        )
+        .unwrap();
+        assert!(parsed.flag("verbose") && parsed.flag("a") && parsed.flag("b"));
+        assert_eq!(parsed.value("output"), Some("out.txt"));
+        assert_eq!(parsed.positionals, strings(&["build", "-", "target"]));
+
+        let parsed: Parsed = parse(&specs(), &["--output=a=b", "--a", "-bo", "x.txt"]).unwrap();
+        assert_eq!(parsed.value("output"), Some("a=b"));
+        assert_eq!(parsed.value("o"), Some("x.txt"));
+        assert_eq!(parsed.flags, ["a", "b"].map(String::from).into());
+        assert!(parsed.positionals.is_empty());
+
+        // an empty inline value and a value that looks like a flag
+        let parsed: Parsed = parse(&specs(), &["--output=", "-o", "--verbose"]).unwrap();
+        assert_eq!(parsed.value("output"), Some(""));
+        assert_eq!(parsed.value("o"), Some("--verbose"));
+        assert!(!parsed.flag("verbose"));
+
+        assert_eq!(parse(&specs(), &[]).unwrap(), Parsed::default());
+    }
+
+    #[test]
+    fn run_args_terminator() {
+        let parsed: Parsed =
+            parse(&specs(), &["-a", "--", "--verbose", "-b", "--", "file"]).unwrap();
+        assert_eq!(parsed.flags, ["a"].map(String::from).into());
+        assert_eq!(
+            parsed.positionals,
+            strings(&["--verbose", "-b", "--", "file"])
+        );
+
+        let parsed: Parsed = parse(&specs(), &["--"]).unwrap();
+        assert!(parsed.positionals.is_empty());
+    }
+
+    #[test]
+    fn run_args_errors() {
+        let parse = |argv: &[&str]| parse(&specs(), argv);
+        assert_eq!(
+            parse(&["--color"]),
+            Err(ArgError::UnknownFlag(String::from("--color")))
+        );
+        assert_eq!(
+            parse(&["--color=red"]),
+            Err(ArgError::UnknownFlag(String::from("--color=red")))
+        );
+        assert_eq!(
+            parse(&["-ax"]),
+            Err(ArgError::UnknownFlag(String::from("-x")))
+        );
+        // a long name can't be given with one dash
+        assert_eq!(
+            parse(&["-verbose"]),
+            Err(ArgError::UnknownFlag(String::from("-v")))
+        );
+
+        assert_eq!(parse(&["--output"]), Err(ArgError::MissingValue("output")));
+        assert_eq!(parse(&["-ab", "-o"]), Err(ArgError::MissingValue("o")));
+        assert_eq!(parse(&["-oa", "x"]), Err(ArgError::MissingValue("o")));
+        assert_eq!(
+            parse(&["--verbose=yes"]),
+            Err(ArgError::UnexpectedValue("verbose"))
+        );
+
+        assert_eq!(parse(&["-a", "-ba"]), Err(ArgError::Duplicate("a")));
+        assert_eq!(parse(&["--a", "-a"]), Err(ArgError::Duplicate("a")));
+        assert_eq!(
+            parse(&["--output=x", "--output", "y"]),
+            Err(ArgError::Duplicate("output"))
+        );
+
+        let mut required: Vec<ArgSpec> = specs();
+        required[1].required = true;
+        required[0].required = true;
+        assert_eq!(
+            super::parse(&required, &["--output", "x"]),
+            Err(ArgError::MissingRequired("verbose"))
+        );
+        assert_eq!(
+            super::parse(&required, &["--verbose", "--", "--output", "x"]),
+            Err(ArgError::MissingRequired("output"))
+        );
+        assert!(super::parse(&required, &["--verbose", "--output", "x"]).is_ok());
+        assert_eq!(
+            ArgError::MissingRequired("output").to_string(),
+            "--output is required"
+        );
+    }
+
+    #[test]
+    fn run_args_grep_opts() {
+        assert_eq!(
+            GrepOpts::from_argv(&["-in", "--max-count=3", "needle", "haystack.txt"]),
+            Ok(GrepOpts {
+                query: String::from("needle"),
+                path: String::from("haystack.txt"),
+                ignore_case: true,
+                line_numbers: true,
+                max_count: Some(3),
+            })
+        );
+        // a query starting with a dash after the terminator
+        assert_eq!(
+            GrepOpts::from_argv(&["-n", "--", "-x", "-"]),
+            Ok(GrepOpts {
+                query: String::from("-x"),
+                path: String::from("-"),
+                ignore_case: false,
+                line_numbers: true,
+                max_count: None,
+            })
+        );
+
+        assert_eq!(
+            GrepOpts::from_argv(&["--max-count", "many", "a", "b"]),
+            Err(ArgError::InvalidValue {
+                name: "max-count",
+                value: String::from("many")
+            })
+        );
+        assert_eq!(
+            GrepOpts::from_argv(&["-i"]),
+            Err(ArgError::MissingPositional("query"))
+        );
+        assert_eq!(
+            GrepOpts::from_argv(&["a"]),
+            Err(ArgError::MissingPositional("path"))
+        );
+        assert_eq!(
+            GrepOpts::from_argv(&["a", "b", "c"]),
+            Err(ArgError::UnexpectedPositional(String::from("c")))
+        );
+        assert_eq!(
+            GrepOpts::from_argv(&["-v", "a", "b"]),
+            Err(ArgError::UnknownFlag(String::from("-v")))
+        );
+    }
+}
@@ -1,4 +1,11 @@
+pub mod args;
 mod drop;
+pub mod markers;
+pub mod observer;
+pub mod prng;
+pub mod serialize;
+pub mod units;
+pub mod views;
 
 pub mod define_trait {
     //! A trait can have multiple methods in its body: the method signatures are listed one per line
@@ -261,6 +268,474 @@ pub mod use_trait_bound_to_conditionally_implement_methods {
     }
 }
 
+pub mod into_iterator {
+    //! A `for` loop calls `IntoIterator::into_iter` on whatever follows `in`, so the receiver decides
+    //! what the loop yields:
+    //!
+    //! ```text
+    //! for track in playlist        // IntoIterator for Playlist       yields String, consumes it
+    //! for track in &playlist       // IntoIterator for &Playlist      yields &String
+    //! for track in &mut playlist   // IntoIterator for &mut Playlist  yields &mut String
+    //! ```
+    //!
+    //! Each form is a separate trait implementation on a separate type, so a collection needs all
+    //! three for all three loops to compile, exactly like `Vec<T>` provides them.
+    //!
+    //! The owned form moves the playlist into the loop, it can't be used afterward:
+    //! ```compile_fail,E0382
+    //! use traits::into_iterator::Playlist;
+    //!
+    //! let playlist = Playlist::new(vec![String::from("intro")]);
+    //! for _track in playlist {}
+    //! println!("{}", playlist.tracks.len());
+    //! ```
+
+    pub struct Playlist {
+        pub tracks: Vec<String>,
+    }
+
+    impl Playlist {
+        pub fn new(tracks: Vec<String>) -> Self {
+            Self { tracks }
+        }
+
+        pub fn iter(&self) -> std::slice::Iter<'_, String> {
+            self.tracks.iter()
+        }
+
+        pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, String> {
+            self.tracks.iter_mut()
+        }
+    }
+
+    impl IntoIterator for Playlist {
+        type Item = String;
+        type IntoIter = std::vec::IntoIter<String>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.tracks.into_iter()
+        }
+    }
+
+    impl<'a> IntoIterator for &'a Playlist {
+        type Item = &'a String;
+        type IntoIter = std::slice::Iter<'a, String>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.iter()
+        }
+    }
+
+    impl<'a> IntoIterator for &'a mut Playlist {
+        type Item = &'a mut String;
+        type IntoIter = std::slice::IterMut<'a, String>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.iter_mut()
+        }
+    }
+
+    pub fn upper_case_all(p: &mut Playlist) {
+        for track in p {
+            *track = track.to_uppercase();
+        }
+    }
+}
+
+pub mod generic_trait_method {
+    //! A method of a trait can have its own type parameters. `save<T>` below is generic per call:
+    //! one `Store` value accepts an `i32`, then a `&str`, then a `Vec<_>`, and there is a single
+    //! `impl Store for DebugStore`.
+    //!
+    //! A generic trait `trait Store<T> { fn save(&mut self, item: T) -> String; }` is instead a
+    //! family of traits, `Store<i32>` and `Store<&str>` are distinct, and a type has to implement
+    //! each of them separately to accept both.
+    //!
+    //! The trade-off is that a trait with a generic method is not dyn compatible: a vtable can't
+    //! hold an entry for every possible `T`, so `Box<dyn Store>` does not compile, while
+    //! `Box<dyn Store<i32>>` would.
+
+    use std::fmt::Debug;
+
+    pub trait Store {
+        fn save<T: Debug>(&mut self, item: T) -> String;
+    }
+
+    /// Keeps the `Debug` representation of every saved item.
+    pub struct DebugStore {
+        pub saved: Vec<String>,
+    }
+
+    impl Store for DebugStore {
+        fn save<T: Debug>(&mut self, item: T) -> String {
+            let record: String = format!("#{}: {:?}", self.saved.len(), item);
+            self.saved.push(record.clone());
+            record
+        }
+    }
+}
+
+pub mod template_method {
+    //! A default method may call the required methods of the same trait. The trait then fixes the
+    //! overall algorithm (the template) once, and each implementor only fills in the steps that
+    //! vary: here `render` decides the layout and `title` is the hook.
+    //!
+    //! This is the template-method pattern without inheritance. An implementor can still override
+    //! `render` when the layout does not fit, but most only write `title`.
+
+    pub trait Report {
+        fn title(&self) -> String;
+
+        fn render(&self) -> String {
+            format!("# {}", self.title())
+        }
+    }
+
+    pub struct SalesReport {
+        pub quarter: u8,
+    }
+
+    impl Report for SalesReport {
+        fn title(&self) -> String {
+            format!("Sales Q{}", self.quarter)
+        }
+    }
+}
+
+pub mod sealed {
+    //! A sealed trait is public, so anyone can name it, use it as a bound and call its methods,
+    //! but only this crate can implement it. `Marker` has the supertrait `private::Sealed`, and
+    //! `private` is a private module: an external crate can't name `Sealed`, so it can't write the
+    //! `impl Sealed for TheirType` that `impl Marker for TheirType` requires.
+    //!
+    //! The benefit is coherence under our control: the set of implementors is closed, so we can
+    //! add methods to `Marker` later, or rely on every implementor being one of our types, without
+    //! breaking anybody downstream. The standard library seals `SliceIndex` in the same way.
+    //!
+    //! Doc tests are compiled as an external crate, so this one is rejected:
+    //! ```compile_fail,E0277
+    //! struct Hours(f64);
+    //!
+    //! impl traits::sealed::Marker for Hours {
+    //!     fn tag(&self) -> &'static str {
+    //!         "h"
+    //!     }
+    //! }
+    //! ```
+
+    mod private {
+        pub trait Sealed {}
+    }
+
+    pub trait Marker: private::Sealed {
+        fn tag(&self) -> &'static str;
+    }
+
+    pub struct Meters(pub f64);
+
+    pub struct Seconds(pub f64);
+
+    impl private::Sealed for Meters {}
+
+    impl Marker for Meters {
+        fn tag(&self) -> &'static str {
+            "m"
+        }
+    }
+
+    impl private::Sealed for Seconds {}
+
+    impl Marker for Seconds {
+        fn tag(&self) -> &'static str {
+            "s"
+        }
+    }
+
+    // In another crate, the following fails with error[E0277]: the trait bound
+    // `Hours: traits::sealed::private::Sealed` is not satisfied, and `private` can't be named to
+    // implement it:
+    //
+    // struct Hours(f64);
+    // impl traits::sealed::Marker for Hours {
+    //     fn tag(&self) -> &'static str { "h" }
+    // }
+
+    pub fn describe(value: &impl Marker) -> String {
+        format!("unit: {}", value.tag())
+    }
+}
+
+pub mod object_safety {
+    //! A trait object `dyn Trait` is a pointer to the data plus a pointer to a vtable holding one
+    //! function pointer per method. A trait can only be used this way (it is object safe, now
+    //! called dyn compatible) when every method can be put in such a table and called without
+    //! knowing the concrete type, which mainly rules out:
+    //!
+    //! - generic methods, there would need to be one vtable entry per type argument, and the set
+    //!   of type arguments is not known when the vtable is built;
+    //! - methods that return `Self` or take it as a non-receiver argument, the size of `Self` is
+    //!   unknown behind the pointer. A `self` receiver by value is allowed, the method is just
+    //!   not callable on an unsized `dyn Trait`;
+    //! - a `Self: Sized` supertrait, `dyn Trait` itself is not `Sized`.
+    //!
+    //! A method can opt out with `where Self: Sized`, it is then simply not callable on a trait
+    //! object and the rest of the trait stays dyn compatible.
+
+    pub trait Speak {
+        fn say(&self) -> String;
+    }
+
+    // Not object safe: `Box<dyn Greet>` is rejected with error[E0038]: the trait `Greet` is not
+    // dyn compatible, because `greet_with` is generic over `T` and `duplicate` returns `Self`.
+    //
+    // pub trait Greet {
+    //     fn greet_with<T: std::fmt::Display>(&self, extra: T) -> String;
+    //     fn duplicate(&self) -> Self;
+    // }
+
+    pub struct Dog;
+
+    pub struct Robot {
+        pub id: u32,
+    }
+
+    impl Speak for Dog {
+        fn say(&self) -> String {
+            String::from("woof")
+        }
+    }
+
+    impl Speak for Robot {
+        fn say(&self) -> String {
+            format!("beep {}", self.id)
+        }
+    }
+
+    pub fn chorus(speakers: &[Box<dyn Speak>]) -> String {
+        speakers
+            .iter()
+            .map(|s| s.say())
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+pub mod impl_trait_args {
+    //! `impl Trait` in argument position is an anonymous generic parameter: `process` and
+    //! `process_generic` compile to the same monomorphized code, one copy per argument type.
+    //!
+    //! It can't be used when:
+    //!
+    //! - two arguments must have the same type, each `impl Trait` is its own parameter, so
+    //!   `fn pair(a: impl Debug, b: impl Debug)` accepts an `i32` and a `&str` together, while
+    //!   `fn pair<T: Debug>(a: T, b: T)` does not;
+    //! - the caller needs to name the type, `process_generic::<i64>(1)` works, a function with
+    //!   `impl Trait` arguments can't be called with turbofish;
+    //! - the type is used elsewhere in the signature, e.g. in the return type or in a `where`
+    //!   clause involving another parameter.
+
+    use std::fmt::Debug;
+
+    pub fn process(item: impl Debug + Clone) -> String {
+        let copy = item.clone();
+        format!("{:?} / {:?}", item, copy)
+    }
+
+    pub fn process_generic<T: Debug + Clone>(item: T) -> String {
+        let copy: T = item.clone();
+        format!("{:?} / {:?}", item, copy)
+    }
+
+    /// Both arguments must be the same `T`, which `impl Trait` can't express.
+    pub fn same_type_pair<T: Debug + PartialEq>(a: T, b: T) -> String {
+        format!("{:?} == {:?}: {}", a, b, a == b)
+    }
+}
+
+pub mod dedup_summaries {
+    //! `fingerprint` hashes only the headline and the author, so the same story republished with
+    //! an edited body is detected as a duplicate.
+    //!
+    //! Such a partial hash must not become the type's identity. `Hash` and `Eq` have to agree:
+    //! `a == b` implies `hash(a) == hash(b)`. Deriving `PartialEq` and `Eq` compares every field,
+    //! body included, and hashing a subset of the fields that `Eq` compares would still be valid,
+    //! but defining `Eq` *from* the fingerprint would make two articles with different bodies
+    //! equal, a `HashSet<Article>` would silently keep only one of them, and a hash collision would
+    //! make unrelated articles equal. The fingerprint stays a separate method and deduplication is
+    //! an explicit step.
+
+    use super::define_trait::Summary;
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::{HashMap, HashSet};
+    use std::hash::{Hash, Hasher};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Article {
+        pub headline: String,
+        pub author: String,
+        pub body: String,
+    }
+
+    impl Summary for Article {
+        fn summarize(&self) -> String {
+            format!("{}, by {}", self.headline, self.author)
+        }
+    }
+
+    impl Article {
+        pub fn new(headline: &str, author: &str, body: &str) -> Self {
+            Article {
+                headline: headline.to_string(),
+                author: author.to_string(),
+                body: body.to_string(),
+            }
+        }
+
+        /// Hashing the tuple rather than the concatenation keeps ("ab", "c") and ("a", "bc")
+        /// apart, `str` writes a terminator after its bytes.
+        pub fn fingerprint(&self) -> u64 {
+            let mut hasher: DefaultHasher = DefaultHasher::new();
+            (&self.headline, &self.author).hash(&mut hasher);
+            hasher.finish()
+        }
+    }
+
+    /// Splits into kept and discarded articles, the first occurrence of each fingerprint is kept
+    /// and both lists preserve the input order.
+    pub fn dedup_by_fingerprint(items: Vec<Article>) -> (Vec<Article>, Vec<Article>) {
+        let mut seen: HashSet<u64> = HashSet::new();
+        items
+            .into_iter()
+            .partition(|article| seen.insert(article.fingerprint()))
+    }
+
+    /// Keeps one article per fingerprint at the position of its first occurrence, with the bodies
+    /// of all its duplicates appended in input order, one per line.
+    pub fn merge_duplicates(items: Vec<Article>) -> Vec<Article> {
+        let mut index: HashMap<u64, usize> = HashMap::new();
+        let mut merged: Vec<Article> = vec![];
+        for article in items {
+            match index.get(&article.fingerprint()) {
+                Some(&i) => {
+                    merged[i].body.push('\n');
+                    merged[i].body.push_str(&article.body);
+                }
+                None => {
+                    index.insert(article.fingerprint(), merged.len());
+                    merged.push(article);
+                }
+            }
+        }
+        merged
+    }
+}
+
+pub mod boxed_iterator {
+    //! `-> impl Iterator<Item = i32>` stands for one concrete type chosen by the compiler, the
+    //! function body may only ever return that type. `(0..10).filter(..)` is a `Filter<Range,
+    //! closure>` and `(0..10).map(..)` a `Map<Range, closure>`, two different types, so returning
+    //! one or the other depending on a runtime value does not compile as `impl Iterator`.
+    //!
+    //! `Box<dyn Iterator<Item = i32>>` erases the concrete type: both branches are boxed into the
+    //! same type and `next` is called through the vtable. The cost is one allocation and a dynamic
+    //! call per item.
+
+    pub fn make_iter(even: bool) -> Box<dyn Iterator<Item = i32>> {
+        if even {
+            Box::new((0..10).filter(|n| n % 2 == 0))
+        } else {
+            Box::new((0..10).map(|n| n * 2 + 1))
+        }
+    }
+}
+
+pub mod any_downcast {
+    //! `std::any::Any` is implemented for every `'static` type. Its one method, `type_id`, returns
+    //! a `TypeId` identifying the concrete type behind a `dyn Any`, and `downcast_ref::<T>` compares
+    //! it with `TypeId::of::<T>()`: it gives `Some(&T)` when the types are the same, `None`
+    //! otherwise. `Box<dyn Any>` has `downcast::<T>`, which hands the box back on failure.
+    //!
+    //! The limitations:
+    //!
+    //! * the check is for one exact type, a `dyn Any` holding an `i32` is not an `i64`, and there
+    //!   is no asking whether it implements some trait;
+    //! * types holding non-`'static` references can't be `Any`, lifetimes are erased at runtime;
+    //! * the set of types is open, so a chain of `downcast_ref` always needs a fallback branch,
+    //!   where a `match` on an enum would be checked for exhaustiveness.
+    //!
+    //! An enum or a trait with the needed methods is usually the better design, `Any` is for
+    //! values whose type really is unknown to the code passing them along, like panic payloads.
+
+    use std::any::Any;
+
+    pub fn values() -> Vec<Box<dyn Any>> {
+        vec![Box::new(42_i32), Box::new(String::from("rust"))]
+    }
+
+    pub fn describe(value: &dyn Any) -> String {
+        if let Some(n) = value.downcast_ref::<i32>() {
+            format!("i32 {}", n)
+        } else if let Some(s) = value.downcast_ref::<String>() {
+            format!("String {:?}", s)
+        } else {
+            String::from("unknown")
+        }
+    }
+}
+
+pub mod hashing {
+    //! Two traits split the work of hashing. `Hasher` is the algorithm: it is fed bytes with
+    //! `write` (and helpers like `write_u32`) and `finish` returns the `u64` hash of everything
+    //! written so far. `Hash` is implemented by the value: its `hash<H: Hasher>(&self, state)`
+    //! feeds the parts that make up the value into any hasher. `#[derive(Hash)]` hashes every
+    //! field in declaration order, which is consistent with a derived `PartialEq`, as required:
+    //! `a == b` must imply equal hashes. The reverse can't hold, a `u64` can't tell every value
+    //! apart, but a good hasher makes a collision between different values unlikely.
+    //!
+    //! When equality is written by hand, `Hash` has to be too, and it must only feed the fields
+    //! `eq` compares: hashing a field that `eq` ignores would give equal values different hashes.
+    //!
+    //! `DefaultHasher` is the hasher a `HashMap` uses, its algorithm is unspecified, so a hash
+    //! is only meaningful within one run of the program and should not be stored.
+
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    #[derive(Hash, PartialEq, Debug)]
+    pub struct Account {
+        pub id: u32,
+        pub owner: String,
+    }
+
+    /// The same fields as `Account`, but the identity of an account is its `id` alone: two values
+    /// with the same `id` are equal whoever the owner is, so `Hash` skips `owner` as `eq` does.
+    #[derive(Debug)]
+    pub struct AccountById {
+        pub id: u32,
+        pub owner: String,
+    }
+
+    impl PartialEq for AccountById {
+        fn eq(&self, other: &Self) -> bool {
+            self.id == other.id
+        }
+    }
+
+    impl Eq for AccountById {}
+
+    impl Hash for AccountById {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.id.hash(state);
+        }
+    }
+
+    pub fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher: DefaultHasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
 #[cfg(test)]
 mod testing {
     use std::collections::HashMap;
@@ -304,4 +779,220 @@ mod testing {
         println!("{}", Facebook {}.summarize());
         println!("{}", Tweet {}.summarize());
     }
+
+    #[test]
+    fn run_into_iterator_playlist() {
+        use crate::into_iterator::{upper_case_all, Playlist};
+        let mut playlist: Playlist =
+            Playlist::new(vec![String::from("intro"), String::from("outro")]);
+
+        let mut lengths: Vec<usize> = vec![];
+        for track in &playlist {
+            let track: &String = track;
+            lengths.push(track.len());
+        }
+        assert_eq!(lengths, vec![5, 5]);
+        assert_eq!(playlist.iter().count(), 2);
+
+        for track in &mut playlist {
+            let track: &mut String = track;
+            track.push('!');
+        }
+        assert_eq!(playlist.tracks, vec!["intro!", "outro!"]);
+
+        playlist.iter_mut().for_each(|track| track.insert(0, '#'));
+        upper_case_all(&mut playlist);
+        assert_eq!(playlist.tracks, vec!["#INTRO!", "#OUTRO!"]);
+
+        let mut owned: Vec<String> = vec![];
+        for track in playlist {
+            let track: String = track;
+            owned.push(track);
+        }
+        assert_eq!(owned, vec!["#INTRO!", "#OUTRO!"]);
+    }
+
+    #[test]
+    fn run_generic_trait_method() {
+        use crate::generic_trait_method::{DebugStore, Store};
+        let mut store: DebugStore = DebugStore { saved: vec![] };
+        assert_eq!(store.save(42), "#0: 42");
+        assert_eq!(store.save("rust"), "#1: \"rust\"");
+        assert_eq!(store.save(vec![1.5, 2.0]), "#2: [1.5, 2.0]");
+        assert_eq!(store.save(Some('c')), "#3: Some('c')");
+        assert_eq!(store.saved.len(), 4);
+    }
+
+    #[test]
+    fn run_template_method() {
+        use crate::template_method::{Report, SalesReport};
+        let report: SalesReport = SalesReport { quarter: 3 };
+        assert_eq!(report.title(), "Sales Q3");
+        assert_eq!(report.render(), "# Sales Q3");
+    }
+
+    #[test]
+    fn run_sealed_marker() {
+        use crate::sealed::{describe, Marker, Meters, Seconds};
+        assert_eq!(Meters(1.0).tag(), "m");
+        assert_eq!(describe(&Seconds(2.0)), "unit: s");
+    }
+
+    #[test]
+    fn run_object_safety_boxed() {
+        use crate::object_safety::{chorus, Dog, Robot, Speak};
+        let speakers: Vec<Box<dyn Speak>> = vec![Box::new(Dog), Box::new(Robot { id: 7 })];
+        assert_eq!(speakers[1].say(), "beep 7");
+        assert_eq!(chorus(&speakers), "woof, beep 7");
+    }
+
+    #[test]
+    fn run_impl_trait_args() {
+        use crate::impl_trait_args::{process, process_generic, same_type_pair};
+        assert_eq!(process(42), process_generic(42));
+        assert_eq!(process(42), "42 / 42");
+        assert_eq!(process_generic::<i64>(-1), "-1 / -1");
+        assert_eq!(same_type_pair("a", "b"), "\"a\" == \"b\": false");
+    }
+
+    fn articles() -> Vec<crate::dedup_summaries::Article> {
+        use crate::dedup_summaries::Article;
+        vec![
+            Article::new("Rust 2024", "ann", "first draft"),
+            Article::new("Go 2", "bob", "generics"),
+            Article::new("Rust 2024", "ann", "edited"),
+            Article::new("Rust 2024", "cat", "another author"),
+            Article::new("Go 2", "bob", "generics"),
+        ]
+    }
+
+    #[test]
+    fn run_dedup_summaries_fingerprint() {
+        use crate::dedup_summaries::Article;
+        let a: Article = Article::new("Rust 2024", "ann", "first draft");
+        let b: Article = Article::new("Rust 2024", "ann", "edited");
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        assert_ne!(a, b);
+        assert_eq!(a.summarize(), "Rust 2024, by ann");
+
+        let split: Article = Article::new("ab", "c", "");
+        assert_ne!(
+            split.fingerprint(),
+            Article::new("a", "bc", "").fingerprint()
+        );
+    }
+
+    #[test]
+    fn run_dedup_summaries_dedup_by_fingerprint() {
+        use crate::dedup_summaries::dedup_by_fingerprint;
+        let (kept, discarded) = dedup_by_fingerprint(articles());
+        let bodies = |v: &[crate::dedup_summaries::Article]| -> Vec<String> {
+            v.iter().map(|a| a.body.clone()).collect()
+        };
+        assert_eq!(
+            bodies(&kept),
+            vec!["first draft", "generics", "another author"]
+        );
+        assert_eq!(bodies(&discarded), vec!["edited", "generics"]);
+
+        let (kept_again, discarded_again) = dedup_by_fingerprint(kept.clone());
+        assert_eq!(kept_again, kept);
+        assert!(discarded_again.is_empty());
+    }
+
+    #[test]
+    fn run_dedup_summaries_merge_duplicates() {
+        use crate::dedup_summaries::merge_duplicates;
+        let merged = merge_duplicates(articles());
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[0].body, "first draft\nedited");
+        assert_eq!(merged[1].body, "generics\ngenerics");
+        assert_eq!(merged[2].author, "cat");
+        assert_eq!(merged[2].body, "another author");
+    }
+
+    #[test]
+    fn run_boxed_iterator() {
+        use crate::boxed_iterator::make_iter;
+        assert_eq!(make_iter(true).collect::<Vec<i32>>(), vec![0, 2, 4, 6, 8]);
+        assert_eq!(
+            make_iter(false).take(3).collect::<Vec<i32>>(),
+            vec![1, 3, 5]
+        );
+        assert_eq!(make_iter(false).count(), 10);
+    }
+
+    #[test]
+    fn run_any_downcast() {
+        use crate::any_downcast::{describe, values};
+        use std::any::{Any, TypeId};
+        let values: Vec<Box<dyn Any>> = values();
+        assert_eq!(values[0].downcast_ref::<i32>(), Some(&42));
+        assert_eq!(values[0].downcast_ref::<String>(), None);
+        assert_eq!(values[1].downcast_ref::<i32>(), None);
+        assert_eq!(
+            values[1].downcast_ref::<String>().map(|s| s.as_str()),
+            Some("rust")
+        );
+
+        // the exact type only: neither a wider integer nor a `&str` matches
+        assert_eq!(values[0].downcast_ref::<i64>(), None);
+        assert_eq!(values[1].downcast_ref::<&str>(), None);
+        // `type_id` is looked up through the box's vtable, the box itself is another type
+        assert_eq!((*values[0]).type_id(), TypeId::of::<i32>());
+
+        let descriptions: Vec<String> = values.iter().map(|v| describe(v.as_ref())).collect();
+        assert_eq!(descriptions, vec!["i32 42", "String \"rust\""]);
+        assert_eq!(describe(&1.5_f64), "unknown");
+
+        let boxed: Box<dyn Any> = Box::new(7_u8);
+        let boxed: Box<dyn Any> = boxed.downcast::<i32>().unwrap_err();
+        assert_eq!(*boxed.downcast::<u8>().unwrap(), 7);
+    }
+
+    #[test]
+    fn run_hashing() {
+        use crate::hashing::{hash_of, Account};
+
+        let account = |id: u32, owner: &str| Account {
+            id,
+            owner: owner.to_string(),
+        };
+        assert_eq!(account(1, "ann"), account(1, "ann"));
+        assert_eq!(hash_of(&account(1, "ann")), hash_of(&account(1, "ann")));
+
+        // with high probability, each differing field changes the hash
+        assert_ne!(hash_of(&account(1, "ann")), hash_of(&account(2, "ann")));
+        assert_ne!(hash_of(&account(1, "ann")), hash_of(&account(1, "bob")));
+    }
+
+    #[test]
+    fn run_hashing_manual_impl() {
+        use crate::hashing::{hash_of, Account, AccountById};
+        use std::collections::HashSet;
+
+        let by_id = |id: u32, owner: &str| AccountById {
+            id,
+            owner: owner.to_string(),
+        };
+        let derived = |id: u32, owner: &str| Account {
+            id,
+            owner: owner.to_string(),
+        };
+
+        // the owner is ignored by the manual impls, not by the derived ones
+        assert_eq!(by_id(1, "ann"), by_id(1, "bob"));
+        assert_eq!(hash_of(&by_id(1, "ann")), hash_of(&by_id(1, "bob")));
+        assert_ne!(derived(1, "ann"), derived(1, "bob"));
+        assert_ne!(hash_of(&derived(1, "ann")), hash_of(&derived(1, "bob")));
+
+        // only the id is fed to the hasher, exactly like hashing the `u32` alone
+        assert_eq!(hash_of(&by_id(7, "ann")), hash_of(&7_u32));
+        assert_ne!(hash_of(&derived(7, "ann")), hash_of(&7_u32));
+
+        // so a set keeps one account per id
+        let set: HashSet<AccountById> = HashSet::from([by_id(1, "ann"), by_id(1, "bob")]);
+        assert_eq!(set.len(), 1);
+        assert_ne!(by_id(1, "ann"), by_id(2, "ann"));
+    }
 }
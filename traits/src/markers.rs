@@ -0,0 +1,122 @@
+//! Marker traits have no methods, they only tell the compiler something about a type.
+//!
+//! `Send` means a value can be moved to another thread, `Sync` means a value can be shared between
+//! threads by reference (`T` is `Sync` exactly when `&T` is `Send`). Both are auto traits: the
+//! compiler implements them for a type whenever all of its fields implement them, so a single
+//! non-thread-safe field makes the whole type lose the marker.
+//!
+//! Generic functions with a marker bound and an empty body turn this into a compile-time
+//! assertion, nothing runs at all:
+//! ```
+//! use traits::markers::assert_send_sync;
+//!
+//! assert_send_sync::<String>();
+//! assert_send_sync::<std::sync::Arc<i32>>();
+//! ```
+//!
+//! `Rc` updates its reference count without synchronization, so it is neither `Send` nor `Sync`:
+//! ```compile_fail,E0277
+//! use traits::markers::assert_send;
+//!
+//! assert_send::<std::rc::Rc<i32>>();
+//! ```
+//!
+//! ```compile_fail,E0277
+//! use traits::markers::assert_sync;
+//!
+//! assert_sync::<std::rc::Rc<i32>>();
+//! ```
+//!
+//! `Cell` allows mutation through a shared reference without locking, so a type holding one can
+//! be moved to another thread but not shared with it:
+//! ```compile_fail,E0277
+//! use traits::markers::{assert_sync, NotSync};
+//!
+//! assert_sync::<NotSync>();
+//! ```
+//!
+//! Our own marker traits work the same way, except they are not implemented automatically. Only
+//! `Clean` implements `Validated`, so `store` refuses a `Raw` string at compile time:
+//! ```compile_fail,E0277
+//! use traits::markers::{store, Raw};
+//!
+//! store(Raw(String::from("<script>")));
+//! ```
+
+use std::cell::Cell;
+
+pub fn assert_send<T: Send>() {}
+
+pub fn assert_sync<T: Sync>() {}
+
+pub fn assert_send_sync<T: Send + Sync>() {}
+
+/// `Send` but not `Sync`, because of the `Cell`.
+pub struct NotSync(pub Cell<u8>);
+
+/// A marker for values that have been checked and are safe to store.
+pub trait Validated {}
+
+/// Input as it was received.
+pub struct Raw(pub String);
+
+/// Input with every character that isn't alphanumeric or a space removed.
+pub struct Clean(String);
+
+impl Validated for Clean {}
+
+impl AsRef<str> for Raw {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Clean {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Raw {
+    /// The only way to get a `Clean` value is to validate a `Raw` one.
+    pub fn validate(self) -> Clean {
+        Clean(
+            self.0
+                .chars()
+                .filter(|c| c.is_alphanumeric() || *c == ' ')
+                .collect(),
+        )
+    }
+}
+
+pub fn store(s: impl Validated + AsRef<str>) -> String {
+    s.as_ref().to_string()
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn run_markers_send_sync() {
+        assert_send_sync::<i32>();
+        assert_send_sync::<String>();
+        assert_send_sync::<Vec<i32>>();
+        assert_send_sync::<HashMap<String, i32>>();
+        assert_send_sync::<Arc<i32>>();
+        assert_send_sync::<Mutex<i32>>();
+        assert_send_sync::<Clean>();
+
+        // `Cell` is `Send`, so `NotSync` is too, it is only `Sync` that it lacks
+        assert_send::<NotSync>();
+        assert_send::<Cell<u8>>();
+    }
+
+    #[test]
+    fn run_markers_validated() {
+        let clean: Clean = Raw(String::from("<b>rust</b> 2021")).validate();
+        assert_eq!(store(clean), "brustb 2021");
+    }
+}
@@ -0,0 +1,222 @@
+//! A subject that notifies observers without keeping them alive.
+//!
+//! If the subject held `Rc<dyn Observer>`, an observer would live as long as the subject, even
+//! after everyone else dropped it, and an observer that pointed back at the subject would make a
+//! cycle that is never freed. So the subject keeps a `Weak<dyn Observer>`, made with
+//! `Rc::downgrade`: it does not count as an owner, it only remembers where the observer was.
+//!
+//! ```text
+//! owner:   Rc ──strong──┐
+//!                       ▼
+//!                   observer      strong_count 1, weak_count 1
+//!                       ▲
+//! subject: Weak ──weak──┘
+//! ```
+//!
+//! To deliver an event, `Weak::upgrade` turns each entry back into an `Rc` for the length of
+//! the call, or returns `None` once the last owner dropped the observer. Such dead entries are
+//! pruned during the same pass.
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+pub trait Observer {
+    fn notify(&self, event: &str);
+}
+
+/// What one `emit` did: observers notified, and dead entries removed.
+#[derive(Debug, PartialEq, Default)]
+pub struct EmitStats {
+    pub delivered: usize,
+    pub pruned: usize,
+}
+
+#[derive(Default)]
+pub struct Subject {
+    observers: Vec<Weak<dyn Observer>>,
+}
+
+impl Subject {
+    pub fn new() -> Self {
+        Subject { observers: vec![] }
+    }
+
+    /// Subscribing does not check for duplicates: an observer subscribed twice has two entries
+    /// and is notified twice per event, until it is dropped and both are pruned.
+    pub fn subscribe(&mut self, obs: &Rc<dyn Observer>) {
+        self.observers.push(Rc::downgrade(obs));
+    }
+
+    pub fn len(&self) -> usize {
+        self.observers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.observers.is_empty()
+    }
+
+    /// Notifies the live observers in subscription order, and forgets the dead ones.
+    pub fn emit(&mut self, event: &str) -> EmitStats {
+        let mut stats: EmitStats = EmitStats::default();
+        self.observers.retain(|weak| match weak.upgrade() {
+            Some(observer) => {
+                observer.notify(event);
+                stats.delivered += 1;
+                true
+            }
+            None => {
+                stats.pruned += 1;
+                false
+            }
+        });
+        stats
+    }
+}
+
+/// Remembers every event it is notified of.
+#[derive(Default)]
+pub struct RecordingObserver {
+    pub log: RefCell<Vec<String>>,
+}
+
+impl Observer for RecordingObserver {
+    fn notify(&self, event: &str) {
+        self.log.borrow_mut().push(event.to_string());
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    fn recorder() -> (Rc<RecordingObserver>, Rc<dyn Observer>) {
+        let concrete: Rc<RecordingObserver> = Rc::new(RecordingObserver::default());
+        let observer: Rc<dyn Observer> = concrete.clone();
+        (concrete, observer)
+    }
+
+    #[test]
+    fn run_observer_delivery() {
+        let (first, first_dyn) = recorder();
+        let (second, second_dyn) = recorder();
+        let mut subject: Subject = Subject::new();
+        subject.subscribe(&first_dyn);
+        subject.subscribe(&second_dyn);
+
+        assert_eq!(
+            subject.emit("opened"),
+            EmitStats {
+                delivered: 2,
+                pruned: 0
+            }
+        );
+        assert_eq!(
+            subject.emit("closed"),
+            EmitStats {
+                delivered: 2,
+                pruned: 0
+            }
+        );
+        assert_eq!(*first.log.borrow(), vec!["opened", "closed"]);
+        assert_eq!(*second.log.borrow(), vec!["opened", "closed"]);
+    }
+
+    #[test]
+    fn run_observer_pruning() {
+        let (first, first_dyn) = recorder();
+        let (second, second_dyn) = recorder();
+        let mut subject: Subject = Subject::new();
+        subject.subscribe(&first_dyn);
+        subject.subscribe(&second_dyn);
+        assert_eq!(
+            subject.emit("one"),
+            EmitStats {
+                delivered: 2,
+                pruned: 0
+            }
+        );
+
+        drop(second);
+        drop(second_dyn);
+        // the entry stays until the next emit finds it dead
+        assert_eq!(subject.len(), 2);
+        assert_eq!(
+            subject.emit("two"),
+            EmitStats {
+                delivered: 1,
+                pruned: 1
+            }
+        );
+        assert_eq!(subject.len(), 1);
+        assert_eq!(
+            subject.emit("three"),
+            EmitStats {
+                delivered: 1,
+                pruned: 0
+            }
+        );
+        assert_eq!(*first.log.borrow(), vec!["one", "two", "three"]);
+
+        drop(first);
+        drop(first_dyn);
+        assert_eq!(
+            subject.emit("four"),
+            EmitStats {
+                delivered: 0,
+                pruned: 1
+            }
+        );
+        assert!(subject.is_empty());
+    }
+
+    #[test]
+    fn run_observer_subscribed_twice() {
+        let (recording, observer) = recorder();
+        let mut subject: Subject = Subject::new();
+        subject.subscribe(&observer);
+        subject.subscribe(&observer);
+
+        assert_eq!(
+            subject.emit("event"),
+            EmitStats {
+                delivered: 2,
+                pruned: 0
+            }
+        );
+        assert_eq!(*recording.log.borrow(), vec!["event", "event"]);
+
+        drop(recording);
+        drop(observer);
+        assert_eq!(
+            subject.emit("event"),
+            EmitStats {
+                delivered: 0,
+                pruned: 2
+            }
+        );
+    }
+
+    #[test]
+    fn run_observer_no_strong_references() {
+        let (recording, observer) = recorder();
+        // `recording` and `observer` are the two owners
+        assert_eq!(Rc::strong_count(&observer), 2);
+        drop(recording);
+        assert_eq!(Rc::strong_count(&observer), 1);
+
+        let mut subject: Subject = Subject::new();
+        subject.subscribe(&observer);
+        subject.subscribe(&observer);
+        assert_eq!(Rc::strong_count(&observer), 1);
+        assert_eq!(Rc::weak_count(&observer), 2);
+
+        // the upgraded `Rc` only lives during the notification
+        subject.emit("event");
+        assert_eq!(Rc::strong_count(&observer), 1);
+
+        // so dropping the one owner really frees the observer
+        let weak: Weak<dyn Observer> = Rc::downgrade(&observer);
+        drop(observer);
+        assert!(weak.upgrade().is_none());
+    }
+}
@@ -0,0 +1,268 @@
+//! Pseudo-random numbers without the `rand` crate, for examples and tests that need
+//! reproducible randomness: the same seed always gives the same sequence.
+//!
+//! Two generators implement `Rng`, which only requires `next_u64`: the other methods, and the
+//! consumers `shuffle`, `sample_without_replacement` and `choose`, are written once against the
+//! trait and work with either.
+//!
+//! * `XorShift64Star` is three shifts and a multiplication on one word of state;
+//! * `Pcg32` is a linear congruential generator whose output is scrambled by a shift and a
+//!   rotation. `inc` selects one of 2^63 independent streams.
+//!
+//! Neither is fit for cryptography.
+//!
+//! `x % n` is not uniform when `n` does not divide 2^64: the first `2^64 % n` results come up
+//! once more than the others. `gen_range` rejects the draws below `2^64 % n`, what remains is a
+//! whole number of copies of `0..n`:
+//!
+//! ```text
+//! 0 .. 2^64 % n | n | n | n | ... | n
+//!   rejected      each value of 0..n equally often
+//! ```
+//!
+//! At most half the draws are rejected, for `n` just above 2^63, and almost none for small `n`.
+
+use std::collections::HashSet;
+use std::ops::Range;
+
+pub trait Rng {
+    fn next_u64(&mut self) -> u64;
+
+    /// Panics if `range` is empty.
+    fn gen_range(&mut self, range: Range<u64>) -> u64 {
+        assert!(range.start < range.end, "empty range {:?}", range);
+        let span: u64 = range.end - range.start;
+        // 2^64 % span, computed without 2^64
+        let threshold: u64 = span.wrapping_neg() % span;
+        loop {
+            let x: u64 = self.next_u64();
+            if x >= threshold {
+                return range.start + x % span;
+            }
+        }
+    }
+
+    /// Uniform in `[0, 1)`, from the 53 high bits, as many as an `f64` mantissa holds.
+    fn gen_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+}
+
+pub struct XorShift64Star(u64);
+
+impl XorShift64Star {
+    /// A zero state would stay zero forever, so the seed 0 is replaced.
+    pub fn new(seed: u64) -> Self {
+        XorShift64Star(if seed == 0 {
+            0x9e37_79b9_7f4a_7c15
+        } else {
+            seed
+        })
+    }
+}
+
+impl Rng for XorShift64Star {
+    fn next_u64(&mut self) -> u64 {
+        let mut x: u64 = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        x.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+}
+
+pub struct Pcg32 {
+    state: u64,
+    inc: u64,
+}
+
+impl Pcg32 {
+    const MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+
+    /// Seeded like the reference `pcg32_srandom_r`, so the outputs match its published ones.
+    pub fn new(seed: u64, stream: u64) -> Self {
+        let mut rng: Pcg32 = Pcg32 {
+            state: 0,
+            inc: (stream << 1) | 1,
+        };
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+        rng
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old: u64 = self.state;
+        self.state = old.wrapping_mul(Self::MULTIPLIER).wrapping_add(self.inc);
+        let xorshifted: u32 = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+}
+
+impl Rng for Pcg32 {
+    fn next_u64(&mut self) -> u64 {
+        let high: u64 = self.next_u32() as u64;
+        (high << 32) | self.next_u32() as u64
+    }
+}
+
+/// Fisher–Yates: each position from the last down takes a random element among those not
+/// placed yet, so every permutation is equally likely.
+pub fn shuffle<T>(rng: &mut impl Rng, v: &mut [T]) {
+    for i in (1..v.len()).rev() {
+        let j: usize = rng.gen_range(0..i as u64 + 1) as usize;
+        v.swap(i, j);
+    }
+}
+
+/// `k` distinct values of `0..n`, in the order they were drawn, with Floyd's algorithm: one
+/// draw per value, even when `k` is close to `n`. Panics if `k > n`.
+pub fn sample_without_replacement(rng: &mut impl Rng, n: u64, k: u64) -> Vec<u64> {
+    assert!(k <= n, "cannot sample {} values out of {}", k, n);
+    let mut seen: HashSet<u64> = HashSet::new();
+    let mut sample: Vec<u64> = Vec::with_capacity(k as usize);
+    for j in n - k..n {
+        let t: u64 = rng.gen_range(0..j + 1);
+        // `j` was never drawn before, it can replace a `t` already taken
+        let value: u64 = if seen.contains(&t) { j } else { t };
+        seen.insert(value);
+        sample.push(value);
+    }
+    sample
+}
+
+pub fn choose<'a, T>(rng: &mut impl Rng, slice: &'a [T]) -> Option<&'a T> {
+    if slice.is_empty() {
+        return None;
+    }
+    slice.get(rng.gen_range(0..slice.len() as u64) as usize)
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    fn draws(rng: &mut impl Rng, n: usize) -> Vec<u64> {
+        (0..n).map(|_| rng.next_u64()).collect()
+    }
+
+    #[test]
+    fn run_prng_determinism() {
+        assert_eq!(
+            draws(&mut XorShift64Star::new(7), 20),
+            draws(&mut XorShift64Star::new(7), 20)
+        );
+        assert_ne!(
+            draws(&mut XorShift64Star::new(7), 20),
+            draws(&mut XorShift64Star::new(8), 20)
+        );
+        assert_eq!(
+            draws(&mut Pcg32::new(7, 1), 20),
+            draws(&mut Pcg32::new(7, 1), 20)
+        );
+        // another stream, another sequence
+        assert_ne!(
+            draws(&mut Pcg32::new(7, 1), 20),
+            draws(&mut Pcg32::new(7, 2), 20)
+        );
+        assert_ne!(XorShift64Star::new(0).next_u64(), 0);
+
+        // the reference implementation's output for `pcg32_srandom_r(&rng, 42, 54)`
+        let mut rng: Pcg32 = Pcg32::new(42, 54);
+        let first: Vec<u32> = (0..6).map(|_| rng.next_u32()).collect();
+        assert_eq!(
+            first,
+            [0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e]
+        );
+    }
+
+    #[test]
+    fn run_prng_range_bounds() {
+        let mut xorshift: XorShift64Star = XorShift64Star::new(1);
+        let mut pcg: Pcg32 = Pcg32::new(1, 1);
+        let ranges: [Range<u64>; 5] = [0..1, 5..6, 0..10, 100..103, u64::MAX - 3..u64::MAX];
+        for range in ranges {
+            for _ in 0..10_000 {
+                assert!(range.contains(&xorshift.gen_range(range.clone())));
+                assert!(range.contains(&pcg.gen_range(range.clone())));
+            }
+        }
+        // more than half of the draws are rejected here, it still ends
+        let half: Range<u64> = 0..(1 << 63) + 1;
+        assert!(half.contains(&pcg.gen_range(half.clone())));
+        for _ in 0..10_000 {
+            let x: f64 = xorshift.gen_f64();
+            assert!((0.0..1.0).contains(&x));
+        }
+    }
+
+    #[test]
+    fn run_prng_shuffle_is_permutation() {
+        let mut rng: Pcg32 = Pcg32::new(3, 3);
+        let sorted: Vec<u32> = (0..50).collect();
+        let mut v: Vec<u32> = sorted.clone();
+        shuffle(&mut rng, &mut v);
+        assert_ne!(v, sorted);
+        v.sort();
+        assert_eq!(v, sorted);
+
+        let mut empty: [u32; 0] = [];
+        shuffle(&mut rng, &mut empty);
+        let mut one: [u32; 1] = [9];
+        shuffle(&mut rng, &mut one);
+        assert_eq!(one, [9]);
+    }
+
+    #[test]
+    fn run_prng_sample_and_choose() {
+        let mut rng: XorShift64Star = XorShift64Star::new(11);
+        for (n, k) in [(10, 3), (10, 10), (1000, 999), (5, 0)] {
+            let sample: Vec<u64> = sample_without_replacement(&mut rng, n, k);
+            assert_eq!(sample.len() as u64, k);
+            assert!(sample.iter().all(|&x| x < n));
+            let unique: HashSet<u64> = sample.iter().copied().collect();
+            assert_eq!(unique.len() as u64, k);
+        }
+        let mut all: Vec<u64> = sample_without_replacement(&mut rng, 10, 10);
+        all.sort();
+        assert_eq!(all, (0..10).collect::<Vec<u64>>());
+
+        let words: [&str; 3] = ["ann", "bob", "cid"];
+        for _ in 0..100 {
+            assert!(words.contains(choose(&mut rng, &words).unwrap()));
+        }
+        assert_eq!(choose::<u8>(&mut rng, &[]), None);
+    }
+
+    /// Pearson's chi-squared statistic over 10 equally likely buckets has 9 degrees of freedom,
+    /// it is above 27.9 with probability 0.001. The seeds are fixed, so this can't flake, it
+    /// only catches a badly skewed generator.
+    #[test]
+    fn run_prng_bucket_counts() {
+        fn chi_squared(rng: &mut impl Rng) -> f64 {
+            let draws: usize = 100_000;
+            let mut buckets: [usize; 10] = [0; 10];
+            for _ in 0..draws {
+                buckets[rng.gen_range(0..10) as usize] += 1;
+            }
+            let expected: f64 = draws as f64 / 10.0;
+            buckets
+                .iter()
+                .map(|&count| (count as f64 - expected).powi(2) / expected)
+                .sum()
+        }
+        assert!(chi_squared(&mut XorShift64Star::new(5)) < 27.9);
+        assert!(chi_squared(&mut Pcg32::new(5, 5)) < 27.9);
+
+        // a generator stuck on one value is far off, the value must not be rejected: below
+        // 2^64 % 10 == 6 `gen_range` would loop forever
+        struct Stuck;
+        impl Rng for Stuck {
+            fn next_u64(&mut self) -> u64 {
+                14
+            }
+        }
+        assert!(chi_squared(&mut Stuck) > 1000.0);
+    }
+}
@@ -0,0 +1,552 @@
+//! One trait, two formats. A type describes itself as a list of named `FieldValue`s with
+//! `Record::fields` and rebuilds itself from such a list with `Record::from_fields`; the encoders
+//! only ever see the list, so every `Record` gets both formats without writing any encoding code.
+//!
+//! The key-value text is a single line, `name:type=value` pairs separated by `;`:
+//!
+//! ```text
+//! headline:str=Rust 2024;author:str=ann
+//! ```
+//!
+//! In a string value `\` and `;` are escaped with a backslash. `=` needs no escaping, a name never
+//! contains one, so the first `=` of a pair always ends the name.
+//!
+//! The binary format is a `u32` field count, then for each field its name, a one byte type tag and
+//! the value. Names and strings are a `u32` length followed by the UTF-8 bytes, numbers are 8
+//! little-endian bytes, all integers are little-endian. A decoder that runs out of bytes returns
+//! `Truncated` instead of panicking, bytes left after the last field are rejected too.
+//!
+//! Fields the type does not know are rejected by `from_fields`, not ignored: a misspelled name
+//! would otherwise silently lose a value. `expect_fields` does that check for an implementation.
+//!
+//! `Record` is implemented for the `Player` of the structs crate, a path dependency: the trait is
+//! local, so the orphan rule allows implementing it for a foreign type.
+
+use std::fmt;
+
+use crate::implement_trait_on_types::Facebook;
+use structs::builder::Player;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    U64(u64),
+    F64(f64),
+    Str(String),
+    Bool(bool),
+}
+
+impl FieldValue {
+    fn type_name(&self) -> &'static str {
+        match self {
+            FieldValue::U64(_) => "u64",
+            FieldValue::F64(_) => "f64",
+            FieldValue::Str(_) => "str",
+            FieldValue::Bool(_) => "bool",
+        }
+    }
+
+    fn tag(&self) -> u8 {
+        match self {
+            FieldValue::U64(_) => 0,
+            FieldValue::F64(_) => 1,
+            FieldValue::Str(_) => 2,
+            FieldValue::Bool(_) => 3,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RecordError {
+    MissingField(&'static str),
+    UnknownField(String),
+    DuplicateField(String),
+    WrongType {
+        field: &'static str,
+        expected: &'static str,
+    },
+    /// A key-value pair that can't be parsed, as it appears in the text.
+    Syntax(String),
+    Truncated,
+    TrailingBytes(usize),
+    InvalidTag(u8),
+    InvalidUtf8,
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordError::MissingField(name) => write!(f, "missing field `{}`", name),
+            RecordError::UnknownField(name) => write!(f, "unknown field `{}`", name),
+            RecordError::DuplicateField(name) => write!(f, "field `{}` appears twice", name),
+            RecordError::WrongType { field, expected } => {
+                write!(f, "field `{}` should be a {}", field, expected)
+            }
+            RecordError::Syntax(pair) => write!(f, "can't parse `{}`", pair),
+            RecordError::Truncated => write!(f, "input ends in the middle of a field"),
+            RecordError::TrailingBytes(n) => write!(f, "{} bytes after the last field", n),
+            RecordError::InvalidTag(tag) => write!(f, "invalid type tag {}", tag),
+            RecordError::InvalidUtf8 => write!(f, "string is not valid UTF-8"),
+        }
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+pub trait Record {
+    fn fields(&self) -> Vec<(&'static str, FieldValue)>;
+
+    fn from_fields(fields: &[(String, FieldValue)]) -> Result<Self, RecordError>
+    where
+        Self: Sized;
+}
+
+/// Rejects a name outside `known` and a name given twice, for use at the start of `from_fields`.
+pub fn expect_fields(
+    fields: &[(String, FieldValue)],
+    known: &[&'static str],
+) -> Result<(), RecordError> {
+    for (i, (name, _)) in fields.iter().enumerate() {
+        if !known.contains(&name.as_str()) {
+            return Err(RecordError::UnknownField(name.clone()));
+        }
+        if fields[..i].iter().any(|(earlier, _)| earlier == name) {
+            return Err(RecordError::DuplicateField(name.clone()));
+        }
+    }
+    Ok(())
+}
+
+fn get<'f>(
+    fields: &'f [(String, FieldValue)],
+    name: &'static str,
+) -> Result<&'f FieldValue, RecordError> {
+    fields
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, value)| value)
+        .ok_or(RecordError::MissingField(name))
+}
+
+pub fn get_u64(fields: &[(String, FieldValue)], name: &'static str) -> Result<u64, RecordError> {
+    match get(fields, name)? {
+        FieldValue::U64(n) => Ok(*n),
+        _ => Err(RecordError::WrongType {
+            field: name,
+            expected: "u64",
+        }),
+    }
+}
+
+pub fn get_f64(fields: &[(String, FieldValue)], name: &'static str) -> Result<f64, RecordError> {
+    match get(fields, name)? {
+        FieldValue::F64(x) => Ok(*x),
+        _ => Err(RecordError::WrongType {
+            field: name,
+            expected: "f64",
+        }),
+    }
+}
+
+pub fn get_str(fields: &[(String, FieldValue)], name: &'static str) -> Result<String, RecordError> {
+    match get(fields, name)? {
+        FieldValue::Str(s) => Ok(s.clone()),
+        _ => Err(RecordError::WrongType {
+            field: name,
+            expected: "str",
+        }),
+    }
+}
+
+pub fn get_bool(fields: &[(String, FieldValue)], name: &'static str) -> Result<bool, RecordError> {
+    match get(fields, name)? {
+        FieldValue::Bool(b) => Ok(*b),
+        _ => Err(RecordError::WrongType {
+            field: name,
+            expected: "bool",
+        }),
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped: String = String::with_capacity(s.len());
+    for c in s.chars() {
+        if c == '\\' || c == ';' {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn unescape(s: &str) -> Option<String> {
+    let mut unescaped: String = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some(next @ ('\\' | ';')) => unescaped.push(next),
+                _ => return None,
+            }
+        } else {
+            unescaped.push(c);
+        }
+    }
+    Some(unescaped)
+}
+
+/// Splits at every `;` that is not escaped, the pieces keep their escapes.
+fn split_pairs(text: &str) -> Vec<&str> {
+    let mut pairs: Vec<&str> = vec![];
+    let mut start: usize = 0;
+    let mut escaped: bool = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ';' => {
+                pairs.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    pairs.push(&text[start..]);
+    pairs
+}
+
+pub fn to_kv_text<R: Record>(r: &R) -> String {
+    r.fields()
+        .iter()
+        .map(|(name, value)| {
+            let text: String = match value {
+                FieldValue::U64(n) => n.to_string(),
+                // `Display` of a float prints the shortest digits that parse back to it exactly
+                FieldValue::F64(x) => x.to_string(),
+                FieldValue::Str(s) => escape(s),
+                FieldValue::Bool(b) => b.to_string(),
+            };
+            format!("{}:{}={}", name, value.type_name(), text)
+        })
+        .collect::<Vec<String>>()
+        .join(";")
+}
+
+pub fn from_kv_text<R: Record>(text: &str) -> Result<R, RecordError> {
+    let mut fields: Vec<(String, FieldValue)> = vec![];
+    if !text.is_empty() {
+        for pair in split_pairs(text) {
+            let syntax = || RecordError::Syntax(pair.to_string());
+            let (key, raw) = pair.split_once('=').ok_or_else(syntax)?;
+            let (name, type_name) = key.split_once(':').ok_or_else(syntax)?;
+            let value: FieldValue = match type_name {
+                "u64" => FieldValue::U64(raw.parse().map_err(|_| syntax())?),
+                "f64" => FieldValue::F64(raw.parse().map_err(|_| syntax())?),
+                "str" => FieldValue::Str(unescape(raw).ok_or_else(syntax)?),
+                "bool" => FieldValue::Bool(raw.parse().map_err(|_| syntax())?),
+                _ => return Err(syntax()),
+            };
+            fields.push((name.to_string(), value));
+        }
+    }
+    R::from_fields(&fields)
+}
+
+fn put_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(bytes);
+}
+
+pub fn to_binary<R: Record>(r: &R) -> Vec<u8> {
+    let fields: Vec<(&'static str, FieldValue)> = r.fields();
+    let mut out: Vec<u8> = (fields.len() as u32).to_le_bytes().to_vec();
+    for (name, value) in &fields {
+        put_bytes(&mut out, name.as_bytes());
+        out.push(value.tag());
+        match value {
+            FieldValue::U64(n) => out.extend_from_slice(&n.to_le_bytes()),
+            FieldValue::F64(x) => out.extend_from_slice(&x.to_bits().to_le_bytes()),
+            FieldValue::Str(s) => put_bytes(&mut out, s.as_bytes()),
+            FieldValue::Bool(b) => out.push(*b as u8),
+        }
+    }
+    out
+}
+
+/// Reads the binary format front to back, every read checks that enough bytes are left.
+struct Reader<'b> {
+    bytes: &'b [u8],
+}
+
+impl<'b> Reader<'b> {
+    fn take(&mut self, n: usize) -> Result<&'b [u8], RecordError> {
+        if self.bytes.len() < n {
+            return Err(RecordError::Truncated);
+        }
+        let (taken, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, RecordError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, RecordError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, RecordError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn string(&mut self) -> Result<String, RecordError> {
+        let len: usize = self.u32()? as usize;
+        let bytes: &[u8] = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| RecordError::InvalidUtf8)
+    }
+}
+
+pub fn from_binary<R: Record>(bytes: &[u8]) -> Result<R, RecordError> {
+    let mut reader: Reader = Reader { bytes };
+    let count: u32 = reader.u32()?;
+    let mut fields: Vec<(String, FieldValue)> = vec![];
+    for _ in 0..count {
+        let name: String = reader.string()?;
+        let value: FieldValue = match reader.u8()? {
+            0 => FieldValue::U64(reader.u64()?),
+            1 => FieldValue::F64(f64::from_bits(reader.u64()?)),
+            2 => FieldValue::Str(reader.string()?),
+            3 => match reader.u8()? {
+                0 => FieldValue::Bool(false),
+                1 => FieldValue::Bool(true),
+                other => return Err(RecordError::InvalidTag(other)),
+            },
+            tag => return Err(RecordError::InvalidTag(tag)),
+        };
+        fields.push((name, value));
+    }
+    if !reader.bytes.is_empty() {
+        return Err(RecordError::TrailingBytes(reader.bytes.len()));
+    }
+    R::from_fields(&fields)
+}
+
+impl Record for Facebook {
+    fn fields(&self) -> Vec<(&'static str, FieldValue)> {
+        vec![
+            ("headline", FieldValue::Str(self.headline.clone())),
+            ("author", FieldValue::Str(self.author.clone())),
+        ]
+    }
+
+    fn from_fields(fields: &[(String, FieldValue)]) -> Result<Self, RecordError> {
+        expect_fields(fields, &["headline", "author"])?;
+        Ok(Facebook {
+            headline: get_str(fields, "headline")?,
+            author: get_str(fields, "author")?,
+        })
+    }
+}
+
+impl Record for Player {
+    fn fields(&self) -> Vec<(&'static str, FieldValue)> {
+        vec![
+            ("name", FieldValue::Str(self.name.clone())),
+            ("rank", FieldValue::U64(self.rank as u64)),
+        ]
+    }
+
+    fn from_fields(fields: &[(String, FieldValue)]) -> Result<Self, RecordError> {
+        expect_fields(fields, &["name", "rank"])?;
+        let name: String = get_str(fields, "name")?;
+        let rank: usize =
+            usize::try_from(get_u64(fields, "rank")?).map_err(|_| RecordError::WrongType {
+                field: "rank",
+                expected: "usize",
+            })?;
+        Ok(Player { name, rank })
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use structs::builder::PlayerBuilder;
+
+    /// Uses every kind of value, neither `Facebook` nor `Player` has a float or a bool.
+    #[derive(Debug, PartialEq)]
+    struct Reading {
+        sensor: String,
+        value: f64,
+        valid: bool,
+        seq: u64,
+    }
+
+    impl Record for Reading {
+        fn fields(&self) -> Vec<(&'static str, FieldValue)> {
+            vec![
+                ("sensor", FieldValue::Str(self.sensor.clone())),
+                ("value", FieldValue::F64(self.value)),
+                ("valid", FieldValue::Bool(self.valid)),
+                ("seq", FieldValue::U64(self.seq)),
+            ]
+        }
+
+        fn from_fields(fields: &[(String, FieldValue)]) -> Result<Self, RecordError> {
+            expect_fields(fields, &["sensor", "value", "valid", "seq"])?;
+            Ok(Reading {
+                sensor: get_str(fields, "sensor")?,
+                value: get_f64(fields, "value")?,
+                valid: get_bool(fields, "valid")?,
+                seq: get_u64(fields, "seq")?,
+            })
+        }
+    }
+
+    fn new_player(name: &str, rank: usize) -> Player {
+        PlayerBuilder::new().name(name).rank(rank).build()
+    }
+
+    fn facebook() -> Facebook {
+        Facebook {
+            headline: String::from("Rust 2024 edition released"),
+            author: String::from("ann"),
+        }
+    }
+
+    #[test]
+    fn run_serialize_round_trips() {
+        let post: Facebook = facebook();
+        let text: String = to_kv_text(&post);
+        assert_eq!(
+            text,
+            "headline:str=Rust 2024 edition released;author:str=ann"
+        );
+        assert_eq!(
+            from_kv_text::<Facebook>(&text).unwrap().fields(),
+            post.fields()
+        );
+        assert_eq!(
+            from_binary::<Facebook>(&to_binary(&post)).unwrap().fields(),
+            post.fields()
+        );
+
+        let player: Player = new_player("rust", 1);
+        assert_eq!(to_kv_text(&player), "name:str=rust;rank:u64=1");
+        assert_eq!(
+            from_kv_text::<Player>(&to_kv_text(&player)),
+            Ok(new_player("rust", 1))
+        );
+        assert_eq!(
+            from_binary::<Player>(&to_binary(&player)),
+            Ok(new_player("rust", 1))
+        );
+
+        for value in [0.1 + 0.2, -0.0, 1e300, f64::MIN_POSITIVE, f64::INFINITY] {
+            let reading = Reading {
+                sensor: String::from("t1"),
+                value,
+                valid: true,
+                seq: u64::MAX,
+            };
+            let from_text: Reading = from_kv_text(&to_kv_text(&reading)).unwrap();
+            let from_binary: Reading = from_binary(&to_binary(&reading)).unwrap();
+            assert_eq!(from_text.value.to_bits(), value.to_bits());
+            assert_eq!(from_text, reading);
+            assert_eq!(from_binary, reading);
+        }
+    }
+
+    #[test]
+    fn run_serialize_escaping() {
+        let post: Facebook = Facebook {
+            headline: String::from(r"a;b=c\d;"),
+            author: String::from("名前"),
+        };
+        let text: String = to_kv_text(&post);
+        assert_eq!(text, r"headline:str=a\;b=c\\d\;;author:str=名前");
+        let decoded: Facebook = from_kv_text(&text).unwrap();
+        assert_eq!(decoded.headline, r"a;b=c\d;");
+        assert_eq!(decoded.author, "名前");
+        assert_eq!(
+            from_binary::<Facebook>(&to_binary(&post)).unwrap().fields(),
+            post.fields()
+        );
+
+        let unknown_escape: &str = r"headline:str=a\b;author:str=ann";
+        assert_eq!(
+            from_kv_text::<Facebook>(unknown_escape).err(),
+            Some(RecordError::Syntax(String::from(r"headline:str=a\b")))
+        );
+    }
+
+    #[test]
+    fn run_serialize_rejected_fields() {
+        let extra: &str = "name:str=rust;rank:u64=1;team:str=core";
+        assert_eq!(
+            from_kv_text::<Player>(extra),
+            Err(RecordError::UnknownField(String::from("team")))
+        );
+        assert_eq!(
+            from_kv_text::<Player>("name:str=rust;name:str=go;rank:u64=1"),
+            Err(RecordError::DuplicateField(String::from("name")))
+        );
+        assert_eq!(
+            from_kv_text::<Player>("name:str=rust"),
+            Err(RecordError::MissingField("rank"))
+        );
+        assert_eq!(
+            from_kv_text::<Player>("name:str=rust;rank:str=1"),
+            Err(RecordError::WrongType {
+                field: "rank",
+                expected: "u64"
+            })
+        );
+        assert_eq!(
+            from_kv_text::<Player>("name:str=rust;rank:u64=-1"),
+            Err(RecordError::Syntax(String::from("rank:u64=-1")))
+        );
+        assert_eq!(
+            from_kv_text::<Player>("name=rust;rank:u64=1"),
+            Err(RecordError::Syntax(String::from("name=rust")))
+        );
+        assert_eq!(
+            from_kv_text::<Player>(""),
+            Err(RecordError::MissingField("name"))
+        );
+    }
+
+    #[test]
+    fn run_serialize_truncated_binary() {
+        let bytes: Vec<u8> = to_binary(&new_player("rust", 7));
+        // every proper prefix ends inside the count, a name, a tag or a value
+        for len in 0..bytes.len() {
+            assert_eq!(
+                from_binary::<Player>(&bytes[..len]),
+                Err(RecordError::Truncated)
+            );
+        }
+
+        let mut longer: Vec<u8> = bytes.clone();
+        longer.push(0);
+        assert_eq!(
+            from_binary::<Player>(&longer),
+            Err(RecordError::TrailingBytes(1))
+        );
+
+        // the tag of the first field follows the count and the 4 + 4 bytes of its name
+        let mut bad_tag: Vec<u8> = bytes.clone();
+        bad_tag[12] = 9;
+        assert_eq!(
+            from_binary::<Player>(&bad_tag),
+            Err(RecordError::InvalidTag(9))
+        );
+
+        let mut bad_utf8: Vec<u8> = bytes;
+        bad_utf8[8] = 0xff;
+        assert_eq!(
+            from_binary::<Player>(&bad_utf8),
+            Err(RecordError::InvalidUtf8)
+        );
+    }
+}
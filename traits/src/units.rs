@@ -0,0 +1,155 @@
+//! A quantity carries its unit in its type: `Quantity<Meters>` and `Quantity<Seconds>` are
+//! both an `f64`, but different types, so mixing them up is a compile error instead of a
+//! wrong number. The unit is a zero-sized marker held in `PhantomData`, it costs nothing at
+//! runtime.
+//!
+//! Adding and subtracting is implemented once for every unit, `Quantity<U> + Quantity<U>`,
+//! so both sides must have the same unit. Other operations combine units, and each
+//! combination that makes sense gets its own impl:
+//!
+//! ```text
+//! Quantity<Meters>          / Quantity<Seconds>  ->  Quantity<MetersPerSecond>
+//! Quantity<MetersPerSecond> * Quantity<Seconds>  ->  Quantity<Meters>
+//! Quantity<U>               * f64                ->  Quantity<U>
+//! ```
+//!
+//! Adding meters to seconds does not compile, error[E0308]: mismatched types:
+//! ```compile_fail,E0308
+//! use traits::units::{Meters, Quantity, Seconds};
+//!
+//! let sum = Quantity::<Meters>::new(1.0) + Quantity::<Seconds>::new(1.0);
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Sub};
+
+pub trait Unit {
+    const SYMBOL: &'static str;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Meters;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Seconds;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetersPerSecond;
+
+impl Unit for Meters {
+    const SYMBOL: &'static str = "m";
+}
+
+impl Unit for Seconds {
+    const SYMBOL: &'static str = "s";
+}
+
+impl Unit for MetersPerSecond {
+    const SYMBOL: &'static str = "m/s";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantity<U>(f64, PhantomData<U>);
+
+impl<U> Quantity<U> {
+    pub fn new(value: f64) -> Self {
+        Quantity(value, PhantomData)
+    }
+
+    pub fn value(self) -> f64 {
+        self.0
+    }
+}
+
+impl<U> Add for Quantity<U> {
+    type Output = Quantity<U>;
+
+    fn add(self, rhs: Quantity<U>) -> Quantity<U> {
+        Quantity::new(self.0 + rhs.0)
+    }
+}
+
+impl<U> Sub for Quantity<U> {
+    type Output = Quantity<U>;
+
+    fn sub(self, rhs: Quantity<U>) -> Quantity<U> {
+        Quantity::new(self.0 - rhs.0)
+    }
+}
+
+impl<U> Mul<f64> for Quantity<U> {
+    type Output = Quantity<U>;
+
+    fn mul(self, rhs: f64) -> Quantity<U> {
+        Quantity::new(self.0 * rhs)
+    }
+}
+
+impl Div<Quantity<Seconds>> for Quantity<Meters> {
+    type Output = Quantity<MetersPerSecond>;
+
+    fn div(self, rhs: Quantity<Seconds>) -> Quantity<MetersPerSecond> {
+        Quantity::new(self.0 / rhs.0)
+    }
+}
+
+impl Mul<Quantity<Seconds>> for Quantity<MetersPerSecond> {
+    type Output = Quantity<Meters>;
+
+    fn mul(self, rhs: Quantity<Seconds>) -> Quantity<Meters> {
+        Quantity::new(self.0 * rhs.0)
+    }
+}
+
+/// The value is formatted with the caller's options, `{:.1}` included, then the symbol.
+impl<U: Unit> fmt::Display for Quantity<U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)?;
+        write!(f, " {}", U::SYMBOL)
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn run_units_same_unit_arithmetic() {
+        let a: Quantity<Meters> = Quantity::new(3.5);
+        let b: Quantity<Meters> = Quantity::new(1.5);
+        assert_eq!(a + b, Quantity::new(5.0));
+        assert_eq!(a - b, Quantity::new(2.0));
+        assert_eq!((b - a).value(), -2.0);
+        assert_eq!(a * 2.0, Quantity::new(7.0));
+        assert_eq!(a * 0.0, Quantity::new(0.0));
+    }
+
+    #[test]
+    fn run_units_velocity() {
+        let distance: Quantity<Meters> = Quantity::new(100.0);
+        let time: Quantity<Seconds> = Quantity::new(8.0);
+        let velocity: Quantity<MetersPerSecond> = distance / time;
+        assert_eq!(velocity.value(), 12.5);
+        // and back
+        assert_eq!(velocity * time, distance);
+        assert_eq!(velocity * (time * 2.0), distance * 2.0);
+    }
+
+    #[test]
+    fn run_units_display() {
+        assert_eq!(Quantity::<Meters>::new(3.5).to_string(), "3.5 m");
+        assert_eq!(Quantity::<Seconds>::new(2.0).to_string(), "2 s");
+        assert_eq!(
+            format!("{:.2}", Quantity::<MetersPerSecond>::new(12.5)),
+            "12.50 m/s"
+        );
+        assert_eq!(MetersPerSecond::SYMBOL, "m/s");
+    }
+
+    #[test]
+    fn run_units_zero_sized_marker() {
+        use std::mem::size_of;
+        assert_eq!(size_of::<Meters>(), 0);
+        assert_eq!(size_of::<Quantity<Meters>>(), size_of::<f64>());
+        assert_eq!(size_of::<Quantity<MetersPerSecond>>(), size_of::<f64>());
+    }
+}
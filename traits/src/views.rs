@@ -0,0 +1,264 @@
+//! A read-only view trait over different storage layouts.
+//!
+//! `Store::get` returns `Option<&[u8]>` borrowed from `&self`: by the elision rules the slice
+//! lives as long as the store, whatever the store does inside. A `HashMap<String, Vec<u8>>`
+//! lends out one of its vectors, a `FlatStore` lends a sub-slice of its single buffer, and the
+//! generic consumers can't tell the difference.
+//!
+//! A `FlatStore` keeps all values back to back in one `Vec<u8>`, plus an index of byte ranges.
+//! That is one allocation instead of one per value, at the cost of an index that must be
+//! validated: a range past the end would make `get` panic, and overlapping ranges would make two
+//! keys share bytes. `FlatStore::new` checks both once, so `get` can slice without checking.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::Range;
+
+pub trait Store {
+    fn get(&self, key: &str) -> Option<&[u8]>;
+    fn keys(&self) -> Vec<&str>;
+}
+
+impl Store for HashMap<String, Vec<u8>> {
+    fn get(&self, key: &str) -> Option<&[u8]> {
+        // `HashMap::get` is inherent, so this calls it rather than recursing
+        HashMap::get(self, key).map(|v| v.as_slice())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        HashMap::keys(self).map(|k| k.as_str()).collect()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum IndexError {
+    Reversed { key: String },
+    OutOfBounds { key: String, end: usize, len: usize },
+    Overlap { first: String, second: String },
+    DuplicateKey(String),
+}
+
+impl fmt::Display for IndexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IndexError::Reversed { key } => write!(f, "range of `{}` ends before it starts", key),
+            IndexError::OutOfBounds { key, end, len } => {
+                write!(
+                    f,
+                    "range of `{}` ends at {}, past the {} bytes of data",
+                    key, end, len
+                )
+            }
+            IndexError::Overlap { first, second } => {
+                write!(f, "ranges of `{}` and `{}` overlap", first, second)
+            }
+            IndexError::DuplicateKey(key) => write!(f, "key `{}` appears twice", key),
+        }
+    }
+}
+
+impl std::error::Error for IndexError {}
+
+#[derive(Debug)]
+pub struct FlatStore {
+    data: Vec<u8>,
+    index: Vec<(String, Range<usize>)>,
+}
+
+impl FlatStore {
+    /// Validates the index against `data`: every range within bounds, no two ranges sharing a
+    /// byte, no key twice. Empty ranges share no byte, so they never overlap.
+    pub fn new(data: Vec<u8>, index: Vec<(String, Range<usize>)>) -> Result<Self, IndexError> {
+        let mut seen: HashSet<&str> = HashSet::new();
+        for (key, range) in &index {
+            if range.start > range.end {
+                return Err(IndexError::Reversed { key: key.clone() });
+            }
+            if range.end > data.len() {
+                return Err(IndexError::OutOfBounds {
+                    key: key.clone(),
+                    end: range.end,
+                    len: data.len(),
+                });
+            }
+            if !seen.insert(key) {
+                return Err(IndexError::DuplicateKey(key.clone()));
+            }
+        }
+
+        // sorted by start, if no range overlaps the next one then no two ranges overlap at all
+        let mut sorted: Vec<&(String, Range<usize>)> = index
+            .iter()
+            .filter(|(_, range)| !range.is_empty())
+            .collect();
+        sorted.sort_by_key(|(_, range)| range.start);
+        for pair in sorted.windows(2) {
+            let ((first, a), (second, b)) = (pair[0], pair[1]);
+            if b.start < a.end {
+                return Err(IndexError::Overlap {
+                    first: first.clone(),
+                    second: second.clone(),
+                });
+            }
+        }
+        Ok(FlatStore { data, index })
+    }
+
+    /// Packs the values one after another, the index can't be wrong by construction.
+    pub fn from_entries(entries: &[(&str, &[u8])]) -> Result<Self, IndexError> {
+        let mut data: Vec<u8> = Vec::with_capacity(entries.iter().map(|(_, v)| v.len()).sum());
+        let mut index: Vec<(String, Range<usize>)> = Vec::with_capacity(entries.len());
+        for (key, value) in entries {
+            let start: usize = data.len();
+            data.extend_from_slice(value);
+            index.push((key.to_string(), start..data.len()));
+        }
+        // only a duplicate key can still be rejected
+        Self::new(data, index)
+    }
+}
+
+impl Store for FlatStore {
+    fn get(&self, key: &str) -> Option<&[u8]> {
+        self.index
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, range)| &self.data[range.clone()])
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.index.iter().map(|(k, _)| k.as_str()).collect()
+    }
+}
+
+pub fn total_bytes<S: Store>(s: &S) -> usize {
+    s.keys()
+        .iter()
+        .filter_map(|k| s.get(k))
+        .map(|v| v.len())
+        .sum()
+}
+
+fn missing_from<S: Store>(keys: Vec<&str>, other: &S) -> Vec<String> {
+    let mut missing: Vec<String> = keys
+        .into_iter()
+        .filter(|k| other.get(k).is_none())
+        .map(String::from)
+        .collect();
+    missing.sort();
+    missing
+}
+
+/// Returns the keys only in `a` and the keys only in `b`, both sorted, since the order of
+/// `keys` depends on the store.
+pub fn diff_keys<A: Store, B: Store>(a: &A, b: &B) -> (Vec<String>, Vec<String>) {
+    (missing_from(a.keys(), b), missing_from(b.keys(), a))
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    fn entries() -> Vec<(&'static str, &'static [u8])> {
+        vec![
+            ("name", b"rust"),
+            ("empty", b""),
+            ("year", b"2015"),
+            ("logo", b"\xf0\x9f\xa6\x80"),
+        ]
+    }
+
+    #[test]
+    fn run_views_implementations_agree() {
+        let map: HashMap<String, Vec<u8>> = entries()
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_vec()))
+            .collect();
+        let flat: FlatStore = FlatStore::from_entries(&entries()).unwrap();
+
+        assert_eq!(total_bytes(&map), 12);
+        assert_eq!(total_bytes(&flat), 12);
+        assert_eq!(diff_keys(&map, &flat), (vec![], vec![]));
+        for key in map.keys() {
+            assert_eq!(Store::get(&map, key), flat.get(key));
+        }
+        assert_eq!(flat.get("missing"), None);
+        assert_eq!(flat.keys(), vec!["name", "empty", "year", "logo"]);
+
+        let mut smaller: HashMap<String, Vec<u8>> = map.clone();
+        smaller.remove("year");
+        smaller.insert(String::from("edition"), b"2021".to_vec());
+        let diff = diff_keys(&flat, &smaller);
+        assert_eq!(
+            diff,
+            (vec![String::from("year")], vec![String::from("edition")])
+        );
+    }
+
+    #[test]
+    fn run_views_flat_store_validation() {
+        let data: Vec<u8> = b"abcdef".to_vec();
+        let index = |entries: &[(&str, Range<usize>)]| -> Vec<(String, Range<usize>)> {
+            entries
+                .iter()
+                .map(|(k, r)| (k.to_string(), r.clone()))
+                .collect()
+        };
+
+        let store = FlatStore::new(
+            data.clone(),
+            index(&[("b", 3..6), ("a", 0..3), ("e", 3..3)]),
+        );
+        assert_eq!(store.unwrap().get("b"), Some(&b"def"[..]));
+
+        assert_eq!(
+            FlatStore::new(data.clone(), index(&[("a", 0..4), ("b", 3..6)])).unwrap_err(),
+            IndexError::Overlap {
+                first: String::from("a"),
+                second: String::from("b")
+            }
+        );
+        assert_eq!(
+            FlatStore::new(data.clone(), index(&[("a", 1..2), ("b", 0..6)])).unwrap_err(),
+            IndexError::Overlap {
+                first: String::from("b"),
+                second: String::from("a")
+            }
+        );
+        assert_eq!(
+            FlatStore::new(data.clone(), index(&[("a", 4..7)])).unwrap_err(),
+            IndexError::OutOfBounds {
+                key: String::from("a"),
+                end: 7,
+                len: 6
+            }
+        );
+        let reversed = Range { start: 4, end: 2 };
+        assert_eq!(
+            FlatStore::new(data.clone(), index(&[("a", reversed)])).unwrap_err(),
+            IndexError::Reversed {
+                key: String::from("a")
+            }
+        );
+        assert_eq!(
+            FlatStore::from_entries(&[("a", b"x"), ("a", b"y")]).unwrap_err(),
+            IndexError::DuplicateKey(String::from("a"))
+        );
+    }
+
+    #[test]
+    fn run_views_empty_store() {
+        let map: HashMap<String, Vec<u8>> = HashMap::new();
+        let flat: FlatStore = FlatStore::new(vec![], vec![]).unwrap();
+        assert_eq!(total_bytes(&map), 0);
+        assert_eq!(total_bytes(&flat), 0);
+        assert!(flat.keys().is_empty());
+        assert_eq!(flat.get(""), None);
+        assert_eq!(diff_keys(&map, &flat), (vec![], vec![]));
+
+        let other: FlatStore = FlatStore::from_entries(&entries()).unwrap();
+        let (only_empty, only_other) = diff_keys(&flat, &other);
+        assert!(only_empty.is_empty());
+        assert_eq!(only_other, vec!["empty", "logo", "name", "year"]);
+    }
+}
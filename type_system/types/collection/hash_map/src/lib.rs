@@ -87,6 +87,17 @@ pub mod update_hash_map {
         dbg!(map);
     }
 
+    /// The same counting as `entry_for_counting` on a `DetMap`, so the debug output, order
+    /// included, is the same on every run.
+    pub fn entry_for_counting_deterministic() -> String {
+        let text: &str = "hello world about world";
+        let mut map: crate::deterministic::DetMap<&str, u32> = crate::deterministic::det_map();
+        for word in text.split_whitespace() {
+            *map.entry(word).or_insert(0) += 1;
+        }
+        format!("{:?}", map)
+    }
+
     pub fn entry_and_or_insert_theory() {
         // ---- testing::run_entry_and_or_insert_theory stdout ----
         // {"rust": false}
@@ -135,6 +146,18 @@ pub mod update_hash_map {
         println!("{:?}", m);
     }
 
+    /// `or_default` is `or_insert(V::default())` for a `V: Default`: on a vacant entry it inserts
+    /// the default value, an empty `Vec` here, and either way returns a mutable reference to the
+    /// value. Unlike `or_insert(Vec::new())` it needs no argument, and unlike `or_insert_with`
+    /// no closure, so grouping values by key is one line per value.
+    pub fn accumulate_lists(pairs: &[(&str, i32)]) -> HashMap<String, Vec<i32>> {
+        let mut map: HashMap<String, Vec<i32>> = HashMap::new();
+        for &(k, v) in pairs {
+            map.entry(k.to_string()).or_default().push(v);
+        }
+        map
+    }
+
     /// Removes a key from map, returning the value at the key if the key was previously in the map.
     ///
     /// The key may be any borrowed form of the map's key type, but [Hash] and [Eq] on the borrowed
@@ -227,6 +250,485 @@ pub mod ownership_hash_map {
     }
 }
 
+pub mod heavy_hitters {
+    //! The Misra–Gries summary finds the frequent keys of a stream while keeping at most `k`
+    //! counters, however many distinct keys the stream has.
+    //!
+    //! When a new key arrives and all `k` counters are taken, every counter is decremented and the
+    //! ones reaching zero are evicted. Each decrement round cancels `k + 1` occurrences (one per
+    //! counter plus the new key), so over a stream of `n` keys there are at most `n / (k + 1)`
+    //! rounds. Hence for every key:
+    //!
+    //! ```text
+    //! estimate <= exact <= estimate + n / (k + 1)
+    //! ```
+    //!
+    //! and any key occurring more than `n / (k + 1)` times is certain to still hold a counter.
+
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    pub struct TopK<K: Hash + Eq + Clone> {
+        counters: HashMap<K, u64>,
+        k: usize,
+    }
+
+    impl<K: Hash + Eq + Clone> TopK<K> {
+        pub fn new(k: usize) -> Self {
+            Self {
+                counters: HashMap::with_capacity(k),
+                k,
+            }
+        }
+
+        pub fn observe(&mut self, key: K) {
+            if let Some(count) = self.counters.get_mut(&key) {
+                *count += 1;
+            } else if self.counters.len() < self.k {
+                self.counters.insert(key, 1);
+            } else {
+                self.counters.retain(|_, count| {
+                    *count -= 1;
+                    *count > 0
+                });
+            }
+        }
+
+        /// The kept keys with their lower-bound counts, highest count first. Keys with equal
+        /// counts come in no particular order.
+        pub fn estimates(&self) -> Vec<(K, u64)> {
+            let mut estimates: Vec<(K, u64)> = self
+                .counters
+                .iter()
+                .map(|(key, &count)| (key.clone(), count))
+                .collect();
+            estimates.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+            estimates
+        }
+
+        /// Keys whose lower-bound count alone exceeds `total / k`, so they are heavy no matter how
+        /// much the summary underestimated them.
+        pub fn guaranteed_heavy(&self, total: u64) -> Vec<K> {
+            self.estimates()
+                .into_iter()
+                .filter(|(_, count)| count * self.k as u64 > total)
+                .map(|(key, _)| key)
+                .collect()
+        }
+
+        /// Adds the counters of `other`, then, if more than `k` keys remain, subtracts the
+        /// `(k + 1)`-th largest count from every counter and drops those that are no longer
+        /// positive. The merged summary keeps the same error bound over the combined stream.
+        pub fn merge(&mut self, other: &TopK<K>) {
+            for (key, &count) in &other.counters {
+                *self.counters.entry(key.clone()).or_insert(0) += count;
+            }
+            if self.counters.len() > self.k {
+                let mut counts: Vec<u64> = self.counters.values().copied().collect();
+                counts.sort_unstable_by(|a, b| b.cmp(a));
+                let cut: u64 = counts[self.k];
+                self.counters.retain(|_, count| {
+                    *count = count.saturating_sub(cut);
+                    *count > 0
+                });
+            }
+        }
+    }
+
+    /// Counts every key exactly, the oracle the summary is checked against.
+    pub fn exact_counts<K: Hash + Eq>(stream: impl IntoIterator<Item = K>) -> HashMap<K, u64> {
+        let mut counts: HashMap<K, u64> = HashMap::new();
+        for key in stream {
+            *counts.entry(key).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+pub mod deterministic {
+    //! `HashMap::new` uses `RandomState`, which picks random SipHash keys for every map, so the
+    //! iteration order changes from run to run and a `{:?}` of the map can't be asserted.
+    //!
+    //! The third type parameter of `HashMap<K, V, S>` is the `BuildHasher` that creates a fresh
+    //! `Hasher` for every key. `FixedState` builds a `DefaultHasher::new()`, whose keys are fixed,
+    //! and writes its own two seed words into it before the key is hashed. Two maps with the same
+    //! seed and the same sequence of inserts end up with the same layout, hence the same order.
+    //!
+    //! The algorithm behind `DefaultHasher` is not specified and may change between Rust releases,
+    //! so the order is only stable for a given toolchain. A predictable hash also lets anyone who
+    //! controls the keys craft collisions, keep `RandomState` for maps fed with untrusted input.
+
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashMap;
+    use std::fmt::Debug;
+    use std::hash::{BuildHasher, Hash, Hasher};
+
+    pub const DEFAULT_SEED: u64 = 0x5eed;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct FixedState(pub u64, pub u64);
+
+    impl FixedState {
+        /// Spreads one seed into two words, so nearby seeds still give unrelated keys.
+        pub fn with_seed(seed: u64) -> Self {
+            FixedState(seed, seed.rotate_left(32) ^ 0x9e37_79b9_7f4a_7c15)
+        }
+    }
+
+    impl BuildHasher for FixedState {
+        type Hasher = DefaultHasher;
+
+        fn build_hasher(&self) -> DefaultHasher {
+            let mut hasher: DefaultHasher = DefaultHasher::new();
+            hasher.write_u64(self.0);
+            hasher.write_u64(self.1);
+            hasher
+        }
+    }
+
+    pub type DetMap<K, V> = HashMap<K, V, FixedState>;
+
+    pub fn det_map<K, V>() -> DetMap<K, V> {
+        det_map_with_seed(DEFAULT_SEED)
+    }
+
+    pub fn det_map_with_seed<K, V>(seed: u64) -> DetMap<K, V> {
+        HashMap::with_hasher(FixedState::with_seed(seed))
+    }
+
+    /// Hashes the `Debug` form of every entry in iteration order, so two maps with the same
+    /// entries get the same fingerprint only if they also iterate in the same order.
+    pub fn iteration_fingerprint<K: Hash + Eq + Debug, V: Debug>(m: &DetMap<K, V>) -> u64 {
+        let mut hasher: DefaultHasher = DefaultHasher::new();
+        for (k, v) in m {
+            format!("{:?}={:?}", k, v).hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
+pub mod joins {
+    //! SQL-style joins of two maps on their keys. Each key is present at most once in a map, so
+    //! a key yields at most one row, except in `join_multi` where each key carries several values
+    //! and yields their cross product:
+    //!
+    //! ```text
+    //! inner  keys in both              (A, B)
+    //! left   keys in left              (A, Option<B>)
+    //! full   keys in either            (Option<A>, Option<B>)
+    //! ```
+    //!
+    //! Each join walks one map and looks every key up in the other, O(1) per lookup, so the cost
+    //! is linear in the sizes of the maps rather than their product.
+
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    pub fn inner_join<K: Hash + Eq + Clone, A: Clone, B: Clone>(
+        left: &HashMap<K, A>,
+        right: &HashMap<K, B>,
+    ) -> HashMap<K, (A, B)> {
+        left.iter()
+            .filter_map(|(key, a)| {
+                right
+                    .get(key)
+                    .map(|b| (key.clone(), (a.clone(), b.clone())))
+            })
+            .collect()
+    }
+
+    pub fn left_join<K: Hash + Eq + Clone, A: Clone, B: Clone>(
+        left: &HashMap<K, A>,
+        right: &HashMap<K, B>,
+    ) -> HashMap<K, (A, Option<B>)> {
+        left.iter()
+            .map(|(key, a)| (key.clone(), (a.clone(), right.get(key).cloned())))
+            .collect()
+    }
+
+    pub fn full_join<K: Hash + Eq + Clone, A: Clone, B: Clone>(
+        left: &HashMap<K, A>,
+        right: &HashMap<K, B>,
+    ) -> HashMap<K, (Option<A>, Option<B>)> {
+        let mut joined: HashMap<K, (Option<A>, Option<B>)> = left
+            .iter()
+            .map(|(key, a)| (key.clone(), (Some(a.clone()), right.get(key).cloned())))
+            .collect();
+        // the keys in both maps are already there
+        for (key, b) in right {
+            if !left.contains_key(key) {
+                joined.insert(key.clone(), (None, Some(b.clone())));
+            }
+        }
+        joined
+    }
+
+    /// Every pair of a left value and a right value, per key in both maps: `m` values on the left
+    /// and `n` on the right give `m * n` pairs, none at all when either side is empty.
+    pub fn join_multi<K: Hash + Eq + Clone, A: Clone, B: Clone>(
+        left: &HashMap<K, Vec<A>>,
+        right: &HashMap<K, Vec<B>>,
+    ) -> HashMap<K, Vec<(A, B)>> {
+        inner_join(left, right)
+            .into_iter()
+            .map(|(key, (xs, ys))| {
+                let pairs: Vec<(A, B)> = xs
+                    .iter()
+                    .flat_map(|a| ys.iter().map(move |b| (a.clone(), b.clone())))
+                    .collect();
+                (key, pairs)
+            })
+            .collect()
+    }
+
+    /// How many keys a join would match, and how many are only on one side.
+    #[derive(Debug, PartialEq)]
+    pub struct JoinStats {
+        pub matched: usize,
+        pub left_only: usize,
+        pub right_only: usize,
+    }
+
+    /// Counts keys without building any joined map, the values are not looked at.
+    pub fn join_report<K: Hash + Eq, A, B>(
+        left: &HashMap<K, A>,
+        right: &HashMap<K, B>,
+    ) -> JoinStats {
+        let matched: usize = left.keys().filter(|key| right.contains_key(*key)).count();
+        JoinStats {
+            matched,
+            left_only: left.len() - matched,
+            right_only: right.len() - matched,
+        }
+    }
+}
+
+pub mod map_diff {
+    //! The difference between two versions of a map, as the entries to add, to remove and to
+    //! change. Applying it to the old map gives the new one:
+    //!
+    //! ```text
+    //! old {a: 1, b: 2, c: 3}      added   [(d, 4)]
+    //! new {a: 1, c: 30, d: 4}     removed [(b, 2)]
+    //!                             changed [(c, 3, 30)]
+    //! ```
+    //!
+    //! A `HashMap` iterates in an unspecified order, so the entries are sorted to make a diff
+    //! deterministic: by the key itself in `diff_maps`, which needs `K: Ord`, or by a key the
+    //! caller extracts in `diff_maps_by_key`, for key types that are not `Ord`.
+
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    #[derive(Debug, PartialEq)]
+    pub struct MapDiff<K, V> {
+        pub added: Vec<(K, V)>,
+        pub removed: Vec<(K, V)>,
+        /// The key, the old value and the new value.
+        pub changed: Vec<(K, V, V)>,
+    }
+
+    impl<K, V> MapDiff<K, V> {
+        /// True when the two maps were equal.
+        pub fn is_empty(&self) -> bool {
+            self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+        }
+    }
+
+    pub fn diff_maps_by_key<K, V, S, F>(
+        old: &HashMap<K, V>,
+        new: &HashMap<K, V>,
+        sort_key: F,
+    ) -> MapDiff<K, V>
+    where
+        K: Hash + Eq + Clone,
+        V: PartialEq + Clone,
+        S: Ord,
+        F: Fn(&K) -> S,
+    {
+        let mut diff: MapDiff<K, V> = MapDiff {
+            added: vec![],
+            removed: vec![],
+            changed: vec![],
+        };
+        for (key, old_value) in old {
+            match new.get(key) {
+                None => diff.removed.push((key.clone(), old_value.clone())),
+                Some(new_value) if new_value != old_value => {
+                    diff.changed
+                        .push((key.clone(), old_value.clone(), new_value.clone()));
+                }
+                Some(_) => {}
+            }
+        }
+        for (key, new_value) in new {
+            if !old.contains_key(key) {
+                diff.added.push((key.clone(), new_value.clone()));
+            }
+        }
+        diff.added.sort_by_key(|(key, _)| sort_key(key));
+        diff.removed.sort_by_key(|(key, _)| sort_key(key));
+        diff.changed.sort_by_key(|(key, _, _)| sort_key(key));
+        diff
+    }
+
+    pub fn diff_maps<K, V>(old: &HashMap<K, V>, new: &HashMap<K, V>) -> MapDiff<K, V>
+    where
+        K: Hash + Eq + Clone + Ord,
+        V: PartialEq + Clone,
+    {
+        diff_maps_by_key(old, new, K::clone)
+    }
+
+    /// Applies a diff computed against `base`. The old values it records are not checked, a
+    /// changed entry simply gets its new value.
+    pub fn apply_diff<K, V>(base: &mut HashMap<K, V>, diff: &MapDiff<K, V>)
+    where
+        K: Hash + Eq + Clone,
+        V: Clone,
+    {
+        for (key, _) in &diff.removed {
+            base.remove(key);
+        }
+        for (key, value) in &diff.added {
+            base.insert(key.clone(), value.clone());
+        }
+        for (key, _, value) in &diff.changed {
+            base.insert(key.clone(), value.clone());
+        }
+    }
+}
+
+pub mod multiset {
+    //! A multiset, or bag, is a set that remembers how many times each item was added. It is the
+    //! counting map of `update_hash_map::entry_for_counting`, item to count, with the set
+    //! operations defined on the counts:
+    //!
+    //! ```text
+    //! a {x: 2, y: 1}           union         {x: 2, y: 3, z: 1}    max of the counts
+    //! b {x: 1, y: 3, z: 1}     intersection  {x: 1, y: 1}          min of the counts
+    //!                          sum           {x: 3, y: 4, z: 1}    counts added
+    //! ```
+    //!
+    //! An item whose count drops to zero is removed from the map, so the map never holds a zero
+    //! and two bags with the same counts are equal as maps.
+
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Bag<T: Hash + Eq>(HashMap<T, usize>);
+
+    impl<T: Hash + Eq> Default for Bag<T> {
+        fn default() -> Self {
+            Bag(HashMap::new())
+        }
+    }
+
+    impl<T: Hash + Eq> Bag<T> {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Returns the count of `item` after the insertion.
+        pub fn insert(&mut self, item: T) -> usize {
+            let count: &mut usize = self.0.entry(item).or_insert(0);
+            *count += 1;
+            *count
+        }
+
+        /// Returns false, and changes nothing, when `item` is not in the bag.
+        pub fn remove_one(&mut self, item: &T) -> bool {
+            match self.0.get_mut(item) {
+                Some(1) => {
+                    self.0.remove(item);
+                    true
+                }
+                Some(count) => {
+                    *count -= 1;
+                    true
+                }
+                None => false,
+            }
+        }
+
+        pub fn count(&self, item: &T) -> usize {
+            self.0.get(item).copied().unwrap_or(0)
+        }
+
+        /// The number of items, each counted as many times as it was added.
+        pub fn len(&self) -> usize {
+            self.0.values().sum()
+        }
+
+        /// The number of different items.
+        pub fn distinct_len(&self) -> usize {
+            self.0.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.0.is_empty()
+        }
+
+        pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+            self.0.iter().map(|(item, &count)| (item, count))
+        }
+
+        /// True when no item is more often in `self` than in `other`.
+        pub fn is_subset(&self, other: &Bag<T>) -> bool {
+            self.iter().all(|(item, count)| count <= other.count(item))
+        }
+
+        /// The `n` items with the highest counts, highest first. Items with equal counts come in
+        /// ascending order, so the result does not depend on the iteration order of the map.
+        pub fn most_common(&self, n: usize) -> Vec<(&T, usize)>
+        where
+            T: Ord,
+        {
+            let mut items: Vec<(&T, usize)> = self.iter().collect();
+            items.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+            items.truncate(n);
+            items
+        }
+    }
+
+    impl<T: Hash + Eq + Clone> Bag<T> {
+        /// Every item of either bag, with `merge` of its two counts, 0 when a bag lacks it.
+        fn combine(&self, other: &Bag<T>, merge: fn(usize, usize) -> usize) -> Bag<T> {
+            let mut combined: HashMap<T, usize> = HashMap::new();
+            for item in self.0.keys().chain(other.0.keys()) {
+                let count: usize = merge(self.count(item), other.count(item));
+                if count > 0 {
+                    combined.insert(item.clone(), count);
+                }
+            }
+            Bag(combined)
+        }
+
+        pub fn union(&self, other: &Bag<T>) -> Bag<T> {
+            self.combine(other, usize::max)
+        }
+
+        pub fn intersection(&self, other: &Bag<T>) -> Bag<T> {
+            self.combine(other, usize::min)
+        }
+
+        pub fn sum(&self, other: &Bag<T>) -> Bag<T> {
+            self.combine(other, |a, b| a + b)
+        }
+    }
+
+    impl<T: Hash + Eq> FromIterator<T> for Bag<T> {
+        fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+            let mut bag: Bag<T> = Bag::new();
+            for item in iter {
+                bag.insert(item);
+            }
+            bag
+        }
+    }
+}
+
 #[cfg(test)]
 mod testing {
     #[test]
@@ -264,6 +766,16 @@ mod testing {
         crate::update_hash_map::entry_for_counting();
     }
 
+    #[test]
+    fn run_update_hash_map_entry_for_counting_deterministic() {
+        let output: String = crate::update_hash_map::entry_for_counting_deterministic();
+        assert_eq!(output, r#"{"world": 2, "hello": 1, "about": 1}"#);
+        assert_eq!(
+            output,
+            crate::update_hash_map::entry_for_counting_deterministic()
+        );
+    }
+
     #[test]
     fn run_entry_and_or_insert_theory() {
         crate::update_hash_map::entry_and_or_insert_theory();
@@ -298,4 +810,516 @@ mod testing {
     fn run_common_used_method_of_hash_map_get_mut() {
         crate::common_used_method_of_hash_map::get_mut();
     }
+
+    /// `a` 400 times, `b` 300 times and 100 distinct keys once each, interleaved.
+    fn skewed_stream() -> Vec<String> {
+        let mut stream: Vec<String> = vec![];
+        for i in 0..100 {
+            stream.extend(["a", "a", "a", "a", "b", "b", "b"].map(String::from));
+            stream.push(format!("k{}", i));
+        }
+        stream
+    }
+
+    /// Checks `estimate <= exact <= estimate + total / (k + 1)` for every key.
+    fn check_bounds(
+        top: &crate::heavy_hitters::TopK<String>,
+        exact: &std::collections::HashMap<String, u64>,
+        k: u64,
+    ) {
+        let total: u64 = exact.values().sum();
+        let estimates: std::collections::HashMap<String, u64> =
+            top.estimates().into_iter().collect();
+        for (key, &count) in exact {
+            let estimate: u64 = estimates.get(key).copied().unwrap_or(0);
+            assert!(estimate <= count);
+            assert!(count <= estimate + total / (k + 1));
+            // no false negatives: every key above the threshold keeps a counter
+            if count > total / (k + 1) {
+                assert!(estimates.contains_key(key));
+            }
+        }
+    }
+
+    #[test]
+    fn run_heavy_hitters_skewed_stream() {
+        use crate::heavy_hitters::{exact_counts, TopK};
+        let stream: Vec<String> = skewed_stream();
+        let exact = exact_counts(stream.iter().cloned());
+        let total: u64 = stream.len() as u64;
+
+        let mut top: TopK<String> = TopK::new(4);
+        for key in stream {
+            top.observe(key);
+        }
+        check_bounds(&top, &exact, 4);
+
+        let estimates: Vec<(String, u64)> = top.estimates();
+        assert_eq!(estimates[0].0, "a");
+        assert_eq!(estimates[1].0, "b");
+
+        let mut heavy: Vec<String> = top.guaranteed_heavy(total);
+        heavy.sort();
+        assert_eq!(heavy, vec![String::from("a"), String::from("b")]);
+        for key in heavy {
+            assert!(exact[&key] * 4 > total);
+        }
+    }
+
+    #[test]
+    fn run_heavy_hitters_uniform_stream() {
+        use crate::heavy_hitters::{exact_counts, TopK};
+        let stream: Vec<String> = (0..500).map(|i| format!("k{}", i % 50)).collect();
+        let exact = exact_counts(stream.iter().cloned());
+
+        let mut top: TopK<String> = TopK::new(5);
+        for key in stream {
+            top.observe(key);
+        }
+        check_bounds(&top, &exact, 5);
+        assert!(top.guaranteed_heavy(500).is_empty());
+    }
+
+    #[test]
+    fn run_heavy_hitters_single_counter() {
+        use crate::heavy_hitters::TopK;
+        // with one counter the summary is the majority vote: a key occurring in more than half of
+        // the stream always survives
+        let mut top: TopK<char> = TopK::new(1);
+        for key in "abacaaDaea".chars() {
+            top.observe(key);
+        }
+        assert_eq!(top.estimates().len(), 1);
+        assert_eq!(top.estimates()[0].0, 'a');
+        assert!(top.guaranteed_heavy(10).is_empty());
+    }
+
+    #[test]
+    fn run_heavy_hitters_merge() {
+        use crate::heavy_hitters::{exact_counts, TopK};
+        let stream: Vec<String> = skewed_stream();
+        let (left, right) = stream.split_at(stream.len() / 3);
+
+        let mut merged: TopK<String> = TopK::new(4);
+        left.iter().cloned().for_each(|key| merged.observe(key));
+        let mut other: TopK<String> = TopK::new(4);
+        right.iter().cloned().for_each(|key| other.observe(key));
+        merged.merge(&other);
+        assert!(merged.estimates().len() <= 4);
+
+        let mut whole: TopK<String> = TopK::new(4);
+        stream.iter().cloned().for_each(|key| whole.observe(key));
+
+        let exact = exact_counts(stream.iter().cloned());
+        check_bounds(&merged, &exact, 4);
+        check_bounds(&whole, &exact, 4);
+
+        let total: u64 = stream.len() as u64;
+        let mut merged_heavy: Vec<String> = merged.guaranteed_heavy(total);
+        let mut whole_heavy: Vec<String> = whole.guaranteed_heavy(total);
+        merged_heavy.sort();
+        whole_heavy.sort();
+        assert_eq!(merged_heavy, whole_heavy);
+    }
+
+    fn build_det_map(seed: u64) -> crate::deterministic::DetMap<String, usize> {
+        let mut map = crate::deterministic::det_map_with_seed(seed);
+        let words: [&str; 8] = [
+            "rust", "go", "c++", "java", "zig", "ocaml", "haskell", "lua",
+        ];
+        for (i, word) in words.iter().enumerate() {
+            map.insert(word.to_string(), i);
+        }
+        map.remove("java");
+        map
+    }
+
+    #[test]
+    fn run_deterministic_same_seed() {
+        use crate::deterministic::iteration_fingerprint;
+        let a = build_det_map(7);
+        let b = build_det_map(7);
+        assert_eq!(iteration_fingerprint(&a), iteration_fingerprint(&b));
+        assert_eq!(format!("{:?}", a), format!("{:?}", b));
+    }
+
+    #[test]
+    fn run_deterministic_different_seed() {
+        use crate::deterministic::iteration_fingerprint;
+        let a = build_det_map(7);
+        let b = build_det_map(8);
+        // same content, the order (and so the fingerprint) is almost certainly different
+        assert_eq!(a, b);
+        assert_ne!(iteration_fingerprint(&a), iteration_fingerprint(&b));
+    }
+
+    #[test]
+    fn run_deterministic_entry_api() {
+        use crate::deterministic::{det_map, DetMap};
+        use std::collections::hash_map::Entry;
+        let mut map: DetMap<char, u32> = det_map();
+        for ch in "rust best".chars() {
+            map.entry(ch)
+                .and_modify(|counter| *counter += 1)
+                .or_insert(1);
+        }
+        assert_eq!(map[&'t'], 2);
+        assert_eq!(map[&'s'], 2);
+
+        match map.entry('x') {
+            Entry::Occupied(_) => unreachable!(),
+            Entry::Vacant(entry) => {
+                entry.insert(9);
+            }
+        }
+        assert_eq!(map.get(&'x'), Some(&9));
+        assert_eq!(map.len(), 8);
+    }
+
+    fn check_join_stats<A: Clone, B: Clone>(
+        left: &std::collections::HashMap<&str, A>,
+        right: &std::collections::HashMap<&str, B>,
+    ) -> crate::joins::JoinStats {
+        let stats = crate::joins::join_report(left, right);
+        assert_eq!(stats.matched + stats.left_only, left.len());
+        assert_eq!(stats.matched + stats.right_only, right.len());
+        assert_eq!(stats.matched, crate::joins::inner_join(left, right).len());
+        assert_eq!(
+            stats.matched + stats.left_only + stats.right_only,
+            crate::joins::full_join(left, right).len()
+        );
+        stats
+    }
+
+    #[test]
+    fn run_joins_overlapping_keys() {
+        use crate::joins::{full_join, inner_join, left_join, JoinStats};
+        use std::collections::HashMap;
+        let ages: HashMap<&str, u32> = HashMap::from([("ann", 31), ("bob", 27), ("cid", 45)]);
+        let cities: HashMap<&str, &str> = HashMap::from([("bob", "Oslo"), ("dan", "Rome")]);
+
+        assert_eq!(
+            inner_join(&ages, &cities),
+            HashMap::from([("bob", (27, "Oslo"))])
+        );
+        assert_eq!(
+            left_join(&ages, &cities),
+            HashMap::from([
+                ("ann", (31, None)),
+                ("bob", (27, Some("Oslo"))),
+                ("cid", (45, None))
+            ])
+        );
+        assert_eq!(
+            full_join(&ages, &cities),
+            HashMap::from([
+                ("ann", (Some(31), None)),
+                ("bob", (Some(27), Some("Oslo"))),
+                ("cid", (Some(45), None)),
+                ("dan", (None, Some("Rome"))),
+            ])
+        );
+        let stats: JoinStats = check_join_stats(&ages, &cities);
+        assert_eq!(
+            stats,
+            JoinStats {
+                matched: 1,
+                left_only: 2,
+                right_only: 1
+            }
+        );
+    }
+
+    #[test]
+    fn run_joins_disjoint_keys() {
+        use crate::joins::{full_join, inner_join, left_join, JoinStats};
+        use std::collections::HashMap;
+        let left: HashMap<&str, u8> = HashMap::from([("a", 1), ("b", 2)]);
+        let right: HashMap<&str, char> = HashMap::from([("c", 'c')]);
+
+        assert!(inner_join(&left, &right).is_empty());
+        assert!(left_join(&left, &right).values().all(|(_, b)| b.is_none()));
+        let full = full_join(&left, &right);
+        assert_eq!(full.len(), 3);
+        assert!(full
+            .values()
+            .all(|pair| pair.0.is_some() != pair.1.is_some()));
+        let stats: JoinStats = check_join_stats(&left, &right);
+        assert_eq!(
+            stats,
+            JoinStats {
+                matched: 0,
+                left_only: 2,
+                right_only: 1
+            }
+        );
+
+        let empty: HashMap<&str, u8> = HashMap::new();
+        assert!(left_join(&empty, &right).is_empty());
+        assert_eq!(full_join(&empty, &right).len(), 1);
+        check_join_stats(&empty, &empty);
+    }
+
+    #[test]
+    fn run_joins_identical_keys() {
+        use crate::joins::{full_join, inner_join, left_join, JoinStats};
+        use std::collections::HashMap;
+        let left: HashMap<&str, u8> = HashMap::from([("a", 1), ("b", 2)]);
+        let right: HashMap<&str, u8> = HashMap::from([("a", 10), ("b", 20)]);
+
+        assert_eq!(
+            inner_join(&left, &right),
+            HashMap::from([("a", (1, 10)), ("b", (2, 20))])
+        );
+        assert!(left_join(&left, &right).values().all(|(_, b)| b.is_some()));
+        assert!(full_join(&left, &right)
+            .values()
+            .all(|(a, b)| a.is_some() && b.is_some()));
+        let stats: JoinStats = check_join_stats(&left, &right);
+        assert_eq!(
+            stats,
+            JoinStats {
+                matched: 2,
+                left_only: 0,
+                right_only: 0
+            }
+        );
+    }
+
+    #[test]
+    fn run_joins_multi() {
+        use crate::joins::join_multi;
+        use std::collections::HashMap;
+        let orders: HashMap<&str, Vec<u32>> =
+            HashMap::from([("ann", vec![1, 2, 3]), ("bob", vec![4]), ("cid", vec![])]);
+        let payments: HashMap<&str, Vec<char>> =
+            HashMap::from([("ann", vec!['x', 'y']), ("bob", vec![]), ("cid", vec!['z'])]);
+
+        let joined: HashMap<&str, Vec<(u32, char)>> = join_multi(&orders, &payments);
+        assert_eq!(joined.len(), 3);
+        assert_eq!(joined["ann"].len(), 3 * 2);
+        assert_eq!(joined["ann"][..3], [(1, 'x'), (1, 'y'), (2, 'x')]);
+        assert!(joined["bob"].is_empty());
+        assert!(joined["cid"].is_empty());
+
+        check_join_stats(&orders, &payments);
+    }
+
+    fn check_round_trip<K, V>(
+        old: &std::collections::HashMap<K, V>,
+        new: &std::collections::HashMap<K, V>,
+    ) -> crate::map_diff::MapDiff<K, V>
+    where
+        K: std::hash::Hash + Eq + Clone + Ord + std::fmt::Debug,
+        V: PartialEq + Clone + std::fmt::Debug,
+    {
+        use crate::map_diff::{apply_diff, diff_maps};
+        let diff = diff_maps(old, new);
+        let mut patched = old.clone();
+        apply_diff(&mut patched, &diff);
+        assert_eq!(&patched, new);
+        assert_eq!(diff.is_empty(), old == new);
+        // the reverse diff undoes it
+        apply_diff(&mut patched, &diff_maps(new, old));
+        assert_eq!(&patched, old);
+        diff
+    }
+
+    #[test]
+    fn run_map_diff() {
+        use std::collections::HashMap;
+        let old: HashMap<&str, i32> = HashMap::from([("a", 1), ("b", 2), ("c", 3)]);
+        let new: HashMap<&str, i32> = HashMap::from([("a", 1), ("c", 30), ("d", 4)]);
+        let diff = check_round_trip(&old, &new);
+        assert_eq!(diff.added, vec![("d", 4)]);
+        assert_eq!(diff.removed, vec![("b", 2)]);
+        assert_eq!(diff.changed, vec![("c", 3, 30)]);
+
+        // identical maps
+        assert!(check_round_trip(&old, &old.clone()).is_empty());
+
+        // disjoint maps, sorted by key whatever the iteration order
+        let other: HashMap<&str, i32> = HashMap::from([("z", 26), ("x", 24), ("y", 25)]);
+        let diff = check_round_trip(&old, &other);
+        assert_eq!(diff.added, vec![("x", 24), ("y", 25), ("z", 26)]);
+        assert_eq!(diff.removed, vec![("a", 1), ("b", 2), ("c", 3)]);
+        assert!(diff.changed.is_empty());
+
+        // empty against non-empty, both ways
+        let empty: HashMap<&str, i32> = HashMap::new();
+        let diff = check_round_trip(&empty, &old);
+        assert_eq!(diff.added.len(), 3);
+        assert!(diff.removed.is_empty() && diff.changed.is_empty());
+        assert_eq!(check_round_trip(&old, &empty).removed.len(), 3);
+        assert!(check_round_trip(&empty, &empty).is_empty());
+    }
+
+    #[test]
+    fn run_map_diff_fixtures() {
+        use std::collections::HashMap;
+        // maps over the keys 0..8, where a fixture decides whether a key is absent or its value
+        let fixture = |seed: u32| -> HashMap<u32, String> {
+            (0..8)
+                .filter_map(|key| match (key * 7 + seed) % 5 {
+                    0 | 1 => None,
+                    v => Some((key, format!("v{}", v + seed % 2))),
+                })
+                .collect()
+        };
+        let fixtures: Vec<HashMap<u32, String>> = (0..6).map(fixture).collect();
+        for old in &fixtures {
+            for new in &fixtures {
+                check_round_trip(old, new);
+            }
+        }
+    }
+
+    #[test]
+    fn run_map_diff_by_key() {
+        use crate::map_diff::{apply_diff, diff_maps_by_key};
+        use std::collections::HashMap;
+
+        // neither the key nor the value is `Ord`
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        struct Sensor {
+            id: u32,
+        }
+        let old: HashMap<Sensor, f64> =
+            HashMap::from([(Sensor { id: 3 }, 1.5), (Sensor { id: 1 }, 0.5)]);
+        let new: HashMap<Sensor, f64> = HashMap::from([
+            (Sensor { id: 3 }, 2.5),
+            (Sensor { id: 2 }, 9.0),
+            (Sensor { id: 0 }, 1.0),
+        ]);
+        let diff = diff_maps_by_key(&old, &new, |sensor| sensor.id);
+        assert_eq!(
+            diff.added,
+            vec![(Sensor { id: 0 }, 1.0), (Sensor { id: 2 }, 9.0)]
+        );
+        assert_eq!(diff.removed, vec![(Sensor { id: 1 }, 0.5)]);
+        assert_eq!(diff.changed, vec![(Sensor { id: 3 }, 1.5, 2.5)]);
+
+        let mut patched: HashMap<Sensor, f64> = old.clone();
+        apply_diff(&mut patched, &diff);
+        assert_eq!(patched, new);
+
+        // sorted by the extracted key, here descending
+        let diff = diff_maps_by_key(&old, &new, |sensor| std::cmp::Reverse(sensor.id));
+        assert_eq!(
+            diff.added,
+            vec![(Sensor { id: 2 }, 9.0), (Sensor { id: 0 }, 1.0)]
+        );
+    }
+
+    #[test]
+    fn run_accumulate_lists() {
+        use crate::update_hash_map::accumulate_lists;
+        use std::collections::HashMap;
+        let expected: HashMap<String, Vec<i32>> = HashMap::from([
+            (String::from("a"), vec![1, 2]),
+            (String::from("b"), vec![3]),
+        ]);
+        assert_eq!(accumulate_lists(&[("a", 1), ("a", 2), ("b", 3)]), expected);
+        assert!(accumulate_lists(&[]).is_empty());
+        // the values keep the order of the pairs
+        assert_eq!(
+            accumulate_lists(&[("a", 2), ("b", 0), ("a", 1)])["a"],
+            vec![2, 1]
+        );
+    }
+
+    #[test]
+    fn run_multiset_insert_and_remove() {
+        use crate::multiset::Bag;
+        let mut bag: Bag<&str> = Bag::new();
+        assert_eq!(bag.insert("x"), 1);
+        assert_eq!(bag.insert("x"), 2);
+        assert_eq!(bag.insert("y"), 1);
+        assert_eq!(bag.len(), 3);
+        assert_eq!(bag.distinct_len(), 2);
+
+        assert!(bag.remove_one(&"x"));
+        assert_eq!(bag.count(&"x"), 1);
+        assert!(bag.remove_one(&"x"));
+        assert_eq!(bag.count(&"x"), 0);
+        // the count does not go below zero
+        assert!(!bag.remove_one(&"x"));
+        assert!(!bag.remove_one(&"z"));
+        assert_eq!(bag.len(), 1);
+        assert_eq!(bag.distinct_len(), 1);
+
+        // no zero is left behind, so the bag equals one that never had an "x"
+        assert_eq!(bag, Bag::from_iter(["y"]));
+        assert!(bag.remove_one(&"y"));
+        assert!(bag.is_empty());
+        assert_eq!(bag.len(), 0);
+    }
+
+    #[test]
+    fn run_multiset_algebra() {
+        use crate::multiset::Bag;
+        let a: Bag<char> = "xxy".chars().collect();
+        let b: Bag<char> = "xyyyz".chars().collect();
+        let empty: Bag<char> = Bag::new();
+
+        assert_eq!(a.union(&b), "xxyyyz".chars().collect());
+        assert_eq!(a.intersection(&b), "xy".chars().collect());
+        assert_eq!(a.sum(&b), "xxxyyyyz".chars().collect());
+
+        for (p, q) in [(&a, &b), (&b, &a), (&a, &empty), (&a, &a)] {
+            assert_eq!(p.union(q), q.union(p));
+            assert_eq!(p.intersection(q), q.intersection(p));
+            assert_eq!(p.sum(q), q.sum(p));
+            // max + min of two counts is their sum
+            assert_eq!(p.union(q).sum(&p.intersection(q)), p.sum(q));
+            assert_eq!(p.sum(q).len(), p.len() + q.len());
+            assert!(p.intersection(q).is_subset(p));
+            assert!(p.is_subset(&p.union(q)));
+        }
+        assert_eq!(a.union(&a), a);
+        assert_eq!(a.intersection(&a), a);
+        assert_eq!(a.union(&empty), a);
+        assert_eq!(a.sum(&empty), a);
+        assert_eq!(a.intersection(&empty), empty);
+    }
+
+    #[test]
+    fn run_multiset_subset() {
+        use crate::multiset::Bag;
+        let a: Bag<char> = "xxy".chars().collect();
+        let empty: Bag<char> = Bag::new();
+        assert!(empty.is_subset(&empty));
+        assert!(empty.is_subset(&a));
+        assert!(!a.is_subset(&empty));
+        assert!(a.is_subset(&a.clone()));
+        // the counts matter, not just the items
+        assert!(!a.is_subset(&"xy".chars().collect()));
+        assert!(a.is_subset(&"xxxyz".chars().collect()));
+    }
+
+    #[test]
+    fn run_multiset_counting_words() {
+        use crate::multiset::Bag;
+        let text: &str = "hello world about world";
+        let bag: Bag<&str> = text.split_whitespace().collect();
+        assert_eq!(bag.count(&"world"), 2);
+        assert_eq!(bag.count(&"hello"), 1);
+        assert_eq!(bag.count(&"rust"), 0);
+        assert_eq!(bag.len(), 4);
+        assert_eq!(bag.distinct_len(), 3);
+
+        // the same counts as `entry_for_counting_deterministic`
+        let mut counts: Vec<(&str, usize)> = bag.iter().map(|(w, c)| (*w, c)).collect();
+        counts.sort();
+        assert_eq!(counts, vec![("about", 1), ("hello", 1), ("world", 2)]);
+
+        let (world, about, hello): (&str, &str, &str) = ("world", "about", "hello");
+        assert_eq!(bag.most_common(1), vec![(&world, 2)]);
+        // ties in ascending order
+        assert_eq!(
+            bag.most_common(5),
+            vec![(&world, 2), (&about, 1), (&hello, 1)]
+        );
+        assert!(bag.most_common(0).is_empty());
+    }
 }
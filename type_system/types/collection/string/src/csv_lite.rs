@@ -0,0 +1,319 @@
+//! # CSV lite
+//!
+//! Comma separated values as in RFC 4180: records end with `\n` or `\r\n`, fields are separated
+//! by `,`, and a field in double quotes may contain commas, line breaks and quotes, written
+//! twice:
+//!
+//! ```text
+//! name,quote                        name   | quote
+//! ann,"she said ""hi"", twice"  ->  ann    | she said "hi", twice
+//! bob,"two                          bob    | two\nlines
+//! lines"
+//! ```
+//!
+//! The first record is the header row and every other record must have as many fields. A line
+//! break after the last record is optional. Because a quoted field can span lines, a record is
+//! not a line: errors about records give the row, 1 being the first record after the header,
+//! and an unterminated quote gives the line where it was opened.
+//!
+//! `write` quotes a field only when it has to, when it contains a comma, a quote or a line
+//! break, so `write(&parse(x)?)` may differ from `x` in quoting and line endings but parses back
+//! to the same `Csv`.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq)]
+pub enum CsvError {
+    UnterminatedQuote {
+        line: usize,
+    },
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        got: usize,
+    },
+    UnknownColumn(String),
+    /// A value `typed_column` could not parse.
+    InvalidValue {
+        row: usize,
+        column: String,
+        value: String,
+    },
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CsvError::UnterminatedQuote { line } => {
+                write!(f, "quote opened on line {} is never closed", line)
+            }
+            CsvError::RaggedRow { row, expected, got } => {
+                write!(f, "row {} has {} fields, expected {}", row, got, expected)
+            }
+            CsvError::UnknownColumn(name) => write!(f, "no column named `{}`", name),
+            CsvError::InvalidValue { row, column, value } => {
+                write!(f, "row {}: `{}` is not a valid {}", row, value, column)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+#[derive(Debug, PartialEq)]
+pub struct Csv {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Csv {
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    pub fn rows(&self) -> &[Vec<String>] {
+        &self.rows
+    }
+
+    fn index_of(&self, name: &str) -> Result<usize, CsvError> {
+        self.headers
+            .iter()
+            .position(|header| header == name)
+            .ok_or_else(|| CsvError::UnknownColumn(name.to_string()))
+    }
+
+    pub fn column(&self, name: &str) -> Result<Vec<&str>, CsvError> {
+        let index: usize = self.index_of(name)?;
+        Ok(self.rows.iter().map(|row| row[index].as_str()).collect())
+    }
+
+    /// Parses every value of the column, the first failure is reported with its row.
+    pub fn typed_column<T: FromStr>(&self, name: &str) -> Result<Vec<T>, CsvError> {
+        let index: usize = self.index_of(name)?;
+        self.rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                row[index].parse().map_err(|_| CsvError::InvalidValue {
+                    row: i + 1,
+                    column: name.to_string(),
+                    value: row[index].clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Splits the input into records of fields, one char at a time.
+fn records(input: &str) -> Result<Vec<Vec<String>>, CsvError> {
+    let mut records: Vec<Vec<String>> = vec![];
+    let mut record: Vec<String> = vec![];
+    let mut field: String = String::new();
+    // whether anything of the current record has been read, so a final line break does not
+    // start an empty record
+    let mut started: bool = false;
+    let mut line: usize = 1;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        started = true;
+        match c {
+            '"' if field.is_empty() => {
+                let opened_on: usize = line;
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            field.push(c);
+                        }
+                        None => return Err(CsvError::UnterminatedQuote { line: opened_on }),
+                    }
+                }
+            }
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+                started = false;
+                line += 1;
+            }
+            c => field.push(c),
+        }
+    }
+    if started {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+pub fn parse(input: &str) -> Result<Csv, CsvError> {
+    let mut records = records(input)?.into_iter();
+    let headers: Vec<String> = records.next().unwrap_or_default();
+    let rows: Vec<Vec<String>> = records.collect();
+    for (i, row) in rows.iter().enumerate() {
+        if row.len() != headers.len() {
+            return Err(CsvError::RaggedRow {
+                row: i + 1,
+                expected: headers.len(),
+                got: row.len(),
+            });
+        }
+    }
+    Ok(Csv { headers, rows })
+}
+
+fn write_field(out: &mut String, field: &str) {
+    if field.contains([',', '"', '\n', '\r']) {
+        out.push('"');
+        out.push_str(&field.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(field);
+    }
+}
+
+/// One record per line, each ended by `\n`.
+pub fn write(csv: &Csv) -> String {
+    let mut out: String = String::new();
+    if csv.headers.is_empty() && csv.rows.is_empty() {
+        return out;
+    }
+    for record in std::iter::once(&csv.headers).chain(&csv.rows) {
+        for (i, field) in record.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write_field(&mut out, field);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn run_csv_quoting() {
+        let input: &str = "name,quote\n\
+                           ann,\"she said \"\"hi\"\", twice\"\n\
+                           bob,\"two\nlines\"\n\
+                           \"\",\"\"\"\"\n\
+                           cat,a\"b\n";
+        let csv: Csv = parse(input).unwrap();
+        assert_eq!(csv.headers(), ["name", "quote"]);
+        assert_eq!(csv.column("name").unwrap(), vec!["ann", "bob", "", "cat"]);
+        assert_eq!(
+            csv.column("quote").unwrap(),
+            vec!["she said \"hi\", twice", "two\nlines", "\"", "a\"b"]
+        );
+
+        // an empty last field, and no line break at the end
+        let csv: Csv = parse("a,b\n1,").unwrap();
+        assert_eq!(csv.rows(), [vec!["1", ""]]);
+
+        assert_eq!(
+            parse("a,b\n1,\"open\n\n"),
+            Err(CsvError::UnterminatedQuote { line: 2 })
+        );
+        assert_eq!(
+            parse("\"a\nb\nc"),
+            Err(CsvError::UnterminatedQuote { line: 1 })
+        );
+    }
+
+    #[test]
+    fn run_csv_ragged_row() {
+        assert_eq!(
+            parse("a,b,c\n1,2,3\n4,5\n"),
+            Err(CsvError::RaggedRow {
+                row: 2,
+                expected: 3,
+                got: 2
+            })
+        );
+        // the quoted comma is part of the field, the row is not too long
+        assert!(parse("a,b\n1,\"2,3\"\n").is_ok());
+        assert_eq!(
+            parse("a,b\n1,2,3\n").unwrap_err().to_string(),
+            "row 1 has 3 fields, expected 2"
+        );
+    }
+
+    #[test]
+    fn run_csv_crlf() {
+        let crlf: Csv = parse("name,age\r\nann,31\r\n\"b\r\nob\",27\r\n").unwrap();
+        assert_eq!(crlf.column("age").unwrap(), vec!["31", "27"]);
+        // inside quotes a line break is kept as written
+        assert_eq!(crlf.column("name").unwrap(), vec!["ann", "b\r\nob"]);
+        assert_eq!(
+            parse("name,age\r\nann,31").unwrap(),
+            parse("name,age\nann,31\n").unwrap()
+        );
+    }
+
+    #[test]
+    fn run_csv_round_trip() {
+        let inputs: [&str; 5] = [
+            "name,quote\nann,\"she said \"\"hi\"\", twice\"\nbob,\"two\r\nlines\"\n",
+            "a,b\r\n\"1\",\" 2 \"\r\n",
+            "single\n\n\"\"\nx\n",
+            "",
+            "only,headers",
+        ];
+        for input in inputs {
+            let csv: Csv = parse(input).unwrap();
+            let written: String = write(&csv);
+            assert_eq!(
+                parse(&written).unwrap(),
+                csv,
+                "{:?} became {:?}",
+                input,
+                written
+            );
+        }
+        let csv: Csv = parse("a,b\n\"1\",\"x,y\"\n").unwrap();
+        assert_eq!(write(&csv), "a,b\n1,\"x,y\"\n");
+        assert_eq!(parse("").unwrap().headers(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn run_csv_typed_column() {
+        let csv: Csv = parse("name,age,score\nann,31,1.5\nbob,x,2\ncid,45,-3e2\n").unwrap();
+        assert_eq!(
+            csv.typed_column::<f64>("score").unwrap(),
+            vec![1.5, 2.0, -300.0]
+        );
+        assert_eq!(
+            csv.typed_column::<String>("name").unwrap(),
+            vec!["ann", "bob", "cid"]
+        );
+        assert_eq!(
+            csv.typed_column::<u8>("age"),
+            Err(CsvError::InvalidValue {
+                row: 2,
+                column: String::from("age"),
+                value: String::from("x"),
+            })
+        );
+        assert_eq!(
+            csv.typed_column::<u8>("height"),
+            Err(CsvError::UnknownColumn(String::from("height")))
+        );
+        assert_eq!(
+            csv.column("height"),
+            Err(CsvError::UnknownColumn(String::from("height")))
+        );
+    }
+}
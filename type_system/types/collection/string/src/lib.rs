@@ -10,41 +10,95 @@
 //! The pointer points to an internal buffer `String` uses to store its data. The length is the
 //! number of bytes currently stored in the buffer, the capacity is the size of the buffer in bytes.
 
-/// A `String` 0~8 bytes store the underline data pointer, 8~16 bytes store capacity, 16~24 bytes
-/// store length.
+pub mod csv_lite;
+pub mod number_format;
+pub mod rope;
+pub mod table;
+pub mod text_diff;
+pub mod tokenizer;
+
+/// The three fields of a `String`, and where the `String` itself is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StringLayout {
+    pub addr: usize,
+    pub data_ptr: usize,
+    pub len: usize,
+    pub capacity: usize,
+}
+
+/// The layout as the public API reports it.
+#[allow(clippy::ptr_arg)]
+pub fn inspect_string(s: &String) -> StringLayout {
+    StringLayout {
+        addr: s as *const String as usize,
+        data_ptr: s.as_ptr() as usize,
+        len: s.len(),
+        capacity: s.capacity(),
+    }
+}
+
+/// The layout read from the raw bytes of the `String`, three `usize` words. Rust does not
+/// guarantee in which order the fields are stored, the compiler may reorder them, so the words
+/// are matched against the values of `inspect_string` to find which is which: the data pointer
+/// is the word equal to `as_ptr()`, then the length is the remaining word equal to `len()` and
+/// the capacity is the last one. When the length equals the capacity, either word will do.
+///
+/// ```text
+/// ------------ addr
+/// word 0           one of data_ptr, capacity, len
+/// ------------ addr + 8
+/// word 1
+/// ------------ addr + 16
+/// word 2
+/// ------------ addr + 24
+/// ```
+///
+/// # Panics
+///
+/// If no word matches, which would mean a `String` is not three words on this target.
+#[allow(clippy::ptr_arg)]
+pub fn unsafe_inspect_string(s: &String) -> StringLayout {
+    let safe: StringLayout = inspect_string(s);
+    let words: &[usize] = unsafe {
+        std::slice::from_raw_parts(
+            s as *const String as *const usize,
+            std::mem::size_of::<String>() / std::mem::size_of::<usize>(),
+        )
+    };
+    let mut fields: Vec<usize> = (0..words.len()).collect();
+    let mut take = |value: usize| -> usize {
+        let position: usize = fields
+            .iter()
+            .position(|&i| words[i] == value)
+            .expect("no word of the String holds the value");
+        words[fields.remove(position)]
+    };
+    let data_ptr: usize = take(safe.data_ptr);
+    let len: usize = take(safe.len);
+    let capacity: usize = take(safe.capacity);
+    StringLayout {
+        addr: words.as_ptr() as usize,
+        data_ptr,
+        len,
+        capacity,
+    }
+}
+
+/// A `String` is a pointer to its heap buffer, a capacity and a length, 24 bytes on a 64 bit
+/// target. The buffer is elsewhere, so the address of the `String` and its data pointer differ.
 /// ```text
-/// -------------- 0x3053bd718
-/// 0x7f9f34804080
-/// -------------- 0x3053bd720
-///      500
-/// -------------- 0x3053bd728
-///       4
-/// -------------- 0x3053bd730
+/// string address: 0x3053bd718
+/// underline data address: 0x7f9f34804080
+/// len: 4, capacity: 500
 /// ```
-pub fn string_memory_layout() {
+pub fn string_memory_layout() -> StringLayout {
     let mut s: String = String::with_capacity(500);
     s.push_str("rust");
-    let p: *const String = &s;
-    println!("string address: {:p}", p); // string address: 0x3053bd718
-    println!("underline data address: {:p}", s.as_ptr()); // underline data address: 0x7f9f34804080
-    unsafe {
-        println!(
-            "0~8 bytes store underline pointer: {:#0x?}",
-            *(p as *const u64)
-        ); // 0~8 bytes store underline pointer: 0x7f9f34804080
-
-        let capacity_address: *const u64 = (p as usize + 8) as *const u64;
-        println!(
-            "8~16 bytes store capacity: {:p}:{}",
-            capacity_address, *capacity_address
-        ); // 8~16 bytes store capacity: 0x3053bd720:500
-
-        let length_address: *const u64 = (p as usize + 16) as *const u64;
-        println!(
-            "16~24 bytes store length: {:p}:{}",
-            length_address, *length_address
-        ); // 16~24 bytes store length: 0x3053bd728:4
-    }
+    let layout: StringLayout = unsafe_inspect_string(&s);
+    println!("string address: {:#x}", layout.addr); // string address: 0x3053bd718
+    println!("underline data address: {:#x}", layout.data_ptr); // underline data address: 0x7f9f34804080
+    println!("len: {}, capacity: {}", layout.len, layout.capacity); // len: 4, capacity: 500
+    layout
 }
 
 pub mod create_string {
@@ -188,7 +242,7 @@ pub mod index_string {
     //! Rust strings don’t support indexing.
     //!
     //! If you try to access parts of a String using indexing syntax in Rust, you’ll get an error.
-    //! ```panic
+    //! ```compile_fail,E0277
     //! let s1 = String::from("hello");
     //! let h = s1[0];
     //! ```
@@ -321,6 +375,114 @@ pub mod common_used_method_of_string {
     }
 }
 
+pub mod split_string {
+    //! The split methods return lazy iterators of `&str` slices borrowing from the original
+    //! string, nothing is copied until the pieces are collected or turned into `String`s. The
+    //! separator is a pattern: a `char`, a `&str`, a slice of chars or a closure.
+
+    /// Every occurrence of the separator ends a piece, so a separator at either end, or two in a
+    /// row, gives an empty slice.
+    pub fn with_split() {
+        let v: Vec<&str> = "a,b,c".split(',').collect();
+        assert_eq!(v, ["a", "b", "c"]);
+
+        let v: Vec<&str> = ",a,,b,".split(',').collect();
+        assert_eq!(v, ["", "a", "", "b", ""]);
+
+        // no separator, one piece; an empty string is still one (empty) piece
+        let v: Vec<&str> = "abc".split(',').collect();
+        assert_eq!(v, ["abc"]);
+        let v: Vec<&str> = "".split(',').collect();
+        assert_eq!(v, [""]);
+
+        // a multi-byte separator, split on its whole UTF-8 encoding
+        let v: Vec<&str> = "东京、大阪、京都".split('、').collect();
+        assert_eq!(v, ["东京", "大阪", "京都"]);
+
+        // a `&str` separator, and any of several chars
+        let v: Vec<&str> = "a::b::c".split("::").collect();
+        assert_eq!(v, ["a", "b", "c"]);
+        let v: Vec<&str> = "a-b_c".split(['-', '_']).collect();
+        assert_eq!(v, ["a", "b", "c"]);
+    }
+
+    /// At most `n` pieces, the last one is the rest of the string, separators included.
+    pub fn with_splitn() {
+        let v: Vec<&str> = "key=value=more".splitn(2, '=').collect();
+        assert_eq!(v, ["key", "value=more"]);
+
+        let v: Vec<&str> = "a,b,c".splitn(5, ',').collect();
+        assert_eq!(v, ["a", "b", "c"]);
+
+        // empty pieces count towards `n`
+        let v: Vec<&str> = ",,a,b".splitn(3, ',').collect();
+        assert_eq!(v, ["", "", "a,b"]);
+
+        // `split_once` is the `splitn(2, ..)` that also says whether the separator was found
+        assert_eq!(
+            "key=value=more".split_once('='),
+            Some(("key", "value=more"))
+        );
+        assert_eq!("key".split_once('='), None);
+    }
+
+    /// The same pieces as `split`, from the end.
+    pub fn with_rsplit() {
+        let v: Vec<&str> = "a/b/c".rsplit('/').collect();
+        assert_eq!(v, ["c", "b", "a"]);
+
+        let v: Vec<&str> = "/a/b/".rsplit('/').collect();
+        assert_eq!(v, ["", "b", "a", ""]);
+
+        // the file name and the rest of a path
+        let v: Vec<&str> = "src/collection/lib.rs".rsplitn(2, '/').collect();
+        assert_eq!(v, ["lib.rs", "src/collection"]);
+    }
+
+    /// Splits on runs of Unicode whitespace, and never yields an empty slice.
+    pub fn with_split_whitespace() {
+        let v: Vec<&str> = "  hello \t world\n ".split_whitespace().collect();
+        assert_eq!(v, ["hello", "world"]);
+
+        // U+3000 IDEOGRAPHIC SPACE is whitespace too
+        let v: Vec<&str> = "你好\u{3000}世界".split_whitespace().collect();
+        assert_eq!(v, ["你好", "世界"]);
+
+        let v: Vec<&str> = " \t\n ".split_whitespace().collect();
+        assert!(v.is_empty());
+    }
+
+    /// Splits on `\n` and removes a `\r` before it. A final line ending does not start an
+    /// empty last line, but an empty line in the middle is kept.
+    pub fn with_lines() {
+        let v: Vec<&str> = "one\ntwo\r\n\nfour\n".lines().collect();
+        assert_eq!(v, ["one", "two", "", "four"]);
+
+        // `split('\n')` keeps the `\r` and yields a last empty piece
+        let v: Vec<&str> = "one\r\ntwo\n".split('\n').collect();
+        assert_eq!(v, ["one\r", "two", ""]);
+
+        let v: Vec<&str> = "".lines().collect();
+        assert!(v.is_empty());
+    }
+
+    /// `split(' ')` splits on each single space and nothing else: repeated spaces give empty
+    /// slices and a tab stays inside a piece. `split_whitespace` is what "words" usually means.
+    pub fn split_vs_split_whitespace() {
+        let text: &str = "a  b\tc ";
+
+        let v: Vec<&str> = text.split(' ').collect();
+        assert_eq!(v, ["a", "", "b\tc", ""]);
+
+        let v: Vec<&str> = text.split_whitespace().collect();
+        assert_eq!(v, ["a", "b", "c"]);
+
+        // filtering out the empty slices is not the same either, the tab is still there
+        let v: Vec<&str> = text.split(' ').filter(|s| !s.is_empty()).collect();
+        assert_eq!(v, ["a", "b\tc"]);
+    }
+}
+
 pub mod advance {
     pub fn string_variable() -> *const u8 {
         let s: String = String::from("A_BCD");
@@ -364,6 +526,125 @@ pub mod advance {
     }
 }
 
+pub mod format_spec {
+    //! Each `{}` placeholder of `format!` can carry a format spec after a colon:
+    //!
+    //! ```text
+    //! {:[[fill]align][sign]['#']['0'][width]['.' precision][type]}
+    //!
+    //! fill        any character used for padding, defaults to a space
+    //! align       `<` left, `^` center, `>` right
+    //! sign        `+` always prints the sign of numbers
+    //! #           alternate form, e.g. `0x` prefix for hex
+    //! 0           pads numbers with zeros after the sign
+    //! width       minimum width of the output
+    //! precision   digits after the decimal point, or max length of a string
+    //! type        `?` Debug, `x` LowerHex, `b` Binary, `e` LowerExp, ...
+    //! ```
+
+    /// Pads the value to the given width, numbers are right-aligned and strings are left-aligned
+    /// by default.
+    pub fn width_and_alignment() {
+        assert_eq!(format!("{:>5}", 42), "   42");
+        assert_eq!(format!("{:<5}", 42), "42   ");
+        assert_eq!(format!("{:^5}", 42), " 42  ");
+        assert_eq!(format!("{:*^7}", "rust"), "*rust**");
+        assert_eq!(format!("{:5}", "rust"), "rust ");
+    }
+
+    pub fn zero_padding() {
+        assert_eq!(format!("{:05}", 42), "00042");
+        assert_eq!(format!("{:05}", -42), "-0042");
+    }
+
+    /// For floats, precision is the number of digits after the decimal point (rounded). For
+    /// strings, it truncates.
+    #[allow(clippy::approx_constant)]
+    pub fn precision() {
+        assert_eq!(format!("{:.2}", 3.14159), "3.14");
+        assert_eq!(format!("{:8.3}", 2.71828), "   2.718");
+        assert_eq!(format!("{:.3}", "rustacean"), "rus");
+    }
+
+    pub fn sign() {
+        assert_eq!(format!("{:+}", 5), "+5");
+        assert_eq!(format!("{:+}", -5), "-5");
+    }
+
+    pub fn alternate_form() {
+        assert_eq!(format!("{:#x}", 255), "0xff");
+        assert_eq!(format!("{:x}", 255), "ff");
+        assert_eq!(format!("{:#010x}", 255), "0x000000ff");
+    }
+
+    /// Arguments can be referred to by position, `{0}`, so the same argument may be used several
+    /// times, or by name, `{name}`, with `name = value` passed after the format string. A name
+    /// that is not passed explicitly is captured from a variable in scope.
+    pub fn format_arguments() {
+        assert_eq!(format!("{0} {1} {0}", "a", "b"), "a b a");
+        assert_eq!(format!("{name}", name = "rust"), "rust");
+
+        let name: &str = "rust";
+        assert_eq!(format!("{name}"), "rust");
+
+        // arguments and format specs combine
+        let width: usize = 6;
+        assert_eq!(format!("{name:>width$}"), "  rust");
+        assert_eq!(format!("{0:>1$}", name, width), "  rust");
+    }
+}
+
+pub mod compile_errors {
+    //! The cases `index_string` and `update_string::with_plus_operator` describe as rejected, as
+    //! `compile_fail` doc tests: `cargo test` fails if one of them starts compiling. Each function
+    //! body is the accepted version.
+    //!
+    //! Slicing in the middle of a character is not one of them, it compiles and panics at
+    //! runtime, so it is checked by a `#[should_panic]` test instead.
+
+    /// Indexing a `String` with a number, error[E0277]: the type `str` cannot be indexed by
+    /// `{integer}`. A byte index may fall inside a character, so the accepted version asks for
+    /// the characters explicitly.
+    ///
+    /// ```compile_fail,E0277
+    /// let s1 = String::from("hello");
+    /// let h = s1[0];
+    /// ```
+    pub fn index_with_integer() -> Option<char> {
+        let s1 = String::from("hello");
+        s1.chars().next()
+    }
+
+    /// `+` takes its left operand by value, error[E0382]: borrow of moved value: `s1`.
+    ///
+    /// ```compile_fail,E0382
+    /// let s1: String = String::from("hello, ");
+    /// let s2: String = String::from("rust!");
+    /// let s3 = s1 + &s2;
+    /// println!("{} {}", s1, s3);
+    /// ```
+    pub fn use_after_plus() -> String {
+        let s1: String = String::from("hello, ");
+        let s2: String = String::from("rust!");
+        let s3 = s1.clone() + &s2;
+        format!("{} {}", s1, s3)
+    }
+
+    /// The right operand of `+` is a `&str`, error[E0308]: mismatched types, expected `&str`,
+    /// found `String`.
+    ///
+    /// ```compile_fail,E0308
+    /// let s1: String = String::from("tic");
+    /// let s2: String = String::from("tac");
+    /// let s = s1 + s2;
+    /// ```
+    pub fn plus_two_strings() -> String {
+        let s1: String = String::from("tic");
+        let s2: String = String::from("tac");
+        s1 + &s2
+    }
+}
+
 #[cfg(test)]
 mod testing {
     #[test]
@@ -373,7 +654,34 @@ mod testing {
 
     #[test]
     fn run_string_memory_layout() {
-        crate::string_memory_layout();
+        let layout: crate::StringLayout = crate::string_memory_layout();
+        assert_eq!(layout.len, 4);
+        assert_eq!(layout.capacity, 500);
+        assert_ne!(layout.addr, layout.data_ptr);
+    }
+
+    #[test]
+    fn run_string_layout_safe_and_unsafe_agree() {
+        use crate::{inspect_string, unsafe_inspect_string};
+        let mut with_capacity: String = String::with_capacity(500);
+        with_capacity.push_str("rust");
+        let strings: [String; 5] = [
+            String::new(),
+            String::with_capacity(500),
+            with_capacity,
+            String::from("größe 名前 🦀"),
+            String::from("abc"),
+        ];
+        for s in &strings {
+            assert_eq!(unsafe_inspect_string(s), inspect_string(s), "{:?}", s);
+        }
+        let layout = inspect_string(&strings[3]);
+        // the length is in bytes, not chars
+        assert_eq!(layout.len, 19);
+        assert_eq!(strings[3].chars().count(), 10);
+        assert_eq!(inspect_string(&strings[0]).capacity, 0);
+        assert_eq!(inspect_string(&strings[1]).capacity, 500);
+        assert_eq!(inspect_string(&strings[1]).len, 0);
     }
 
     #[test]
@@ -460,4 +768,81 @@ mod testing {
     fn run_common_used_method_of_string_remove() {
         crate::common_used_method_of_string::remove();
     }
+
+    #[test]
+    fn run_split_string_with_split() {
+        crate::split_string::with_split();
+    }
+
+    #[test]
+    fn run_split_string_with_splitn() {
+        crate::split_string::with_splitn();
+    }
+
+    #[test]
+    fn run_split_string_with_rsplit() {
+        crate::split_string::with_rsplit();
+    }
+
+    #[test]
+    fn run_split_string_with_split_whitespace() {
+        crate::split_string::with_split_whitespace();
+    }
+
+    #[test]
+    fn run_split_string_with_lines() {
+        crate::split_string::with_lines();
+    }
+
+    #[test]
+    fn run_split_string_split_vs_split_whitespace() {
+        crate::split_string::split_vs_split_whitespace();
+    }
+
+    #[test]
+    fn run_format_spec_width_and_alignment() {
+        crate::format_spec::width_and_alignment();
+    }
+
+    #[test]
+    fn run_format_spec_zero_padding() {
+        crate::format_spec::zero_padding();
+    }
+
+    #[test]
+    fn run_format_spec_precision() {
+        crate::format_spec::precision();
+    }
+
+    #[test]
+    fn run_format_spec_sign() {
+        crate::format_spec::sign();
+    }
+
+    #[test]
+    fn run_format_spec_alternate_form() {
+        crate::format_spec::alternate_form();
+    }
+
+    #[test]
+    fn run_format_spec_format_arguments() {
+        crate::format_spec::format_arguments();
+    }
+
+    #[test]
+    fn run_compile_errors_accepted_versions() {
+        assert_eq!(crate::compile_errors::index_with_integer(), Some('h'));
+        assert_eq!(
+            crate::compile_errors::use_after_plus(),
+            "hello,  hello, rust!"
+        );
+        assert_eq!(crate::compile_errors::plus_two_strings(), "tictac");
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a char boundary")]
+    fn run_compile_errors_slice_inside_char() {
+        let hello: String = "Здравствуйте".to_string();
+        let _s: &str = &hello[0..1];
+    }
 }
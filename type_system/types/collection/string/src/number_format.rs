@@ -0,0 +1,218 @@
+//! # Number format
+//!
+//! Turns numbers into strings meant for people: digit grouping, a fixed number of decimals,
+//! engineering notation and byte sizes, plus `parse_thousands` to read grouped integers back.
+//!
+//! ```text
+//! with_thousands(-1234567, ',')  -> "-1,234,567"
+//! fixed(2.5, 0)                  -> "2"
+//! engineering(0.00123)           -> "1.23e-3"
+//! human_bytes(1536)              -> "1.5 KiB"
+//! ```
+
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum NumError {
+    Empty,
+    InvalidDigit,
+    /// The first group has 1 to 3 digits, every following group exactly 3.
+    BadGrouping,
+    Overflow,
+}
+
+impl fmt::Display for NumError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NumError::Empty => write!(f, "empty number"),
+            NumError::InvalidDigit => write!(f, "invalid digit"),
+            NumError::BadGrouping => write!(f, "digits are not grouped by three"),
+            NumError::Overflow => write!(f, "number does not fit in an i128"),
+        }
+    }
+}
+
+impl std::error::Error for NumError {}
+
+/// `unsigned_abs` gives the magnitude as a `u128`, so `i128::MIN`, whose magnitude is one more
+/// than `i128::MAX`, does not overflow as `-n` would.
+pub fn with_thousands(n: i128, sep: char) -> String {
+    let digits: String = n.unsigned_abs().to_string();
+    let mut grouped: String = String::with_capacity(digits.len() + digits.len() / 3 + 1);
+    if n < 0 {
+        grouped.push('-');
+    }
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(sep);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// `{:.N}` rounds the exact binary value, and a tie, which only happens when the value is exactly
+/// representable like `2.5` or `0.125`, goes to the even digit. So `fixed(0.125, 2)` is `"0.12"`
+/// while `fixed(0.135, 2)` is `"0.14"`, because `0.135` is stored slightly above.
+///
+/// `format!` keeps the sign of negative zero, and of negative values rounding to zero, printing
+/// `"-0.00"`. A minus sign in front of nothing but zeros is dropped here.
+pub fn fixed(x: f64, decimals: usize) -> String {
+    let formatted: String = format!("{:.*}", decimals, x);
+    match formatted.strip_prefix('-') {
+        Some(rest) if rest.chars().all(|c| c == '0' || c == '.') => rest.to_string(),
+        _ => formatted,
+    }
+}
+
+/// Like scientific notation, but the exponent is a multiple of 3 so it maps onto the SI prefixes
+/// (kilo, milli, ...) and the mantissa lies in `[1, 1000)`. The mantissa keeps up to three
+/// decimals, trailing zeros are removed.
+pub fn engineering(x: f64) -> String {
+    if !x.is_finite() {
+        return x.to_string();
+    }
+    if x == 0.0 {
+        return String::from("0e0");
+    }
+    let mut exponent: i32 = (x.abs().log10().floor() as i32).div_euclid(3) * 3;
+    let mut mantissa: f64 = x / 10f64.powi(exponent);
+    // `log10` may be off by one ulp around exact powers of ten, and rounding may reach 1000
+    if mantissa.abs() < 1.0 {
+        exponent -= 3;
+        mantissa *= 1000.0;
+    }
+    if (mantissa.abs() * 1000.0).round() >= 1_000_000.0 {
+        exponent += 3;
+        mantissa /= 1000.0;
+    }
+    let mantissa: String = fixed(mantissa, 3);
+    let mantissa: &str = mantissa.trim_end_matches('0').trim_end_matches('.');
+    format!("{}e{}", mantissa, exponent)
+}
+
+/// Binary units, one step every 1024. Below 1 KiB the exact count is shown.
+pub fn human_bytes(n: u64) -> String {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    if n < 1024 {
+        return format!("{} B", n);
+    }
+    let mut value: f64 = n as f64 / 1024.0;
+    let mut unit: usize = 0;
+    // 1023.95 and above would print as "1024.0", move to the next unit instead
+    while value >= 1023.95 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// The inverse of `with_thousands`. An optional leading `-`, then groups separated by `sep`.
+pub fn parse_thousands(s: &str, sep: char) -> Result<i128, NumError> {
+    let (negative, body): (bool, &str) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    if body.is_empty() {
+        return Err(NumError::Empty);
+    }
+    let mut digits: String = String::from(if negative { "-" } else { "" });
+    for (i, group) in body.split(sep).enumerate() {
+        if !group.chars().all(|c| c.is_ascii_digit()) {
+            return Err(NumError::InvalidDigit);
+        }
+        let valid: bool = if i == 0 {
+            (1..=3).contains(&group.len())
+        } else {
+            group.len() == 3
+        };
+        if !valid {
+            return Err(NumError::BadGrouping);
+        }
+        digits.push_str(group);
+    }
+    // parsing with the sign attached is what lets i128::MIN through
+    digits.parse::<i128>().map_err(|_| NumError::Overflow)
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn run_with_thousands() {
+        assert_eq!(with_thousands(0, ','), "0");
+        assert_eq!(with_thousands(999, ','), "999");
+        assert_eq!(with_thousands(1000, ','), "1,000");
+        assert_eq!(with_thousands(-1234567, '_'), "-1_234_567");
+        assert_eq!(
+            with_thousands(i128::MIN, ','),
+            "-170,141,183,460,469,231,731,687,303,715,884,105,728"
+        );
+    }
+
+    #[test]
+    fn run_parse_thousands() {
+        assert_eq!(parse_thousands("1,234,567", ','), Ok(1234567));
+        assert_eq!(parse_thousands("-12", ','), Ok(-12));
+        for n in [0, 7, -999, 1_000, 123_456_789, i128::MAX, i128::MIN] {
+            assert_eq!(parse_thousands(&with_thousands(n, '.'), '.'), Ok(n));
+        }
+
+        assert_eq!(parse_thousands("1,23,4", ','), Err(NumError::BadGrouping));
+        assert_eq!(parse_thousands("1234", ','), Err(NumError::BadGrouping));
+        assert_eq!(parse_thousands("1,2345", ','), Err(NumError::BadGrouping));
+        assert_eq!(parse_thousands(",123", ','), Err(NumError::BadGrouping));
+        assert_eq!(parse_thousands("1,234,", ','), Err(NumError::BadGrouping));
+        assert_eq!(parse_thousands("1_234", ','), Err(NumError::InvalidDigit));
+        assert_eq!(parse_thousands("+1", ','), Err(NumError::InvalidDigit));
+        assert_eq!(parse_thousands("-", ','), Err(NumError::Empty));
+        assert_eq!(parse_thousands("", ','), Err(NumError::Empty));
+
+        let too_big: String = with_thousands(i128::MAX, ',').replace("727", "728");
+        assert_eq!(parse_thousands(&too_big, ','), Err(NumError::Overflow));
+    }
+
+    #[test]
+    fn run_fixed() {
+        assert_eq!(fixed(1.23456, 2), "1.23");
+        assert_eq!(fixed(2.5, 0), "2");
+        assert_eq!(fixed(3.5, 0), "4");
+        assert_eq!(fixed(0.125, 2), "0.12");
+        assert_eq!(fixed(0.135, 2), "0.14");
+        assert_eq!(fixed(1.0, 3), "1.000");
+        assert_eq!(fixed(-1.5, 1), "-1.5");
+
+        assert_eq!(format!("{:.2}", -0.0), "-0.00");
+        assert_eq!(fixed(-0.0, 2), "0.00");
+        assert_eq!(fixed(-0.001, 2), "0.00");
+        assert_eq!(fixed(-0.0, 0), "0");
+    }
+
+    #[test]
+    fn run_engineering() {
+        assert_eq!(engineering(12300.0), "12.3e3");
+        assert_eq!(engineering(1000.0), "1e3");
+        assert_eq!(engineering(999.0), "999e0");
+        assert_eq!(engineering(-4.7e7), "-47e6");
+        assert_eq!(engineering(0.0), "0e0");
+
+        assert_eq!(engineering(0.5), "500e-3");
+        assert_eq!(engineering(0.001), "1e-3");
+        assert_eq!(engineering(0.00123), "1.23e-3");
+        assert_eq!(engineering(2.2e-11), "22e-12");
+        assert_eq!(engineering(999_999.999_9), "1e6");
+    }
+
+    #[test]
+    fn run_human_bytes() {
+        assert_eq!(human_bytes(0), "0 B");
+        assert_eq!(human_bytes(999), "999 B");
+        assert_eq!(human_bytes(1023), "1023 B");
+        assert_eq!(human_bytes(1024), "1.0 KiB");
+        assert_eq!(human_bytes(1536), "1.5 KiB");
+        assert_eq!(human_bytes(1024 * 1024 - 1), "1.0 MiB");
+        assert_eq!(human_bytes(5 * 1024 * 1024 * 1024), "5.0 GiB");
+        assert_eq!(human_bytes(u64::MAX), "16.0 EiB");
+    }
+}
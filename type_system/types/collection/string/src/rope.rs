@@ -0,0 +1,265 @@
+//! # Rope
+//!
+//! A string kept as a list of chunks instead of one buffer. Appending fills the last chunk until
+//! it reaches the chunk size, then starts a new one, so the text written so far is never copied
+//! again, whereas a growing `String` moves all of its bytes every time it reallocates. Joining two
+//! ropes moves the other rope's chunks, not their text.
+//!
+//! ```text
+//! push_str("hello ") push_str("rope") push_str("!")     chunk size 8
+//! chunks ["hello "] ["rope!"]                            len 11
+//! ```
+//!
+//! A chunk always holds whole pushes: a `&str` that does not fit in the last chunk goes into a
+//! new one, even when it is longer than the chunk size. So no char ever spans two chunks, which
+//! is what lets `char_at` look for a char inside a single chunk.
+//!
+//! The price is on the reading side: the text is not contiguous, `char_at` walks the chunks and
+//! `to_string` has to copy everything into one buffer.
+
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_CHUNK_SIZE: usize = 1024;
+
+#[derive(Debug, Clone)]
+pub struct Rope {
+    chunks: Vec<String>,
+    /// The length in bytes, the sum of the chunk lengths.
+    len: usize,
+    chunk_size: usize,
+}
+
+impl Rope {
+    pub fn new() -> Self {
+        Self::with_chunk_size(DEFAULT_CHUNK_SIZE)
+    }
+
+    pub fn with_chunk_size(chunk_size: usize) -> Self {
+        Rope {
+            chunks: vec![],
+            len: 0,
+            chunk_size,
+        }
+    }
+
+    /// A chunk started here is allocated with room for `chunk_size` bytes, so appending to it
+    /// never reallocates. After `concat` the last chunk comes from the other rope with whatever
+    /// capacity it had there, and filling it up to `chunk_size` may reallocate it.
+    pub fn push_str(&mut self, s: &str) {
+        if s.is_empty() {
+            return;
+        }
+        match self.chunks.last_mut() {
+            Some(last) if last.len() + s.len() <= self.chunk_size => last.push_str(s),
+            _ => {
+                let mut chunk: String = String::with_capacity(self.chunk_size.max(s.len()));
+                chunk.push_str(s);
+                self.chunks.push(chunk);
+            }
+        }
+        self.len += s.len();
+    }
+
+    /// Appends the chunks of `other` as they are, its text is not copied. The chunk size of
+    /// `self` is kept, and the next `push_str` may still append to the last chunk of `other`.
+    pub fn concat(mut self, other: Rope) -> Rope {
+        self.chunks.extend(other.chunks);
+        self.len += other.len;
+        self
+    }
+
+    /// The char at char index `idx`, found by skipping whole chunks first.
+    pub fn char_at(&self, idx: usize) -> Option<char> {
+        let mut idx: usize = idx;
+        for chunk in &self.chunks {
+            let count: usize = chunk.chars().count();
+            if idx < count {
+                return chunk.chars().nth(idx);
+            }
+            idx -= count;
+        }
+        None
+    }
+
+    /// Copies the chunks into a `String` allocated once, with exactly `byte_len` bytes.
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        let mut s: String = String::with_capacity(self.len);
+        for chunk in &self.chunks {
+            s.push_str(chunk);
+        }
+        s
+    }
+
+    pub fn byte_len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter_chunks(&self) -> impl Iterator<Item = &str> {
+        self.chunks.iter().map(|chunk| chunk.as_str())
+    }
+}
+
+impl Default for Rope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds the same text by pushing `parts` into a `Rope` and into a `String`, with
+/// `(rope, string)` timings. Only meaningful in a release build.
+pub fn bench_build(parts: &[&str]) -> (Duration, Duration) {
+    let start: Instant = Instant::now();
+    let mut rope: Rope = Rope::new();
+    for part in parts {
+        rope.push_str(std::hint::black_box(part));
+    }
+    let rope_time: Duration = start.elapsed();
+
+    let start: Instant = Instant::now();
+    let mut string: String = String::new();
+    for part in parts {
+        string.push_str(std::hint::black_box(part));
+    }
+    let string_time: Duration = start.elapsed();
+
+    assert_eq!(rope.to_string(), string);
+    (rope_time, string_time)
+}
+
+/// Counts, while pushing `parts`, the times text already written was moved to a bigger buffer,
+/// `(rope, string)`. It is seen as a change in capacity of a buffer that was already allocated.
+/// The rope's own `Vec<String>` also grows, but that moves the chunk headers, not the text.
+pub fn reallocations(parts: &[&str], chunk_size: usize) -> (usize, usize) {
+    let mut rope: Rope = Rope::with_chunk_size(chunk_size);
+    let mut rope_count: usize = 0;
+    for part in parts {
+        let chunks: usize = rope.chunks.len();
+        let capacity: Option<usize> = rope.chunks.last().map(String::capacity);
+        rope.push_str(part);
+        if rope.chunks.len() == chunks && rope.chunks.last().map(String::capacity) != capacity {
+            rope_count += 1;
+        }
+    }
+
+    let mut string: String = String::new();
+    let mut string_count: usize = 0;
+    for part in parts {
+        let capacity: usize = string.capacity();
+        string.push_str(part);
+        if capacity > 0 && string.capacity() != capacity {
+            string_count += 1;
+        }
+    }
+    (rope_count, string_count)
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    fn parts() -> Vec<&'static str> {
+        vec![
+            "hello",
+            ", ",
+            "ропе",
+            "",
+            "🦀🦀",
+            " and ",
+            "中文",
+            "!",
+            "a longer part than a chunk",
+        ]
+    }
+
+    fn build(parts: &[&str], chunk_size: usize) -> Rope {
+        let mut rope: Rope = Rope::with_chunk_size(chunk_size);
+        for part in parts {
+            rope.push_str(part);
+        }
+        rope
+    }
+
+    #[test]
+    fn run_rope_matches_naive_build() {
+        let naive: String = parts().concat();
+        for chunk_size in [1, 4, 8, 16, DEFAULT_CHUNK_SIZE] {
+            let rope: Rope = build(&parts(), chunk_size);
+            assert_eq!(rope.to_string(), naive);
+            assert_eq!(rope.to_string().capacity(), naive.len());
+            assert_eq!(rope.byte_len(), naive.len());
+            for (i, c) in naive.chars().enumerate() {
+                assert_eq!(rope.char_at(i), Some(c));
+            }
+            assert_eq!(rope.char_at(naive.chars().count()), None);
+        }
+
+        // bench_build asserts both end with the same text
+        bench_build(&parts().repeat(50));
+    }
+
+    #[test]
+    fn run_rope_chunks_hold_whole_pushes() {
+        let rope: Rope = build(&parts(), 8);
+        let chunks: Vec<&str> = rope.iter_chunks().collect();
+        assert_eq!(
+            chunks,
+            vec![
+                "hello, ",
+                "ропе",
+                "🦀🦀",
+                " and ",
+                "中文!",
+                "a longer part than a chunk"
+            ]
+        );
+        // every chunk fits the chunk size unless it is a single longer push
+        assert!(chunks.iter().all(|c| c.len() <= 8 || parts().contains(c)));
+
+        let (rope_count, string_count) = reallocations(&parts().repeat(100), 64);
+        assert_eq!(rope_count, 0);
+        assert!(string_count > 0);
+    }
+
+    #[test]
+    fn run_rope_concat_order() {
+        let left: Rope = build(&["ab", "cd"], 4);
+        let right: Rope = build(&["ef", "gh", "ij"], 4);
+        let joined: Rope = left.clone().concat(right.clone());
+        assert_eq!(joined.to_string(), "abcdefghij");
+        assert_eq!(joined.byte_len(), 10);
+        assert_eq!(
+            joined.iter_chunks().collect::<Vec<&str>>(),
+            vec!["abcd", "efgh", "ij"]
+        );
+        assert_eq!(joined.char_at(4), Some('e'));
+        assert_eq!(right.concat(left).to_string(), "efghijabcd");
+
+        // the joined rope keeps appending to its last chunk
+        let mut joined: Rope = joined;
+        joined.push_str("k");
+        assert_eq!(joined.iter_chunks().last(), Some("ijk"));
+    }
+
+    #[test]
+    fn run_rope_empty() {
+        let empty: Rope = Rope::new();
+        assert!(empty.is_empty());
+        assert_eq!(empty.byte_len(), 0);
+        assert_eq!(empty.to_string(), "");
+        assert_eq!(empty.char_at(0), None);
+        assert_eq!(empty.iter_chunks().count(), 0);
+
+        let mut rope: Rope = Rope::new();
+        rope.push_str("");
+        assert!(rope.is_empty());
+        assert_eq!(rope.iter_chunks().count(), 0);
+
+        let rope: Rope = Rope::new().concat(build(&["x"], 4)).concat(Rope::new());
+        assert_eq!(rope.to_string(), "x");
+    }
+}
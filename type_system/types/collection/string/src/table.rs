@@ -0,0 +1,227 @@
+//! # Table
+//!
+//! Renders rows of strings as an ASCII table. Column widths are counts of [char]s rather than
+//! bytes, because `format!` padding counts chars too, so `é` or `ж` take one column like an ASCII
+//! letter. A char count is not a display width though: wide chars such as CJK ideographs and
+//! most emoji take two columns in a terminal, and rows holding them will misalign.
+//!
+//! ```text
+//! +-------+-----+
+//! | name  | age |
+//! +-------+-----+
+//! | rust  |  13 |
+//! | c++   |  42 |
+//! +-------+-----+
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// A row didn't have as many cells as the table has headers.
+#[derive(Debug, PartialEq)]
+pub struct ArityError {
+    pub expected: usize,
+    pub got: usize,
+}
+
+/// The fields are private so that every row goes through `push_row` and has one cell per
+/// header, which `render` relies on.
+#[derive(Debug, PartialEq)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: Vec<String>) -> Self {
+        Self {
+            headers,
+            rows: vec![],
+        }
+    }
+
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    pub fn rows(&self) -> &[Vec<String>] {
+        &self.rows
+    }
+
+    pub fn push_row(&mut self, cells: Vec<String>) -> Result<(), ArityError> {
+        if cells.len() != self.headers.len() {
+            return Err(ArityError {
+                expected: self.headers.len(),
+                got: cells.len(),
+            });
+        }
+        self.rows.push(cells);
+        Ok(())
+    }
+
+    /// Renders the table, every line ends with `\n`. Headers are left-aligned, cells are
+    /// left-aligned unless they look like a number, in which case they are right-aligned.
+    pub fn render(&self) -> String {
+        let widths: Vec<usize> = (0..self.headers.len())
+            .map(|i| {
+                self.rows
+                    .iter()
+                    .map(|row| row[i].chars().count())
+                    .chain(std::iter::once(self.headers[i].chars().count()))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let border: String = widths.iter().fold(String::from("+"), |mut line, w| {
+            line.push_str(&"-".repeat(w + 2));
+            line.push('+');
+            line
+        });
+
+        let mut out: String = String::new();
+        out.push_str(&border);
+        out.push('\n');
+        out.push_str(&render_line(&self.headers, &widths, |_| false));
+        out.push_str(&border);
+        out.push('\n');
+        if !self.rows.is_empty() {
+            for row in &self.rows {
+                out.push_str(&render_line(row, &widths, is_numeric));
+            }
+            out.push_str(&border);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Builds a table from fixed-size records, formatting every value with `Display`.
+    pub fn from_records<T: Display, const N: usize>(headers: [&str; N], rows: &[[T; N]]) -> Table {
+        Table {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: rows
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+                .collect(),
+        }
+    }
+}
+
+/// A two-column `key | value` table, sorted by key so the output is deterministic.
+impl From<&HashMap<String, String>> for Table {
+    fn from(map: &HashMap<String, String>) -> Self {
+        let mut entries: Vec<(&String, &String)> = map.iter().collect();
+        entries.sort();
+        Table {
+            headers: vec![String::from("key"), String::from("value")],
+            rows: entries
+                .into_iter()
+                .map(|(k, v)| vec![k.clone(), v.clone()])
+                .collect(),
+        }
+    }
+}
+
+fn render_line(cells: &[String], widths: &[usize], right_align: impl Fn(&str) -> bool) -> String {
+    let mut line: String = String::from("|");
+    for (cell, &width) in cells.iter().zip(widths) {
+        if right_align(cell) {
+            line.push_str(&format!(" {:>width$} |", cell));
+        } else {
+            line.push_str(&format!(" {:<width$} |", cell));
+        }
+    }
+    line.push('\n');
+    line
+}
+
+/// An optional sign, digits, and at most one decimal point: `42`, `-3.5`, `+.5`.
+fn is_numeric(cell: &str) -> bool {
+    let digits: &str = cell.strip_prefix(['+', '-']).unwrap_or(cell);
+    digits.chars().any(|c| c.is_ascii_digit())
+        && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && digits.chars().filter(|&c| c == '.').count() <= 1
+}
+
+#[cfg(test)]
+mod testing {
+    use super::{ArityError, Table};
+    use std::collections::HashMap;
+
+    #[test]
+    fn run_table_render() {
+        let mut table: Table = Table::new(vec![String::from("name"), String::from("age")]);
+        table
+            .push_row(vec![String::from("rust"), String::from("13")])
+            .unwrap();
+        table
+            .push_row(vec![String::from("c++"), String::from("-4.5")])
+            .unwrap();
+        assert_eq!(
+            table.render(),
+            "+------+------+\n\
+             | name | age  |\n\
+             +------+------+\n\
+             | rust |   13 |\n\
+             | c++  | -4.5 |\n\
+             +------+------+\n"
+        );
+    }
+
+    #[test]
+    fn run_table_render_headers_only() {
+        let table: Table = Table::from_records::<u8, 2>(["id", "score"], &[]);
+        assert_eq!(
+            table.render(),
+            "+----+-------+\n\
+             | id | score |\n\
+             +----+-------+\n"
+        );
+    }
+
+    #[test]
+    fn run_table_render_multibyte() {
+        let table: Table =
+            Table::from_records(["语言", "emoji"], &[["中文", "🔥"], ["rust", "🦀🦀"]]);
+        assert_eq!(
+            table.render(),
+            "+------+-------+\n\
+             | 语言   | emoji |\n\
+             +------+-------+\n\
+             | 中文   | 🔥     |\n\
+             | rust | 🦀🦀    |\n\
+             +------+-------+\n"
+        );
+    }
+
+    #[test]
+    fn run_table_from_hash_map() {
+        let map: HashMap<String, String> = HashMap::from([
+            (String::from("rust"), String::from("2015")),
+            (String::from("c"), String::from("1972")),
+        ]);
+        assert_eq!(
+            Table::from(&map).render(),
+            "+------+-------+\n\
+             | key  | value |\n\
+             +------+-------+\n\
+             | c    |  1972 |\n\
+             | rust |  2015 |\n\
+             +------+-------+\n"
+        );
+    }
+
+    #[test]
+    fn run_table_arity_error() {
+        let mut table: Table = Table::new(vec![String::from("a"), String::from("b")]);
+        assert_eq!(
+            table.push_row(vec![String::from("1")]),
+            Err(ArityError {
+                expected: 2,
+                got: 1
+            })
+        );
+        assert!(table.rows().is_empty());
+        assert_eq!(table.headers(), ["a", "b"]);
+    }
+}
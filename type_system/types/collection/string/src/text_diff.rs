@@ -0,0 +1,322 @@
+//! # Text diff
+//!
+//! A line diff is the longest common subsequence (LCS) of the two lists of lines: the lines in
+//! it are kept, the other old lines are removed and the other new lines are added. The lengths
+//! of the LCS of every pair of suffixes go in a table, computed from the end:
+//!
+//! ```text
+//! lcs[i][j] = lcs[i + 1][j + 1] + 1                  if old[i] == new[j]
+//!           = max(lcs[i + 1][j], lcs[i][j + 1])      otherwise
+//! ```
+//!
+//! and the edits are read from the start, following the table. That takes `old.len() *
+//! new.len()` time and memory, fine for files of a few thousand lines.
+//!
+//! A line includes its `\n`, so `"a\nb"` and `"a\nb\n"` differ in their last line, and
+//! applying the edits gives back the new text byte for byte. `unified` renders the changes like
+//! `diff -u`, without the `---`/`+++` file names:
+//!
+//! ```text
+//! @@ -2,3 +2,3 @@        old lines 2 to 4, new lines 2 to 4
+//!  b                     context, in both
+//! -c                     removed from old
+//! +C                     added in new
+//!  d
+//! ```
+//!
+//! The count is always written, and a hunk with no line on one side gives the line before it,
+//! `-0,0` when adding to an empty text. A last line without `\n` is followed by
+//! `\ No newline at end of file`.
+
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineEdit<'a> {
+    Context(&'a str),
+    Added(&'a str),
+    Removed(&'a str),
+}
+
+impl LineEdit<'_> {
+    fn is_change(&self) -> bool {
+        !matches!(self, LineEdit::Context(_))
+    }
+}
+
+fn lines(text: &str) -> Vec<&str> {
+    text.split_inclusive('\n').collect()
+}
+
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<LineEdit<'a>> {
+    let (old, new): (Vec<&str>, Vec<&str>) = (lines(old), lines(new));
+    let mut lcs: Vec<Vec<usize>> = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits: Vec<LineEdit> = vec![];
+    let (mut i, mut j): (usize, usize) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            edits.push(LineEdit::Context(old[i]));
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            // on a tie the removal goes first, so a replaced line reads `-old` then `+new`
+            edits.push(LineEdit::Removed(old[i]));
+            i += 1;
+        } else {
+            edits.push(LineEdit::Added(new[j]));
+            j += 1;
+        }
+    }
+    edits
+}
+
+/// Groups the changes into ranges of `edits`, each with up to `context` lines around it. Two
+/// changes separated by at most `2 * context` unchanged lines share a hunk, their context would
+/// overlap or touch otherwise.
+fn hunks(edits: &[LineEdit], context: usize) -> Vec<Range<usize>> {
+    let mut hunks: Vec<Range<usize>> = vec![];
+    for (index, _) in edits.iter().enumerate().filter(|(_, e)| e.is_change()) {
+        let start: usize = index.saturating_sub(context);
+        let end: usize = (index + 1 + context).min(edits.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.end => last.end = end,
+            _ => hunks.push(start..end),
+        }
+    }
+    hunks
+}
+
+/// `start,count` of a hunk side, `before` being the number of lines of that side before it.
+fn header_range(before: usize, count: usize) -> String {
+    if count == 0 {
+        format!("{},0", before)
+    } else {
+        format!("{},{}", before + 1, count)
+    }
+}
+
+fn push_line(out: &mut String, prefix: char, line: &str) {
+    out.push(prefix);
+    out.push_str(line);
+    if !line.ends_with('\n') {
+        out.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
+/// An empty string when the two texts are equal.
+pub fn unified(old: &str, new: &str, context: usize) -> String {
+    let edits: Vec<LineEdit> = diff_lines(old, new);
+    let mut out: String = String::new();
+    // lines of each side before the current edit
+    let (mut old_line, mut new_line): (usize, usize) = (0, 0);
+    let mut position: usize = 0;
+
+    for hunk in hunks(&edits, context) {
+        for edit in &edits[position..hunk.start] {
+            old_line += usize::from(!matches!(edit, LineEdit::Added(_)));
+            new_line += usize::from(!matches!(edit, LineEdit::Removed(_)));
+        }
+        let lines: &[LineEdit] = &edits[hunk.clone()];
+        let removed: usize = lines
+            .iter()
+            .filter(|e| matches!(e, LineEdit::Removed(_)))
+            .count();
+        let added: usize = lines
+            .iter()
+            .filter(|e| matches!(e, LineEdit::Added(_)))
+            .count();
+        let unchanged: usize = lines.len() - removed - added;
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            header_range(old_line, unchanged + removed),
+            header_range(new_line, unchanged + added)
+        ));
+        for edit in lines {
+            match edit {
+                LineEdit::Context(line) => push_line(&mut out, ' ', line),
+                LineEdit::Added(line) => push_line(&mut out, '+', line),
+                LineEdit::Removed(line) => push_line(&mut out, '-', line),
+            }
+        }
+        old_line += unchanged + removed;
+        new_line += unchanged + added;
+        position = hunk.end;
+    }
+    out
+}
+
+/// Replays `edits` on `old`: the context lines are copied, the removed ones skipped and the
+/// added ones inserted.
+///
+/// # Panics
+///
+/// If the edits were not made from `old`: a context or removed line that is not the next line
+/// of `old`, or lines of `old` left over at the end.
+pub fn apply(old: &str, edits: &[LineEdit]) -> String {
+    let mut old_lines = old.split_inclusive('\n');
+    let mut out: String = String::new();
+    for edit in edits {
+        match *edit {
+            LineEdit::Context(line) => {
+                assert_eq!(old_lines.next(), Some(line), "context does not match");
+                out.push_str(line);
+            }
+            LineEdit::Removed(line) => {
+                assert_eq!(old_lines.next(), Some(line), "removed line does not match");
+            }
+            LineEdit::Added(line) => out.push_str(line),
+        }
+    }
+    assert_eq!(old_lines.next(), None, "edits end before the old text");
+    out
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    const OLD: &str = "1\n2\n3\n4\n5\n6\n7\n8\n";
+
+    #[test]
+    fn run_text_diff_identical() {
+        assert_eq!(unified(OLD, OLD, 3), "");
+        assert_eq!(unified("", "", 3), "");
+        assert!(diff_lines(OLD, OLD)
+            .iter()
+            .all(|edit| matches!(edit, LineEdit::Context(_))));
+        assert_eq!(diff_lines("", ""), vec![]);
+    }
+
+    #[test]
+    fn run_text_diff_one_line_change() {
+        let new: &str = "1\n2\nthree\n4\n5\n6\n7\n8\n";
+        assert_eq!(
+            diff_lines("a\nb\n", "a\nc\n"),
+            vec![
+                LineEdit::Context("a\n"),
+                LineEdit::Removed("b\n"),
+                LineEdit::Added("c\n"),
+            ]
+        );
+        assert_eq!(unified(OLD, new, 0), "@@ -3,1 +3,1 @@\n-3\n+three\n");
+        assert_eq!(
+            unified(OLD, new, 1),
+            "@@ -2,3 +2,3 @@\n 2\n-3\n+three\n 4\n"
+        );
+        // the context stops at the first line
+        assert_eq!(
+            unified(OLD, new, 3),
+            "@@ -1,6 +1,6 @@\n 1\n 2\n-3\n+three\n 4\n 5\n 6\n"
+        );
+    }
+
+    #[test]
+    fn run_text_diff_first_and_last_lines() {
+        let new: &str = "one\n2\n3\n4\n5\n6\n7\neight\n";
+        assert_eq!(
+            unified(OLD, new, 1),
+            "@@ -1,2 +1,2 @@\n-1\n+one\n 2\n\
+             @@ -7,2 +7,2 @@\n 7\n-8\n+eight\n"
+        );
+        // an insertion at the start and a removal at the end
+        assert_eq!(
+            unified(OLD, "0\n1\n2\n3\n4\n5\n6\n7\n", 1),
+            "@@ -1,1 +1,2 @@\n+0\n 1\n@@ -7,2 +8,1 @@\n 7\n-8\n"
+        );
+        assert_eq!(unified("", "a\nb\n", 3), "@@ -0,0 +1,2 @@\n+a\n+b\n");
+        assert_eq!(unified("a\n", "", 3), "@@ -1,1 +0,0 @@\n-a\n");
+    }
+
+    #[test]
+    fn run_text_diff_merging_hunks() {
+        // two unchanged lines between the changes
+        let new: &str = "1\n2\nthree\n4\n5\nsix\n7\n8\n";
+        assert_eq!(
+            unified(OLD, new, 0),
+            "@@ -3,1 +3,1 @@\n-3\n+three\n@@ -6,1 +6,1 @@\n-6\n+six\n"
+        );
+        // their context touches at 1, one hunk
+        assert_eq!(
+            unified(OLD, new, 1),
+            "@@ -2,6 +2,6 @@\n 2\n-3\n+three\n 4\n 5\n-6\n+six\n 7\n"
+        );
+        // the line numbers of the new side shift after an insertion
+        let new: &str = "1\n2\n2.5\n3\n4\n5\n6\n7\nseven\n8\n";
+        assert_eq!(
+            unified(OLD, new, 1),
+            "@@ -2,2 +2,3 @@\n 2\n+2.5\n 3\n@@ -7,2 +8,3 @@\n 7\n+seven\n 8\n"
+        );
+    }
+
+    #[test]
+    fn run_text_diff_trailing_newline() {
+        assert_eq!(
+            unified("a\nb", "a\nb\n", 1),
+            "@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+b\n"
+        );
+        assert_eq!(
+            unified("a\n", "a\nb", 0),
+            "@@ -1,0 +2,1 @@\n+b\n\\ No newline at end of file\n"
+        );
+    }
+
+    /// The LCS length by trying every choice, without the table, to check `diff_lines` against.
+    fn brute_force_lcs(old: &[&str], new: &[&str]) -> usize {
+        match (old.split_first(), new.split_first()) {
+            (Some((a, old_rest)), Some((b, new_rest))) if a == b => {
+                1 + brute_force_lcs(old_rest, new_rest)
+            }
+            (Some((_, old_rest)), Some((_, new_rest))) => {
+                brute_force_lcs(old_rest, new).max(brute_force_lcs(old, new_rest))
+            }
+            _ => 0,
+        }
+    }
+
+    #[test]
+    fn run_text_diff_apply_round_trip() {
+        let texts: [&str; 9] = [
+            OLD,
+            "1\n2\nthree\n4\n5\nsix\n7\n8\n",
+            "0\n1\n2\n3\n",
+            "8\n7\n6\n5\n4\n3\n2\n1\n",
+            "a\nb",
+            "a\nb\n",
+            "\n\n",
+            "",
+            "only line",
+        ];
+        for old in texts {
+            for new in texts {
+                let edits: Vec<LineEdit> = diff_lines(old, new);
+                assert_eq!(apply(old, &edits), new, "{:?} -> {:?}", old, new);
+                // the kept lines are a longest common subsequence, no shorter diff exists
+                let kept: usize = edits.iter().filter(|e| !e.is_change()).count();
+                assert_eq!(kept, brute_force_lcs(&lines(old), &lines(new)));
+                let removed: usize = edits
+                    .iter()
+                    .filter(|e| matches!(e, LineEdit::Removed(_)))
+                    .count();
+                assert_eq!(kept + removed, lines(old).len());
+            }
+        }
+        let edits: Vec<LineEdit> = diff_lines(OLD, "8\n7\n6\n5\n4\n3\n2\n1\n");
+        assert_eq!(edits.iter().filter(|e| !e.is_change()).count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "context does not match")]
+    fn run_text_diff_apply_to_other_text() {
+        let edits: Vec<LineEdit> = diff_lines("a\nb\n", "a\nc\n");
+        apply("x\nb\n", &edits);
+    }
+}
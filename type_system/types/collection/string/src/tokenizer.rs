@@ -0,0 +1,274 @@
+//! # Tokenizer
+//!
+//! Splits source text into tokens that borrow from it: a `Token<'a>` holds `&'a str` slices of
+//! the input, so the only allocation is the output `Vec`, and the tokens can't outlive the text
+//! they point into. Each token comes with its span, the byte range it covers. Every byte of the
+//! input belongs to exactly one token, whitespace included, so the spans laid end to end give
+//! back the input.
+//!
+//! ```text
+//! let x_1 = 2.5 + "a\"b";
+//! Ident   Symbol Number Symbol StringLit Symbol     (with a Whitespace between most of them)
+//! ```
+//!
+//! * an identifier starts with a letter or `_`, then letters, digits and `_`, in any script;
+//! * a number is ASCII digits, optionally followed by `.` and more digits;
+//! * a string is in double quotes, a `\` escapes the next char. The token holds the text between
+//!   the quotes as written: the escapes are not processed, that would need a new `String`;
+//! * any other ASCII punctuation is a single char symbol.
+
+use std::fmt;
+use std::iter::Peekable;
+use std::ops::Range;
+use std::str::CharIndices;
+
+#[derive(Debug, PartialEq)]
+pub enum Token<'a> {
+    Ident(&'a str),
+    Number(&'a str),
+    StringLit(&'a str),
+    Symbol(char),
+    Whitespace,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Spanned<'a> {
+    pub token: Token<'a>,
+    pub span: Range<usize>,
+}
+
+/// Offsets are in bytes, like the spans.
+#[derive(Debug, PartialEq)]
+pub enum TokenError {
+    /// `offset` is where the opening quote is.
+    UnterminatedString {
+        offset: usize,
+    },
+    UnexpectedChar {
+        ch: char,
+        offset: usize,
+    },
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TokenError::UnterminatedString { offset } => {
+                write!(f, "string starting at byte {} is never closed", offset)
+            }
+            TokenError::UnexpectedChar { ch, offset } => {
+                write!(f, "unexpected {:?} at byte {}", ch, offset)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TokenError {}
+
+/// Advances past the chars matching `pred` and returns the offset after the last one.
+fn skip_while(chars: &mut Peekable<CharIndices>, end: usize, pred: fn(char) -> bool) -> usize {
+    let mut end: usize = end;
+    while let Some(&(i, c)) = chars.peek() {
+        if !pred(c) {
+            break;
+        }
+        end = i + c.len_utf8();
+        chars.next();
+    }
+    end
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+pub fn tokenize(input: &str) -> Result<Vec<Spanned<'_>>, TokenError> {
+    let mut tokens: Vec<Spanned> = vec![];
+    let mut chars: Peekable<CharIndices> = input.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let after: usize = start + c.len_utf8();
+        let (token, end): (Token, usize) = if c.is_whitespace() {
+            let end: usize = skip_while(&mut chars, after, char::is_whitespace);
+            (Token::Whitespace, end)
+        } else if c.is_alphabetic() || c == '_' {
+            let end: usize = skip_while(&mut chars, after, is_ident_char);
+            (Token::Ident(&input[start..end]), end)
+        } else if c.is_ascii_digit() {
+            let mut end: usize = skip_while(&mut chars, after, |c| c.is_ascii_digit());
+            // a `.` is part of the number only when a digit follows, `1.` is `1` and `.`
+            let fraction: bool = input[end..].starts_with('.')
+                && input[end + 1..].starts_with(|c: char| c.is_ascii_digit());
+            if fraction {
+                chars.next();
+                end = skip_while(&mut chars, end + 1, |c| c.is_ascii_digit());
+            }
+            (Token::Number(&input[start..end]), end)
+        } else if c == '"' {
+            let close: usize = loop {
+                match chars.next() {
+                    Some((_, '\\')) => {
+                        chars.next();
+                    }
+                    Some((i, '"')) => break i,
+                    Some(_) => {}
+                    None => return Err(TokenError::UnterminatedString { offset: start }),
+                }
+            };
+            (Token::StringLit(&input[after..close]), close + 1)
+        } else if c.is_ascii_punctuation() {
+            (Token::Symbol(c), after)
+        } else {
+            return Err(TokenError::UnexpectedChar {
+                ch: c,
+                offset: start,
+            });
+        };
+        tokens.push(Spanned {
+            token,
+            span: start..end,
+        });
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    /// The tokens without the whitespace.
+    fn tokens(input: &str) -> Vec<Token<'_>> {
+        tokenize(input)
+            .unwrap()
+            .into_iter()
+            .map(|spanned| spanned.token)
+            .filter(|token| *token != Token::Whitespace)
+            .collect()
+    }
+
+    #[test]
+    fn run_tokenizer_spans_reassemble_input() {
+        let inputs: [&str; 6] = [
+            "let x_1 = 2.5 + \"a\\\"b\";",
+            "  fn\tmain() {\n    return 1.;\n}\n",
+            "größe*2",
+            "_ __a1 a.b.c 10.25.3",
+            "\"\" \"\\\\\"",
+            "",
+        ];
+        for input in inputs {
+            let spanned: Vec<Spanned> = tokenize(input).unwrap();
+            let pieces: String = spanned.iter().map(|s| &input[s.span.clone()]).collect();
+            assert_eq!(pieces, input);
+            // the spans follow each other without gaps
+            for pair in spanned.windows(2) {
+                assert_eq!(pair[0].span.end, pair[1].span.start);
+            }
+        }
+        assert!(tokenize("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn run_tokenizer_tokens() {
+        assert_eq!(
+            tokens("let x_1 = 2.5 + 10;"),
+            vec![
+                Token::Ident("let"),
+                Token::Ident("x_1"),
+                Token::Symbol('='),
+                Token::Number("2.5"),
+                Token::Symbol('+'),
+                Token::Number("10"),
+                Token::Symbol(';'),
+            ]
+        );
+        assert_eq!(
+            tokens("1.x 3."),
+            vec![
+                Token::Number("1"),
+                Token::Symbol('.'),
+                Token::Ident("x"),
+                Token::Number("3"),
+                Token::Symbol('.'),
+            ]
+        );
+        assert_eq!(
+            tokenize(" \t\n").unwrap(),
+            vec![Spanned {
+                token: Token::Whitespace,
+                span: 0..3
+            }]
+        );
+    }
+
+    #[test]
+    fn run_tokenizer_multibyte_identifiers() {
+        let spanned: Vec<Spanned> = tokenize("größe = 名前_2").unwrap();
+        assert_eq!(
+            spanned[0],
+            Spanned {
+                token: Token::Ident("größe"),
+                span: 0..7
+            }
+        );
+        assert_eq!(
+            spanned[4],
+            Spanned {
+                token: Token::Ident("名前_2"),
+                span: 10..18
+            }
+        );
+    }
+
+    #[test]
+    fn run_tokenizer_strings() {
+        let input: &str = r#"say("a \"quoted\" word", "")"#;
+        assert_eq!(
+            tokens(input),
+            vec![
+                Token::Ident("say"),
+                Token::Symbol('('),
+                Token::StringLit(r#"a \"quoted\" word"#),
+                Token::Symbol(','),
+                Token::StringLit(""),
+                Token::Symbol(')'),
+            ]
+        );
+        // the span includes the quotes
+        assert_eq!(tokenize(input).unwrap()[2].span, 4..23);
+        // an escaped backslash does not escape the closing quote
+        assert_eq!(tokens(r#""\\""#), vec![Token::StringLit(r"\\")]);
+    }
+
+    #[test]
+    fn run_tokenizer_errors() {
+        assert_eq!(
+            tokenize("x = \"open"),
+            Err(TokenError::UnterminatedString { offset: 4 })
+        );
+        assert_eq!(
+            tokenize(r#"x = "ends in \""#),
+            Err(TokenError::UnterminatedString { offset: 4 })
+        );
+        assert_eq!(
+            tokenize("\"trailing \\"),
+            Err(TokenError::UnterminatedString { offset: 0 })
+        );
+        assert_eq!(
+            tokenize("a € b"),
+            Err(TokenError::UnexpectedChar {
+                ch: '€', offset: 2
+            })
+        );
+        assert_eq!(
+            tokenize("é→"),
+            Err(TokenError::UnexpectedChar {
+                ch: '→', offset: 2
+            })
+        );
+        assert_eq!(
+            tokenize("ok 🦀").unwrap_err().to_string(),
+            "unexpected '🦀' at byte 3"
+        );
+    }
+}
@@ -71,6 +71,20 @@ pub mod create_vector {
         let mut mutable_vector: Vec<&str> = vec!["c++"];
         mutable_vector.push("rust");
     }
+
+    /// `collect` is a thin wrapper around `FromIterator::from_iter`: `iter.collect::<Vec<T>>()`
+    /// calls `Vec::<T>::from_iter(iter)`, so both build exactly the same vector. `collect` reads
+    /// better at the end of an iterator chain, it only needs the target type from an annotation or
+    /// a turbofish.
+    pub fn range_collect() {
+        let collected: Vec<i32> = (1..=5).collect::<Vec<i32>>();
+        let from_iter: Vec<i32> = Vec::from_iter(1..=5);
+        assert_eq!(collected, from_iter);
+        assert_eq!(collected, vec![1, 2, 3, 4, 5]);
+
+        let stepped: Vec<i32> = (0..10).step_by(2).collect();
+        assert_eq!(stepped, vec![0, 2, 4, 6, 8]);
+    }
 }
 
 pub mod update_vector {
@@ -247,6 +261,1112 @@ pub mod vector_trap {
     }
 }
 
+pub mod inplace_algorithms {
+    //! Algorithms that rearrange a vector through indices and mutable slices instead of building a
+    //! new one, so they need only O(1) extra space (except the sliding window, which keeps a deque
+    //! of at most `k` indices).
+
+    use std::collections::VecDeque;
+
+    /// Removes duplicates from a sorted vector and returns the new length.
+    ///
+    /// `write` is the index of the last unique value kept so far, `read` scans ahead and copies
+    /// each new value right after it.
+    pub fn remove_dups_sorted(v: &mut Vec<i32>) -> usize {
+        if v.is_empty() {
+            return 0;
+        }
+        let mut write: usize = 0;
+        for read in 1..v.len() {
+            if v[read] != v[write] {
+                write += 1;
+                v[write] = v[read];
+            }
+        }
+        v.truncate(write + 1);
+        v.len()
+    }
+
+    /// Moves all zeros to the end, keeping the relative order of the non-zero values.
+    pub fn move_zeros_to_end(v: &mut [i32]) {
+        let mut write: usize = 0;
+        for read in 0..v.len() {
+            if v[read] != 0 {
+                v.swap(write, read);
+                write += 1;
+            }
+        }
+    }
+
+    /// Reverses the order of the space separated words in an ASCII buffer: reverse the whole
+    /// buffer, then reverse each word back. Spaces are mirrored too, so `"  a b "` becomes
+    /// `" b a  "`.
+    #[allow(clippy::ptr_arg)]
+    pub fn reverse_words_bytes(buf: &mut Vec<u8>) {
+        buf.reverse();
+        for word in buf.split_mut(|b: &u8| *b == b' ') {
+            word.reverse();
+        }
+    }
+
+    /// Returns the maximum of every window of `k` consecutive values, or nothing if `k` is `0` or
+    /// larger than the slice.
+    ///
+    /// The deque holds indices whose values are decreasing from front to back: a new value evicts
+    /// every smaller value behind it since those can never be a maximum again, and the front is
+    /// dropped once it slides out of the window.
+    pub fn max_sliding_window(v: &[i32], k: usize) -> Vec<i32> {
+        if k == 0 || k > v.len() {
+            return vec![];
+        }
+        let mut maxima: Vec<i32> = Vec::with_capacity(v.len() - k + 1);
+        let mut deque: VecDeque<usize> = VecDeque::with_capacity(k);
+        for (i, &x) in v.iter().enumerate() {
+            while deque.back().is_some_and(|&j| v[j] <= x) {
+                deque.pop_back();
+            }
+            deque.push_back(i);
+            if deque.front().is_some_and(|&j| j + k <= i) {
+                deque.pop_front();
+            }
+            if i + 1 >= k {
+                maxima.push(v[deque[0]]);
+            }
+        }
+        maxima
+    }
+}
+
+pub mod vector_capacity {
+    //! When a push exceeds the capacity, the vector allocates a larger buffer (the capacity
+    //! roughly doubles), copies every element over and frees the old buffer. Doubling keeps `push`
+    //! amortized O(1), but each reallocation is still a call to the allocator plus a copy of the
+    //! whole vector, and it invalidates any raw pointer into the old buffer.
+    //!
+    //! If the final number of elements is known up front, `Vec::with_capacity` allocates once and
+    //! no push ever reallocates.
+
+    /// Pushes `n` items into a new vector and counts how many times its capacity changed.
+    pub fn reallocation_count(n: usize) -> usize {
+        count_reallocations(Vec::new(), n)
+    }
+
+    /// Same as `reallocation_count`, but the vector is created with room for `n` items.
+    pub fn reallocation_count_with_capacity(n: usize) -> usize {
+        count_reallocations(Vec::with_capacity(n), n)
+    }
+
+    fn count_reallocations(mut v: Vec<usize>, n: usize) -> usize {
+        let mut reallocations: usize = 0;
+        let mut capacity: usize = v.capacity();
+        for i in 0..n {
+            v.push(i);
+            if v.capacity() != capacity {
+                capacity = v.capacity();
+                reallocations += 1;
+            }
+        }
+        reallocations
+    }
+
+    /// `shrink_to_fit` reallocates the buffer down to the length, giving the spare capacity back
+    /// to the allocator. It costs a reallocation and a copy, so it is only worthwhile when a vector
+    /// that grew large is going to be kept around with far fewer elements, e.g. after a bulk
+    /// `retain` or once a build-up phase is over and the vector becomes read-only.
+    ///
+    /// `reserve(n)` makes room for at least `n` more elements and may over-allocate to keep pushes
+    /// amortized, `reserve_exact(n)` asks for exactly `n` more. Neither shrinks the buffer.
+    pub fn shrink_demo() {
+        let mut v: Vec<i32> = Vec::new();
+        for i in 0..9 {
+            v.push(i);
+        }
+        assert_eq!(v.len(), 9);
+        // the exact capacities are up to the allocator and the growth strategy, only these
+        // bounds are guaranteed
+        assert!(v.capacity() >= v.len());
+
+        // the buffer may still be a little larger than the length afterwards
+        let grown: usize = v.capacity();
+        v.shrink_to_fit();
+        assert!(v.capacity() >= v.len() && v.capacity() <= grown);
+
+        // there is no room left, so `reserve` grows the buffer
+        v.reserve(1);
+        assert!(v.capacity() > v.len());
+
+        // `reserve_exact` asks for just what is needed
+        let mut v: Vec<i32> = vec![1, 2, 3];
+        v.reserve_exact(7);
+        assert!(v.capacity() >= v.len() + 7);
+
+        // reserving less than the spare capacity does nothing
+        let reserved: usize = v.capacity();
+        v.reserve(2);
+        assert_eq!(v.capacity(), reserved);
+    }
+}
+
+pub mod stable_partition {
+    //! A stable partition moves the elements matching a predicate to the front while both groups
+    //! keep their original relative order.
+
+    /// Moves matching elements into the returned vector and the others into a single auxiliary
+    /// vector appended at the end. O(n) time, O(n) extra space. Returns the partitioned vector and
+    /// the index of the first non-matching element.
+    pub fn stable_partition<T>(v: Vec<T>, pred: impl Fn(&T) -> bool) -> (Vec<T>, usize) {
+        let mut matching: Vec<T> = Vec::with_capacity(v.len());
+        let mut rest: Vec<T> = vec![];
+        for x in v {
+            if pred(&x) {
+                matching.push(x);
+            } else {
+                rest.push(x);
+            }
+        }
+        let split: usize = matching.len();
+        matching.append(&mut rest);
+        (matching, split)
+    }
+
+    /// Partitions in place with O(1) extra space by rotation: `v[..split]` is already partitioned,
+    /// and every time a run of matching elements is found after a block of non-matching ones, the
+    /// two blocks are swapped with `rotate_left`, which keeps the order within each block.
+    ///
+    /// Each rotation moves every element of the non-matching block, so the worst case (alternating
+    /// elements) is O(n²) moves, against O(n) for `stable_partition`. Prefer this only when the
+    /// allocation is not acceptable.
+    pub fn stable_partition_in_place<T>(v: &mut [T], pred: impl Fn(&T) -> bool) -> usize {
+        let mut split: usize = 0;
+        let mut i: usize = 0;
+        while i < v.len() {
+            if !pred(&v[i]) {
+                i += 1;
+                continue;
+            }
+            let run_start: usize = i;
+            while i < v.len() && pred(&v[i]) {
+                i += 1;
+            }
+            // v[split..run_start] doesn't match, v[run_start..i] matches
+            v[split..i].rotate_left(run_start - split);
+            split += i - run_start;
+        }
+        split
+    }
+
+    /// Returns the index of the first element for which `pred` is false, assuming every matching
+    /// element comes before every non-matching one. Binary search, O(log n).
+    pub fn partition_point_by<T>(sorted: &[T], pred: impl Fn(&T) -> bool) -> usize {
+        let (mut low, mut high): (usize, usize) = (0, sorted.len());
+        while low < high {
+            let mid: usize = low + (high - low) / 2;
+            if pred(&sorted[mid]) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+}
+
+pub mod arena {
+    //! Graphs are awkward with references: a node cannot hold `&Node` to its neighbours when the
+    //! graph owns all nodes, and cycles make `Rc<RefCell<_>>` leak unless `Weak` is used. The
+    //! usual answer is an arena, all values live in one `Vec<T>` and refer to each other by index.
+    //! An index is a plain `Copy` value, so it carries no borrow, and a whole graph is dropped with
+    //! its vector.
+    //!
+    //! The price is that an `Id` is not checked by the compiler: an id from another arena, or one
+    //! made up, is only caught at runtime, hence `get` returns an `Option`.
+
+    use std::collections::{HashSet, VecDeque};
+
+    /// A handle to a value in an `Arena`, the index into its `items`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Id(usize);
+
+    pub struct Arena<T> {
+        items: Vec<T>,
+    }
+
+    impl<T> Arena<T> {
+        pub fn new() -> Self {
+            Arena { items: vec![] }
+        }
+
+        pub fn alloc(&mut self, value: T) -> Id {
+            self.items.push(value);
+            Id(self.items.len() - 1)
+        }
+
+        pub fn get(&self, id: Id) -> Option<&T> {
+            self.items.get(id.0)
+        }
+
+        pub fn get_mut(&mut self, id: Id) -> Option<&mut T> {
+            self.items.get_mut(id.0)
+        }
+
+        pub fn len(&self) -> usize {
+            self.items.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.items.is_empty()
+        }
+    }
+
+    impl<T> Default for Arena<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    pub type NodeId = Id;
+
+    /// Returned by `add_edge` when one of the ends was not handed out by this graph.
+    #[derive(Debug, PartialEq)]
+    pub struct UnknownNode(pub NodeId);
+
+    struct Node {
+        label: String,
+        edges: Vec<NodeId>,
+    }
+
+    /// A directed graph whose nodes are stored in an `Arena`, edges are lists of `NodeId`.
+    #[derive(Default)]
+    pub struct GraphArena {
+        nodes: Arena<Node>,
+    }
+
+    impl GraphArena {
+        pub fn new() -> Self {
+            GraphArena {
+                nodes: Arena::new(),
+            }
+        }
+
+        pub fn add_node(&mut self, label: &str) -> NodeId {
+            self.nodes.alloc(Node {
+                label: label.to_string(),
+                edges: vec![],
+            })
+        }
+
+        pub fn add_edge(&mut self, from: NodeId, to: NodeId) -> Result<(), UnknownNode> {
+            if self.nodes.get(to).is_none() {
+                return Err(UnknownNode(to));
+            }
+            match self.nodes.get_mut(from) {
+                Some(node) => {
+                    node.edges.push(to);
+                    Ok(())
+                }
+                None => Err(UnknownNode(from)),
+            }
+        }
+
+        pub fn label(&self, id: NodeId) -> Option<&str> {
+            self.nodes.get(id).map(|node| node.label.as_str())
+        }
+
+        /// The direct successors of `id`, empty for an unknown node.
+        pub fn neighbors(&self, id: NodeId) -> &[NodeId] {
+            self.nodes.get(id).map_or(&[], |node| node.edges.as_slice())
+        }
+
+        /// Breadth-first search from `from`, which is included in the result. The `visited` set
+        /// is what stops a cycle from being walked forever.
+        pub fn reachable(&self, from: NodeId) -> HashSet<NodeId> {
+            let mut visited: HashSet<NodeId> = HashSet::new();
+            if self.nodes.get(from).is_none() {
+                return visited;
+            }
+            let mut queue: VecDeque<NodeId> = VecDeque::from([from]);
+            visited.insert(from);
+            while let Some(id) = queue.pop_front() {
+                for &next in self.neighbors(id) {
+                    if visited.insert(next) {
+                        queue.push_back(next);
+                    }
+                }
+            }
+            visited
+        }
+    }
+}
+
+pub mod scheduler {
+    //! A discrete-event scheduler: tasks are kept in a `BinaryHeap` ordered by the time they are
+    //! due, and `run_until` pops and runs them in order while advancing the clock.
+    //!
+    //! `BinaryHeap` is a max-heap, wrapping each task in `std::cmp::Reverse` turns it into a
+    //! min-heap so the earliest task is on top. Tasks due at the same time run in the order they
+    //! were scheduled, the `seq` counter breaks the tie.
+    //!
+    //! A running task can't get `&mut Scheduler`, the scheduler is already mutably borrowed by
+    //! `run_until` and owns the heap the task was just popped from. Instead the task receives a
+    //! `Context` that buffers new tasks, and `run_until` moves them into the heap after the task
+    //! returns. A heap entry can't be removed cheaply either, so `cancel` records the id in a
+    //! tombstone set, and a cancelled task is skipped when it is popped.
+
+    use std::cmp::{Ordering, Reverse};
+    use std::collections::{BinaryHeap, HashSet};
+
+    pub type Action = Box<dyn FnMut(&mut Context)>;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct TaskId(u64);
+
+    struct Task {
+        at: u64,
+        seq: u64,
+        action: Action,
+    }
+
+    // the action can't be compared, tasks are ordered by due time then insertion sequence
+    impl PartialEq for Task {
+        fn eq(&self, other: &Self) -> bool {
+            (self.at, self.seq) == (other.at, other.seq)
+        }
+    }
+
+    impl Eq for Task {}
+
+    impl PartialOrd for Task {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Task {
+        fn cmp(&self, other: &Self) -> Ordering {
+            (self.at, self.seq).cmp(&(other.at, other.seq))
+        }
+    }
+
+    /// What a task sees while it runs: a log to write to, the current time, and a buffer of tasks
+    /// to schedule once it returns.
+    #[derive(Default)]
+    pub struct Context {
+        pub log: Vec<String>,
+        now: u64,
+        pending: Vec<(u64, Action)>,
+    }
+
+    impl Context {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn now(&self) -> u64 {
+            self.now
+        }
+
+        pub fn schedule_at(&mut self, at: u64, action: impl FnMut(&mut Context) + 'static) {
+            self.pending.push((at, Box::new(action)));
+        }
+
+        pub fn schedule_after(&mut self, delay: u64, action: impl FnMut(&mut Context) + 'static) {
+            self.schedule_at(self.now + delay, action);
+        }
+    }
+
+    #[derive(Default)]
+    pub struct Scheduler {
+        queue: BinaryHeap<Reverse<Task>>,
+        now: u64,
+        next_seq: u64,
+        cancelled: HashSet<u64>,
+    }
+
+    impl Scheduler {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn now(&self) -> u64 {
+            self.now
+        }
+
+        /// A time already in the past is not rejected, the task runs at the next `run_until`.
+        pub fn schedule_at(
+            &mut self,
+            at: u64,
+            action: impl FnMut(&mut Context) + 'static,
+        ) -> TaskId {
+            self.push(at, Box::new(action))
+        }
+
+        pub fn schedule_after(
+            &mut self,
+            delay: u64,
+            action: impl FnMut(&mut Context) + 'static,
+        ) -> TaskId {
+            self.push(self.now + delay, Box::new(action))
+        }
+
+        fn push(&mut self, at: u64, action: Action) -> TaskId {
+            let seq: u64 = self.next_seq;
+            self.next_seq += 1;
+            self.queue.push(Reverse(Task { at, seq, action }));
+            TaskId(seq)
+        }
+
+        /// Returns `true` if the task was still waiting, `false` if it already ran or was already
+        /// cancelled. Finding it is a linear scan of the heap.
+        pub fn cancel(&mut self, id: TaskId) -> bool {
+            let pending: bool = self.queue.iter().any(|Reverse(task)| task.seq == id.0);
+            pending && self.cancelled.insert(id.0)
+        }
+
+        /// Runs every task due at or before `t`, including those scheduled by tasks during this
+        /// call, then moves the clock to `t`. Returns how many tasks ran.
+        pub fn run_until(&mut self, t: u64, ctx: &mut Context) -> usize {
+            let mut executed: usize = 0;
+            while self.queue.peek().is_some_and(|Reverse(task)| task.at <= t) {
+                let Reverse(mut task) = self.queue.pop().unwrap();
+                if self.cancelled.remove(&task.seq) {
+                    continue;
+                }
+                self.now = self.now.max(task.at);
+                ctx.now = self.now;
+                (task.action)(ctx);
+                executed += 1;
+                for (at, action) in std::mem::take(&mut ctx.pending) {
+                    self.push(at, action);
+                }
+            }
+            self.now = self.now.max(t);
+            ctx.now = self.now;
+            executed
+        }
+    }
+}
+
+pub mod compile_errors {
+    //! The cases `vector_trap` and `drop_vector` describe as rejected, as `compile_fail` doc
+    //! tests: `cargo test` fails if one of them starts compiling. Each function body is the
+    //! accepted version.
+
+    /// Pushing while a reference to an element is still used, error[E0502]: cannot borrow `v` as
+    /// mutable because it is also borrowed as immutable. The push may reallocate and leave
+    /// `first` dangling.
+    ///
+    /// ```compile_fail,E0502
+    /// let mut v = vec![1, 2, 3, 4, 5];
+    /// let first = &v[0];
+    /// v.push(6);
+    /// println!("The first element is: {}", first);
+    /// ```
+    pub fn push_after_last_use() -> i32 {
+        let mut v: Vec<i32> = vec![1, 2, 3, 4, 5];
+        let first: i32 = v[0]; // a copy, not a borrow
+        v.push(6);
+        first
+    }
+
+    /// Using a vector after a `for` loop consumed it, error[E0382]: borrow of moved value: `v`.
+    ///
+    /// ```compile_fail,E0382
+    /// let v: Vec<i32> = vec![1, 2, 3];
+    /// for _ in v {}
+    /// println!("{:?}", v);
+    /// ```
+    pub fn iterate_by_reference() -> usize {
+        let v: Vec<i32> = vec![1, 2, 3];
+        for _ in &v {}
+        v.len()
+    }
+
+    /// Using a vector after dropping it, error[E0382]: borrow of moved value: `v`. `drop` takes
+    /// its argument by value.
+    ///
+    /// ```compile_fail,E0382
+    /// let v: Vec<i8> = vec![1, 2, 3];
+    /// std::mem::drop(v);
+    /// println!("{:?}", v);
+    /// ```
+    pub fn drop_after_last_use() -> usize {
+        let v: Vec<i8> = vec![1, 2, 3];
+        let len: usize = v.len();
+        std::mem::drop(v);
+        len
+    }
+}
+
+pub mod gap_buffer {
+    //! A gap buffer keeps a run of empty slots, the gap, at the position of the last edit:
+    //!
+    //! ```text
+    //! logical  [a b c d e]
+    //! buf      [a b c _ _ _ d e]      gap = 3..6
+    //! ```
+    //!
+    //! Inserting at the gap fills its first slot, O(1). Editing somewhere else first moves the gap
+    //! there, which shifts only the elements in between, so a sequence of edits close to each
+    //! other, like typing in a text editor, is cheap. `Vec::insert` instead shifts the whole tail
+    //! every time. When the gap is used up the buffer grows to twice its size, as `Vec` does, so
+    //! insertions are amortized O(1) near the gap.
+    //!
+    //! The slots are `Option<T>` so that the gap holds `None` without unsafe code. Every slot in
+    //! `gap` is `None` and every other slot is `Some`, which `move_gap_to` keeps by swapping
+    //! elements with empty slots.
+
+    use std::ops::Range;
+    use std::time::{Duration, Instant};
+
+    #[derive(Debug)]
+    pub struct GapBuffer<T> {
+        buf: Vec<Option<T>>,
+        gap: Range<usize>,
+    }
+
+    impl<T> GapBuffer<T> {
+        pub fn new() -> Self {
+            GapBuffer {
+                buf: vec![],
+                gap: 0..0,
+            }
+        }
+
+        /// The vector becomes the text before the gap, the gap is empty and at the end.
+        pub fn from_vec(v: Vec<T>) -> Self {
+            let buf: Vec<Option<T>> = v.into_iter().map(Some).collect();
+            let len: usize = buf.len();
+            GapBuffer { buf, gap: len..len }
+        }
+
+        pub fn into_vec(self) -> Vec<T> {
+            self.buf.into_iter().flatten().collect()
+        }
+
+        pub fn len(&self) -> usize {
+            self.buf.len() - self.gap.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        /// The position of `index` in `buf`, elements after the gap are offset by its length.
+        fn physical(&self, index: usize) -> usize {
+            if index < self.gap.start {
+                index
+            } else {
+                index + self.gap.len()
+            }
+        }
+
+        pub fn get(&self, index: usize) -> Option<&T> {
+            if index >= self.len() {
+                return None;
+            }
+            self.buf[self.physical(index)].as_ref()
+        }
+
+        /// Moves the gap so that it starts before the element at `index`, shifting the elements
+        /// in between to the other side of it.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `index > len`.
+        pub fn move_gap_to(&mut self, index: usize) {
+            assert!(index <= self.len(), "gap index {} out of bounds", index);
+            let Range { start, end } = self.gap;
+            let gap_len: usize = end - start;
+            if index < start {
+                // `buf[index..start]` moves right, to end at `end`
+                for i in (index..start).rev() {
+                    self.buf.swap(i, i + gap_len);
+                }
+            } else {
+                // `buf[end..index + gap_len]` moves left, to start at `start`
+                for i in 0..index - start {
+                    self.buf.swap(start + i, end + i);
+                }
+            }
+            self.gap = index..index + gap_len;
+        }
+
+        /// Doubles the capacity, the new slots become the gap at `index`.
+        fn grow(&mut self, index: usize) {
+            let extra: usize = self.buf.len().max(4);
+            self.gap = index..index + extra;
+            self.buf
+                .splice(index..index, std::iter::repeat_with(|| None).take(extra));
+        }
+
+        /// # Panics
+        ///
+        /// Panics if `index > len`, like `Vec::insert`.
+        pub fn insert(&mut self, index: usize, value: T) {
+            assert!(
+                index <= self.len(),
+                "insertion index {} out of bounds",
+                index
+            );
+            if self.gap.is_empty() {
+                self.grow(index);
+            } else {
+                self.move_gap_to(index);
+            }
+            self.buf[self.gap.start] = Some(value);
+            self.gap.start += 1;
+        }
+
+        /// Removes the element at `index`, the gap then takes its place. `None` if out of bounds.
+        pub fn remove(&mut self, index: usize) -> Option<T> {
+            if index >= self.len() {
+                return None;
+            }
+            self.move_gap_to(index);
+            let value: Option<T> = self.buf[self.gap.end].take();
+            self.gap.end += 1;
+            value
+        }
+
+        /// The elements in logical order, the gap's `None`s are skipped.
+        pub fn iter(&self) -> impl Iterator<Item = &T> {
+            self.buf.iter().flatten()
+        }
+    }
+
+    impl<T> Default for GapBuffer<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    /// Inserts `n` values at the middle of `n` initial elements, always at the same index, with
+    /// `(gap_buffer, vec)` timings. Only meaningful in a release build.
+    pub fn bench_sequential_inserts(n: usize) -> (Duration, Duration) {
+        let middle: usize = n / 2;
+
+        let start: Instant = Instant::now();
+        let mut gap: GapBuffer<usize> = GapBuffer::from_vec((0..n).collect());
+        for i in 0..n {
+            gap.insert(middle, std::hint::black_box(i));
+        }
+        let gap_time: Duration = start.elapsed();
+
+        let start: Instant = Instant::now();
+        let mut vec: Vec<usize> = (0..n).collect();
+        for i in 0..n {
+            vec.insert(middle, std::hint::black_box(i));
+        }
+        let vec_time: Duration = start.elapsed();
+
+        assert_eq!(gap.into_vec(), vec);
+        (gap_time, vec_time)
+    }
+}
+
+pub mod deque {
+    //! `VecDeque<T>` is a ring buffer: a `Vec`-like allocation whose contents may wrap around
+    //! from the end back to the start, with a head index telling where the first element is.
+    //!
+    //! ```text
+    //! buf   [d e _ _ a b c]      head = 4
+    //! deque [a b c d e]
+    //! ```
+    //!
+    //! So pushing and popping at both ends is O(1), where `Vec::insert(0, _)` and
+    //! `Vec::remove(0)` shift every element. The price is that the elements are not always
+    //! contiguous: `as_slices` returns the two halves, and `make_contiguous` rotates them into
+    //! one slice.
+
+    use std::collections::VecDeque;
+
+    /// The last `capacity` values pushed, oldest first.
+    #[derive(Debug)]
+    pub struct RecentN<T> {
+        values: VecDeque<T>,
+        capacity: usize,
+    }
+
+    pub fn recent_n<T>(capacity: usize) -> RecentN<T> {
+        RecentN {
+            values: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    impl<T> RecentN<T> {
+        /// When full, the oldest value is evicted from the front and returned.
+        pub fn push(&mut self, value: T) -> Option<T> {
+            if self.capacity == 0 {
+                return Some(value);
+            }
+            let evicted: Option<T> = if self.values.len() == self.capacity {
+                self.values.pop_front()
+            } else {
+                None
+            };
+            self.values.push_back(value);
+            evicted
+        }
+
+        pub fn len(&self) -> usize {
+            self.values.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.values.is_empty()
+        }
+
+        pub fn iter(&self) -> impl Iterator<Item = &T> {
+            self.values.iter()
+        }
+
+        pub fn to_vec(&self) -> Vec<T>
+        where
+            T: Clone,
+        {
+            self.values.iter().cloned().collect()
+        }
+    }
+
+    /// Rotates right by `k`, the last `k` elements move to the front, and left by `-k` when `k`
+    /// is negative. `k` is taken modulo the length, so rotating by the length is a no-op.
+    pub fn rotate_deque<T>(d: &mut VecDeque<T>, k: isize) {
+        if d.is_empty() {
+            return;
+        }
+        // `rem_euclid` is never negative: a left rotation by 1 is a right rotation by len - 1
+        let k: usize = k.rem_euclid(d.len() as isize) as usize;
+        d.rotate_right(k);
+    }
+
+    /// Compares the elements pairwise from both ends until the two iterators meet.
+    pub fn is_palindrome<T: PartialEq>(d: &VecDeque<T>) -> bool {
+        let mut iter = d.iter();
+        while let (Some(front), Some(back)) = (iter.next(), iter.next_back()) {
+            if front != back {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// The minimum of each window of `window` consecutive values, in O(n).
+    ///
+    /// The deque holds indices of values that may still become a minimum, their values
+    /// increasing from front to back. A new value evicts from the back every value greater or
+    /// equal, which can't be a minimum anymore while it is in the window, and the front is
+    /// evicted once it slides out of the window. The front is then the minimum.
+    pub fn sliding_minimum(values: &[i32], window: usize) -> Vec<i32> {
+        assert!(window > 0, "window must not be empty");
+        let mut candidates: VecDeque<usize> = VecDeque::new();
+        let mut minimums: Vec<i32> = vec![];
+        for (i, &value) in values.iter().enumerate() {
+            while candidates.back().is_some_and(|&j| values[j] >= value) {
+                candidates.pop_back();
+            }
+            candidates.push_back(i);
+            if candidates[0] + window <= i {
+                candidates.pop_front();
+            }
+            if i + 1 >= window {
+                minimums.push(values[candidates[0]]);
+            }
+        }
+        minimums
+    }
+}
+
+pub mod sort_order {
+    //! `std::cmp::Reverse<T>` is a wrapper whose `Ord` is the opposite of `T`'s, so sorting by
+    //! `Reverse(key)` sorts by `key` descending. Unlike negating the key it works for any `Ord`
+    //! type, strings included, and can't overflow on `i32::MIN`; unlike `sort` then `reverse` it
+    //! keeps the sort stable and can be combined with other keys in a tuple, each in its own
+    //! direction.
+
+    use std::cmp::Reverse;
+
+    #[allow(clippy::ptr_arg)]
+    pub fn sort_descending(v: &mut Vec<i32>) {
+        v.sort_by_key(|&x| Reverse(x));
+    }
+
+    /// Highest score first, and players with the same score by name.
+    pub fn rank(scores: &mut [(String, i32)]) {
+        scores.sort_by(|(a_name, a_score), (b_name, b_score)| {
+            (Reverse(a_score), a_name).cmp(&(Reverse(b_score), b_name))
+        });
+    }
+}
+
+pub mod group_consecutive {
+    //! Run-length encoding: each run of equal consecutive elements becomes one `(value, count)`
+    //! pair. There is no std adapter for it (`dedup` keeps the values but drops the counts), so
+    //! it is built by hand, either way works:
+    //!
+    //! * a fold whose accumulator is the output: extend the last run if the element equals its
+    //!   value, otherwise push a new run. This is the version below.
+    //! * a `peekable` iterator: take an element, then `next_if_eq` as long as the next one is the
+    //!   same, counting them.
+    //!
+    //! ```
+    //! let v: [i32; 6] = [1, 1, 2, 3, 3, 3];
+    //! let mut iter = v.iter().peekable();
+    //! let mut runs: Vec<(i32, usize)> = vec![];
+    //! while let Some(value) = iter.next() {
+    //!     let mut count: usize = 1;
+    //!     while iter.next_if_eq(&value).is_some() {
+    //!         count += 1;
+    //!     }
+    //!     runs.push((*value, count));
+    //! }
+    //! assert_eq!(runs, vec![(1, 2), (2, 1), (3, 3)]);
+    //! ```
+
+    /// `[1, 1, 2, 3, 3, 3]` becomes `[(1, 2), (2, 1), (3, 3)]`.
+    pub fn group_consecutive(v: &[i32]) -> Vec<(i32, usize)> {
+        v.iter()
+            .fold(vec![], |mut runs: Vec<(i32, usize)>, &value| {
+                match runs.last_mut() {
+                    Some((last, count)) if *last == value => *count += 1,
+                    _ => runs.push((value, 1)),
+                }
+                runs
+            })
+    }
+}
+
+pub mod iter_sources {
+    //! Functions in `std::iter` that create an iterator out of nothing but a value or a closure,
+    //! to `collect` into a vector or to `chain` with another iterator:
+    //!
+    //! * `repeat(value)` yields clones of `value` forever, so it needs a `take(n)`. `repeat_n`
+    //!   does both in one call.
+    //! * `once(value)` yields `value` a single time, an iterator of length one without building
+    //!   a vector for it.
+    //! * `repeat_with(f)` calls `f` for every item. Unlike `repeat(f())`, which computes one value
+    //!   and clones it, each item is computed fresh, and only when it is asked for.
+    //!
+    //! `empty()` is the iterator yielding nothing, and `from_fn` and `successors` build one from
+    //! a closure that keeps its own state.
+
+    use std::cell::Cell;
+    use std::iter;
+
+    // the spelled out form on purpose, to show `take` ending an infinite iterator
+    #[allow(clippy::manual_repeat_n)]
+    pub fn repeated() -> Vec<i32> {
+        iter::repeat(7).take(3).collect()
+    }
+
+    pub fn single() -> Vec<i32> {
+        iter::once(5).collect()
+    }
+
+    /// Stands for a costly computation, counting its calls.
+    pub fn expensive(calls: &Cell<u32>) -> String {
+        calls.set(calls.get() + 1);
+        format!("computed #{}", calls.get())
+    }
+
+    pub fn computed_each_time(calls: &Cell<u32>) -> Vec<String> {
+        iter::repeat_with(|| expensive(calls)).take(2).collect()
+    }
+
+    pub fn computed_once(calls: &Cell<u32>) -> Vec<String> {
+        iter::repeat_n(expensive(calls), 2).collect()
+    }
+}
+
+pub mod chain {
+    //! `a.chain(b)` is an iterator that yields everything from `a`, then everything from `b`.
+    //! It is lazy: nothing is copied when the chain is built, and each item is pulled from
+    //! whichever iterator is current only when asked for. So concatenating to loop over both,
+    //! or to feed another adapter, needs no intermediate vector. `chain` takes any
+    //! `IntoIterator` as its argument, which is why a `Vec` can be passed directly.
+
+    /// The one allocation is the output, `collect` sizes it from the chain's `size_hint`.
+    pub fn chain_vectors(a: Vec<i32>, b: Vec<i32>) -> Vec<i32> {
+        a.into_iter().chain(b).collect()
+    }
+}
+
+pub mod cycle {
+    //! `cycle` turns an iterator into one that starts over from the beginning each time it runs
+    //! out, so it never ends. That is safe as long as something else bounds it: `take(n)` stops
+    //! after `n` items, and the cycle is only advanced as far as they need. The iterator must be
+    //! `Clone`, `cycle` keeps a copy of the original to restart from. Cycling an empty iterator
+    //! yields nothing instead of looping forever.
+
+    pub fn repeat_pattern(pattern: &[i32], n: usize) -> Vec<i32> {
+        pattern.iter().cloned().cycle().take(n).collect()
+    }
+}
+
+pub mod history {
+    //! Undo and redo with two stacks of edits. Every edit is recorded as its inverse, the edit
+    //! that takes the document back to before it, on the undo stack:
+    //!
+    //! ```text
+    //! InsertLine { at, text }          <->  RemoveLine { at, text }
+    //! ReplaceLine { at, old, new }     <->  ReplaceLine { at, old: new, new: old }
+    //! ```
+    //!
+    //! `undo` pops an inverse, applies it, and pushes the inverse of that, which is the original
+    //! edit, on the redo stack; `redo` does the same the other way round. So an edit moves
+    //! between the stacks, flipped each time, and whatever sequence of undos and redos is run,
+    //! the top of each stack undoes or redoes exactly the last step in its direction. A new edit
+    //! clears the redo stack: the edits there were made on a document that no longer exists.
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Edit {
+        InsertLine { at: usize, text: String },
+        RemoveLine { at: usize, text: String },
+        ReplaceLine { at: usize, old: String, new: String },
+    }
+
+    impl Edit {
+        pub fn inverse(&self) -> Edit {
+            match self.clone() {
+                Edit::InsertLine { at, text } => Edit::RemoveLine { at, text },
+                Edit::RemoveLine { at, text } => Edit::InsertLine { at, text },
+                Edit::ReplaceLine { at, old, new } => Edit::ReplaceLine {
+                    at,
+                    old: new,
+                    new: old,
+                },
+            }
+        }
+
+        fn apply(&self, lines: &mut Vec<String>) {
+            match self {
+                Edit::InsertLine { at, text } => lines.insert(*at, text.clone()),
+                Edit::RemoveLine { at, text } => {
+                    let removed: String = lines.remove(*at);
+                    debug_assert_eq!(&removed, text);
+                }
+                Edit::ReplaceLine { at, new, .. } => lines[*at] = new.clone(),
+            }
+        }
+    }
+
+    #[derive(Debug, Default)]
+    pub struct Document {
+        lines: Vec<String>,
+        undo_stack: Vec<Edit>,
+        redo_stack: Vec<Edit>,
+    }
+
+    impl Document {
+        pub fn new() -> Self {
+            Document::default()
+        }
+
+        /// Applies a new edit, recording its inverse.
+        fn edit(&mut self, edit: Edit) {
+            edit.apply(&mut self.lines);
+            self.undo_stack.push(edit.inverse());
+            self.redo_stack.clear();
+        }
+
+        /// Inserts before line `at`, or at the end when `at` is the number of lines. False, and
+        /// nothing recorded, past that.
+        pub fn insert_line(&mut self, at: usize, text: &str) -> bool {
+            if at > self.lines.len() {
+                return false;
+            }
+            self.edit(Edit::InsertLine {
+                at,
+                text: text.to_string(),
+            });
+            true
+        }
+
+        pub fn remove_line(&mut self, at: usize) -> Option<String> {
+            let text: String = self.lines.get(at)?.clone();
+            self.edit(Edit::RemoveLine {
+                at,
+                text: text.clone(),
+            });
+            Some(text)
+        }
+
+        /// Returns the replaced line.
+        pub fn replace_line(&mut self, at: usize, text: &str) -> Option<String> {
+            let old: String = self.lines.get(at)?.clone();
+            self.edit(Edit::ReplaceLine {
+                at,
+                old: old.clone(),
+                new: text.to_string(),
+            });
+            Some(old)
+        }
+
+        /// False when there is nothing to undo.
+        pub fn undo(&mut self) -> bool {
+            match self.undo_stack.pop() {
+                Some(inverse) => {
+                    inverse.apply(&mut self.lines);
+                    self.redo_stack.push(inverse.inverse());
+                    true
+                }
+                None => false,
+            }
+        }
+
+        /// False when there is nothing to redo.
+        pub fn redo(&mut self) -> bool {
+            match self.redo_stack.pop() {
+                Some(edit) => {
+                    edit.apply(&mut self.lines);
+                    self.undo_stack.push(edit.inverse());
+                    true
+                }
+                None => false,
+            }
+        }
+
+        pub fn lines(&self) -> &[String] {
+            &self.lines
+        }
+
+        /// The lines joined with `\n`.
+        pub fn text(&self) -> String {
+            self.lines.join("\n")
+        }
+
+        /// The number of edits that can be undone and redone.
+        pub fn history_depth(&self) -> (usize, usize) {
+            (self.undo_stack.len(), self.redo_stack.len())
+        }
+    }
+}
+
+pub mod try_fold {
+    //! `try_fold` is `fold` with a closure that returns a `Result` (or an `Option`, anything that
+    //! works with `?`). As long as the closure returns `Ok(acc)` it keeps folding with `acc`; the
+    //! first `Err` stops the iteration on the spot and becomes the result, the remaining items
+    //! are never visited. If every step succeeds the result is `Ok` of the final accumulator.
+
+    #[derive(Debug, PartialEq)]
+    pub enum SumError {
+        Negative(i32),
+        /// The sum of the values before the first negative one exceeds `i32::MAX`.
+        Overflow,
+    }
+
+    /// The sum of the values, or `Err` with the first negative one. A sum that doesn't fit in an
+    /// `i32` stops the fold too, instead of panicking in a debug build.
+    pub fn sum_until_negative(v: &[i32]) -> Result<i32, SumError> {
+        v.iter().try_fold(0_i32, |sum, &x| {
+            if x < 0 {
+                return Err(SumError::Negative(x));
+            }
+            sum.checked_add(x).ok_or(SumError::Overflow)
+        })
+    }
+}
+
 #[cfg(test)]
 mod testing {
     #[test]
@@ -275,6 +1395,11 @@ mod testing {
         crate::create_vector::with_marco();
     }
 
+    #[test]
+    fn run_create_vector_range_collect() {
+        crate::create_vector::range_collect();
+    }
+
     #[test]
     fn run_update_vector_push() {
         crate::update_vector::push();
@@ -304,4 +1429,719 @@ mod testing {
     fn run_iter_vector_update() {
         crate::iter_vector::update();
     }
+
+    #[test]
+    fn run_inplace_algorithms_remove_dups_sorted() {
+        use crate::inplace_algorithms::remove_dups_sorted;
+        let mut v: Vec<i32> = vec![1, 1, 2, 3, 3, 3, 7];
+        assert_eq!(remove_dups_sorted(&mut v), 4);
+        assert_eq!(v, vec![1, 2, 3, 7]);
+
+        let mut deduped: Vec<i32> = vec![1, 2, 3];
+        assert_eq!(remove_dups_sorted(&mut deduped), 3);
+        assert_eq!(deduped, vec![1, 2, 3]);
+
+        let mut empty: Vec<i32> = vec![];
+        assert_eq!(remove_dups_sorted(&mut empty), 0);
+
+        let mut v: Vec<i32> = vec![-5, -5, 0, 0, 0, 4, 9, 9];
+        let mut oracle: Vec<i32> = v.clone();
+        oracle.dedup();
+        remove_dups_sorted(&mut v);
+        assert_eq!(v, oracle);
+    }
+
+    #[test]
+    fn run_inplace_algorithms_move_zeros_to_end() {
+        use crate::inplace_algorithms::move_zeros_to_end;
+        let mut v: Vec<i32> = vec![0, 1, 0, 3, 12];
+        move_zeros_to_end(&mut v);
+        assert_eq!(v, vec![1, 3, 12, 0, 0]);
+
+        let mut zeros: Vec<i32> = vec![0, 0, 0];
+        move_zeros_to_end(&mut zeros);
+        assert_eq!(zeros, vec![0, 0, 0]);
+
+        let mut v: Vec<i32> = vec![4, 0, -2, 0, 0, 7, 1, 0];
+        let mut oracle: Vec<i32> = v.iter().copied().filter(|&x| x != 0).collect();
+        oracle.resize(v.len(), 0);
+        move_zeros_to_end(&mut v);
+        assert_eq!(v, oracle);
+    }
+
+    #[test]
+    fn run_inplace_algorithms_reverse_words_bytes() {
+        use crate::inplace_algorithms::reverse_words_bytes;
+        let mut buf: Vec<u8> = b"the sky is blue".to_vec();
+        reverse_words_bytes(&mut buf);
+        assert_eq!(buf, b"blue is sky the");
+
+        let mut buf: Vec<u8> = b"  hello world ".to_vec();
+        reverse_words_bytes(&mut buf);
+        assert_eq!(buf, b" world hello  ");
+
+        // reversing the words of `split(' ')` mirrors the spaces the same way
+        let text: &str = "  leading and trailing   ";
+        let oracle: String = text.split(' ').rev().collect::<Vec<&str>>().join(" ");
+        let mut buf: Vec<u8> = text.as_bytes().to_vec();
+        reverse_words_bytes(&mut buf);
+        assert_eq!(buf, oracle.as_bytes());
+    }
+
+    #[test]
+    fn run_inplace_algorithms_max_sliding_window() {
+        use crate::inplace_algorithms::max_sliding_window;
+        let v: Vec<i32> = vec![1, 3, -1, -3, 5, 3, 6, 7];
+        assert_eq!(max_sliding_window(&v, 3), vec![3, 3, 5, 5, 6, 7]);
+        assert_eq!(max_sliding_window(&v, 1), v);
+        assert_eq!(max_sliding_window(&v, v.len()), vec![7]);
+        assert_eq!(max_sliding_window(&v, 0), vec![]);
+        assert_eq!(max_sliding_window(&v, v.len() + 1), vec![]);
+
+        let v: Vec<i32> = vec![9, 2, 2, 8, -4, 0, 5, 5, 1, 3, 7, -6];
+        for k in 1..=v.len() {
+            let oracle: Vec<i32> = v.windows(k).map(|w| *w.iter().max().unwrap()).collect();
+            assert_eq!(max_sliding_window(&v, k), oracle);
+        }
+    }
+
+    #[test]
+    fn run_vector_capacity_reallocation_count() {
+        use crate::vector_capacity::{reallocation_count, reallocation_count_with_capacity};
+        // the growth is geometric, not specified exactly: 0 -> 4 -> 8 -> ... -> 1024 today
+        for n in [1000, 100_000] {
+            let reallocations: usize = reallocation_count(n);
+            assert!(reallocations > 0);
+            assert!(reallocations <= n.ilog2() as usize + 1);
+        }
+        for n in [0, 1, 1000, 100_000] {
+            assert_eq!(reallocation_count_with_capacity(n), 0);
+        }
+    }
+
+    #[test]
+    fn run_vector_capacity_shrink_demo() {
+        crate::vector_capacity::shrink_demo();
+    }
+
+    #[test]
+    fn run_stable_partition() {
+        use crate::stable_partition::{stable_partition, stable_partition_in_place};
+        let (v, split) = stable_partition(vec![1, 2, 3, 4, 5, 6, 7], |x: &i32| x % 2 == 0);
+        assert_eq!(v, vec![2, 4, 6, 1, 3, 5, 7]);
+        assert_eq!(split, 3);
+
+        let fixtures: Vec<Vec<i32>> = vec![
+            vec![],
+            vec![1],
+            vec![2],
+            vec![2, 4, 6],
+            vec![1, 3, 5],
+            vec![1, 2, 3, 4, 5, 6, 7, 8],
+            vec![8, 7, 6, 5, 4, 3, 2, 1],
+            vec![1, 1, 2, 2, 1, 1, 2, 2, 2, 1],
+            vec![5, 10, 15, 20, 25, 30, 35, 40, 45, 50, 55],
+        ];
+        let predicates: [fn(&i32) -> bool; 4] =
+            [|x| x % 2 == 0, |x| x % 5 == 0, |_| true, |_| false];
+        for fixture in &fixtures {
+            for pred in predicates {
+                let (expected, expected_split) = stable_partition(fixture.clone(), pred);
+                let mut v: Vec<i32> = fixture.clone();
+                let split: usize = stable_partition_in_place(&mut v, pred);
+                assert_eq!(v, expected);
+                assert_eq!(split, expected_split);
+                assert_eq!(split, fixture.iter().filter(|x| pred(x)).count());
+            }
+        }
+    }
+
+    #[test]
+    fn run_stable_partition_keeps_order() {
+        use crate::stable_partition::stable_partition_in_place;
+        // partition by the key only, the labels show both groups keep their order
+        let mut v: Vec<(u8, char)> = vec![(1, 'a'), (0, 'b'), (1, 'c'), (0, 'd'), (1, 'e')];
+        assert_eq!(stable_partition_in_place(&mut v, |x| x.0 == 0), 2);
+        assert_eq!(v, vec![(0, 'b'), (0, 'd'), (1, 'a'), (1, 'c'), (1, 'e')]);
+    }
+
+    #[test]
+    fn run_partition_point_by() {
+        use crate::stable_partition::{partition_point_by, stable_partition};
+        assert_eq!(partition_point_by(&[] as &[i32], |_| true), 0);
+        assert_eq!(partition_point_by(&[1, 2, 3, 10, 20], |&x| x < 5), 3);
+        assert_eq!(partition_point_by(&[1, 2, 3], |_| true), 3);
+        assert_eq!(partition_point_by(&[1, 2, 3], |_| false), 0);
+
+        let data: Vec<i32> = (0..50).map(|x| (x * 37) % 101).collect();
+        let pred = |x: &i32| *x > 40;
+        let (partitioned, split) = stable_partition(data, pred);
+        assert_eq!(partition_point_by(&partitioned, pred), split);
+        assert_eq!(partitioned.partition_point(pred), split);
+    }
+
+    #[test]
+    fn run_arena_alloc_and_get() {
+        use crate::arena::Arena;
+        let mut arena: Arena<String> = Arena::new();
+        assert!(arena.is_empty());
+        let a = arena.alloc(String::from("a"));
+        let b = arena.alloc(String::from("b"));
+        arena.get_mut(a).unwrap().push('!');
+        assert_eq!(arena.get(a).map(String::as_str), Some("a!"));
+        assert_eq!(arena.get(b).map(String::as_str), Some("b"));
+        assert_eq!(arena.len(), 2);
+
+        // an id from a bigger arena is not valid here
+        let mut other: Arena<String> = Arena::new();
+        for _ in 0..3 {
+            other.alloc(String::new());
+        }
+        let foreign = other.alloc(String::new());
+        assert!(arena.get(foreign).is_none());
+    }
+
+    #[test]
+    fn run_graph_arena_empty_and_unknown() {
+        use crate::arena::{GraphArena, UnknownNode};
+        let mut other: GraphArena = GraphArena::new();
+        other.add_node("x");
+        let foreign = other.add_node("y");
+
+        let mut graph: GraphArena = GraphArena::new();
+        assert!(graph.reachable(foreign).is_empty());
+        assert!(graph.neighbors(foreign).is_empty());
+
+        let a = graph.add_node("a");
+        assert_eq!(graph.add_edge(a, foreign), Err(UnknownNode(foreign)));
+        assert_eq!(graph.add_edge(foreign, a), Err(UnknownNode(foreign)));
+        assert!(graph.neighbors(a).is_empty());
+    }
+
+    #[test]
+    fn run_graph_arena_cycles_and_self_edges() {
+        use crate::arena::GraphArena;
+        let mut graph: GraphArena = GraphArena::new();
+        let a = graph.add_node("a");
+        let b = graph.add_node("b");
+        let c = graph.add_node("c");
+        graph.add_edge(a, a).unwrap();
+        assert_eq!(graph.neighbors(a), &[a]);
+        assert_eq!(graph.reachable(a).len(), 1);
+
+        graph.add_edge(a, b).unwrap();
+        graph.add_edge(b, c).unwrap();
+        graph.add_edge(c, a).unwrap();
+        for id in [a, b, c] {
+            assert_eq!(graph.reachable(id).len(), 3);
+        }
+    }
+
+    #[test]
+    fn run_graph_arena_reachable() {
+        use crate::arena::GraphArena;
+        use std::collections::HashSet;
+        // a -> b -> d, a -> c, e -> a, f isolated
+        let mut graph: GraphArena = GraphArena::new();
+        let [a, b, c, d, e, f] = ["a", "b", "c", "d", "e", "f"].map(|label| graph.add_node(label));
+        for (from, to) in [(a, b), (b, d), (a, c), (e, a)] {
+            graph.add_edge(from, to).unwrap();
+        }
+        assert_eq!(graph.label(d), Some("d"));
+        assert_eq!(graph.neighbors(a), &[b, c]);
+
+        let labels = |from| -> HashSet<&str> {
+            graph
+                .reachable(from)
+                .into_iter()
+                .map(|id| graph.label(id).unwrap())
+                .collect()
+        };
+        assert_eq!(labels(a), HashSet::from(["a", "b", "c", "d"]));
+        assert_eq!(labels(b), HashSet::from(["b", "d"]));
+        assert_eq!(labels(e), HashSet::from(["e", "a", "b", "c", "d"]));
+        assert_eq!(labels(f), HashSet::from(["f"]));
+    }
+
+    fn log(message: &'static str) -> impl FnMut(&mut crate::scheduler::Context) {
+        move |ctx| ctx.log.push(format!("{}@{}", message, ctx.now()))
+    }
+
+    #[test]
+    fn run_scheduler_ordering() {
+        use crate::scheduler::{Context, Scheduler};
+        let mut scheduler: Scheduler = Scheduler::new();
+        let mut ctx: Context = Context::new();
+        scheduler.schedule_at(5, log("b"));
+        scheduler.schedule_at(2, log("a"));
+        scheduler.schedule_at(5, log("c"));
+        scheduler.schedule_at(5, log("d"));
+        scheduler.schedule_at(9, log("late"));
+
+        assert_eq!(scheduler.run_until(5, &mut ctx), 4);
+        assert_eq!(ctx.log, vec!["a@2", "b@5", "c@5", "d@5"]);
+        assert_eq!(scheduler.now(), 5);
+
+        assert_eq!(scheduler.run_until(8, &mut ctx), 0);
+        scheduler.schedule_after(0, log("now"));
+        assert_eq!(scheduler.run_until(10, &mut ctx), 2);
+        assert_eq!(ctx.log[4..], ["now@8", "late@9"]);
+    }
+
+    #[test]
+    fn run_scheduler_task_schedules_task() {
+        use crate::scheduler::{Context, Scheduler};
+        let mut scheduler: Scheduler = Scheduler::new();
+        let mut ctx: Context = Context::new();
+        scheduler.schedule_at(1, |ctx: &mut Context| {
+            ctx.log.push(String::from("parent@1"));
+            ctx.schedule_after(2, log("child"));
+            ctx.schedule_after(10, log("too late"));
+        });
+        scheduler.schedule_at(4, log("sibling"));
+
+        assert_eq!(scheduler.run_until(5, &mut ctx), 3);
+        assert_eq!(ctx.log, vec!["parent@1", "child@3", "sibling@4"]);
+        assert_eq!(scheduler.run_until(11, &mut ctx), 1);
+        assert_eq!(ctx.log.last().unwrap(), "too late@11");
+    }
+
+    #[test]
+    fn run_scheduler_cancel() {
+        use crate::scheduler::{Context, Scheduler};
+        let mut scheduler: Scheduler = Scheduler::new();
+        let mut ctx: Context = Context::new();
+        let first = scheduler.schedule_at(1, log("first"));
+        let second = scheduler.schedule_at(2, log("second"));
+
+        assert!(scheduler.cancel(second));
+        assert!(!scheduler.cancel(second));
+        assert_eq!(scheduler.run_until(3, &mut ctx), 1);
+        assert_eq!(ctx.log, vec!["first@1"]);
+
+        // already executed
+        assert!(!scheduler.cancel(first));
+
+        // a stateful task runs once per schedule
+        let mut runs: u32 = 0;
+        scheduler.schedule_after(1, move |ctx: &mut Context| {
+            runs += 1;
+            ctx.log.push(format!("run {}", runs));
+        });
+        assert_eq!(scheduler.run_until(100, &mut ctx), 1);
+        assert_eq!(ctx.log.last().unwrap(), "run 1");
+    }
+
+    #[test]
+    fn run_compile_errors_accepted_versions() {
+        assert_eq!(crate::compile_errors::push_after_last_use(), 1);
+        assert_eq!(crate::compile_errors::iterate_by_reference(), 3);
+        assert_eq!(crate::compile_errors::drop_after_last_use(), 3);
+    }
+
+    #[test]
+    fn run_gap_buffer_against_vec() {
+        use crate::gap_buffer::GapBuffer;
+        let mut gap: GapBuffer<u32> = GapBuffer::new();
+        let mut oracle: Vec<u32> = vec![];
+
+        // a small linear congruential generator, for a reproducible mix of operations
+        let mut state: u32 = 7;
+        let mut next = move |bound: usize| -> usize {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (state >> 16) as usize % bound
+        };
+        for value in 0..500 {
+            if next(3) == 0 {
+                let index: usize = next(oracle.len() + 1);
+                let expected: Option<u32> = (index < oracle.len()).then(|| oracle.remove(index));
+                assert_eq!(gap.remove(index), expected);
+            } else {
+                let index: usize = next(oracle.len() + 1);
+                gap.insert(index, value);
+                oracle.insert(index, value);
+            }
+            assert_eq!(gap.len(), oracle.len());
+            assert!(gap.iter().eq(oracle.iter()));
+        }
+        for (i, value) in oracle.iter().enumerate() {
+            assert_eq!(gap.get(i), Some(value));
+        }
+        assert_eq!(gap.get(oracle.len()), None);
+        assert_eq!(gap.into_vec(), oracle);
+    }
+
+    #[test]
+    fn run_gap_buffer_ends() {
+        use crate::gap_buffer::GapBuffer;
+        let mut gap: GapBuffer<char> = GapBuffer::new();
+        assert!(gap.is_empty());
+        assert_eq!(gap.remove(0), None);
+
+        gap.insert(0, 'b');
+        gap.insert(0, 'a');
+        gap.insert(2, 'c');
+        gap.insert(3, 'd');
+        gap.insert(0, '<');
+        gap.insert(gap.len(), '>');
+        assert_eq!(gap.iter().collect::<String>(), "<abcd>");
+
+        gap.move_gap_to(0);
+        assert_eq!(gap.iter().collect::<String>(), "<abcd>");
+        gap.move_gap_to(gap.len());
+        assert_eq!(gap.get(5), Some(&'>'));
+        assert_eq!(gap.into_vec(), vec!['<', 'a', 'b', 'c', 'd', '>']);
+    }
+
+    #[test]
+    #[should_panic(expected = "insertion index 2 out of bounds")]
+    fn run_gap_buffer_insert_out_of_bounds() {
+        let mut gap = crate::gap_buffer::GapBuffer::from_vec(vec![1]);
+        gap.insert(2, 0);
+    }
+
+    #[test]
+    fn run_gap_buffer_remove_across_gap() {
+        use crate::gap_buffer::GapBuffer;
+        let mut gap: GapBuffer<u8> = GapBuffer::from_vec(vec![0, 1, 2, 3, 4, 5, 6, 7]);
+        gap.move_gap_to(4);
+        gap.insert(4, 40);
+
+        // before the gap, at its start, after it
+        assert_eq!(gap.remove(1), Some(1));
+        assert_eq!(gap.remove(3), Some(40));
+        assert_eq!(gap.remove(5), Some(6));
+        assert_eq!(gap.remove(5), Some(7));
+        assert_eq!(gap.remove(5), None);
+        assert_eq!(gap.into_vec(), vec![0, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn run_gap_buffer_round_trip() {
+        use crate::gap_buffer::{bench_sequential_inserts, GapBuffer};
+        let words: Vec<String> = ["gap", "buffer"].map(String::from).to_vec();
+        assert_eq!(GapBuffer::from_vec(words.clone()).into_vec(), words);
+        assert!(GapBuffer::<u8>::from_vec(vec![]).into_vec().is_empty());
+
+        let mut gap: GapBuffer<String> = GapBuffer::from_vec(words);
+        gap.insert(1, String::from("-"));
+        gap.move_gap_to(0);
+        assert_eq!(gap.into_vec().concat(), "gap-buffer");
+
+        // bench_sequential_inserts asserts both end with the same elements
+        bench_sequential_inserts(100);
+    }
+
+    #[test]
+    fn run_deque_recent_n() {
+        use crate::deque::{recent_n, RecentN};
+        let mut recent: RecentN<u8> = recent_n(3);
+        assert_eq!(recent.push(1), None);
+        assert_eq!(recent.push(2), None);
+        assert_eq!(recent.push(3), None);
+        assert_eq!(recent.to_vec(), vec![1, 2, 3]);
+
+        // the oldest goes first
+        assert_eq!(recent.push(4), Some(1));
+        assert_eq!(recent.push(5), Some(2));
+        assert_eq!(recent.iter().copied().collect::<Vec<u8>>(), vec![3, 4, 5]);
+        assert_eq!(recent.len(), 3);
+
+        let mut none: RecentN<u8> = recent_n(0);
+        assert_eq!(none.push(1), Some(1));
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn run_deque_rotate() {
+        use crate::deque::rotate_deque;
+        use std::collections::VecDeque;
+        let rotated = |k: isize| {
+            let mut d: VecDeque<u8> = VecDeque::from(vec![1, 2, 3, 4, 5]);
+            rotate_deque(&mut d, k);
+            Vec::from(d)
+        };
+        assert_eq!(rotated(0), vec![1, 2, 3, 4, 5]);
+        assert_eq!(rotated(2), vec![4, 5, 1, 2, 3]);
+        assert_eq!(rotated(-2), vec![3, 4, 5, 1, 2]);
+        assert_eq!(rotated(5), vec![1, 2, 3, 4, 5]);
+        assert_eq!(rotated(12), rotated(2));
+        assert_eq!(rotated(-12), rotated(-2));
+        assert_eq!(rotated(-3), rotated(2));
+        assert_eq!(rotated(isize::MIN), rotated(isize::MIN % 5));
+
+        let mut empty: VecDeque<u8> = VecDeque::new();
+        rotate_deque(&mut empty, -3);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn run_deque_is_palindrome() {
+        use crate::deque::is_palindrome;
+        use std::collections::VecDeque;
+        let palindrome = |s: &str| is_palindrome(&s.chars().collect::<VecDeque<char>>());
+        assert!(palindrome(""));
+        assert!(palindrome("a"));
+        assert!(palindrome("abba"));
+        assert!(palindrome("racecar"));
+        assert!(!palindrome("ab"));
+        assert!(!palindrome("abca"));
+        assert!(!palindrome("racecars"));
+
+        // a deque that wraps around its buffer
+        let mut wrapped: VecDeque<u8> = VecDeque::with_capacity(4);
+        wrapped.extend([9, 9, 1, 2]);
+        wrapped.pop_front();
+        wrapped.pop_front();
+        wrapped.push_back(1);
+        assert!(is_palindrome(&wrapped));
+    }
+
+    #[test]
+    fn run_deque_sliding_minimum() {
+        use crate::deque::sliding_minimum;
+        fn brute_force(values: &[i32], window: usize) -> Vec<i32> {
+            values
+                .windows(window)
+                .map(|w| *w.iter().min().unwrap())
+                .collect()
+        }
+
+        assert_eq!(
+            sliding_minimum(&[4, 2, 12, 3, 8, 1, 7], 3),
+            vec![2, 2, 3, 1, 1]
+        );
+        assert!(sliding_minimum(&[1, 2], 3).is_empty());
+
+        let mut seed: u64 = 7;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((seed >> 33) % 21) as i32 - 10
+        };
+        for len in 0..40 {
+            let values: Vec<i32> = (0..len).map(|_| next()).collect();
+            for window in 1..=len.max(1) {
+                assert_eq!(
+                    sliding_minimum(&values, window),
+                    brute_force(&values, window)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn run_sort_order_descending() {
+        use crate::sort_order::sort_descending;
+        let mut v: Vec<i32> = vec![3, -1, i32::MIN, 7, 3, i32::MAX, 0];
+        sort_descending(&mut v);
+        assert_eq!(v, vec![i32::MAX, 7, 3, 3, 0, -1, i32::MIN]);
+        assert!(v.windows(2).all(|w| w[0] >= w[1]));
+    }
+
+    #[test]
+    fn run_sort_order_rank() {
+        use crate::sort_order::rank;
+        let mut scores: Vec<(String, i32)> = [("cid", 7), ("bob", 9), ("eve", 7), ("ann", 7)]
+            .map(|(name, score)| (name.to_string(), score))
+            .to_vec();
+        rank(&mut scores);
+        let names: Vec<&str> = scores.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["bob", "ann", "cid", "eve"]);
+    }
+
+    #[test]
+    fn run_group_consecutive() {
+        use crate::group_consecutive::group_consecutive;
+        assert_eq!(
+            group_consecutive(&[1, 1, 2, 3, 3, 3]),
+            vec![(1, 2), (2, 1), (3, 3)]
+        );
+        assert_eq!(group_consecutive(&[]), vec![]);
+        assert_eq!(group_consecutive(&[7]), vec![(7, 1)]);
+        // only consecutive elements are grouped
+        assert_eq!(
+            group_consecutive(&[1, 2, 1, 1]),
+            vec![(1, 1), (2, 1), (1, 2)]
+        );
+
+        let v: Vec<i32> = vec![0, 0, 0, -4, 5, 5];
+        let decoded: Vec<i32> = group_consecutive(&v)
+            .into_iter()
+            .flat_map(|(value, count)| std::iter::repeat_n(value, count))
+            .collect();
+        assert_eq!(decoded, v);
+    }
+
+    #[test]
+    fn run_iter_sources() {
+        use crate::iter_sources::{computed_each_time, computed_once, repeated, single};
+        use std::cell::Cell;
+        assert_eq!(repeated(), vec![7, 7, 7]);
+        assert_eq!(single(), vec![5]);
+
+        let calls: Cell<u32> = Cell::new(0);
+        assert_eq!(
+            computed_each_time(&calls),
+            vec!["computed #1", "computed #2"]
+        );
+        assert_eq!(calls.get(), 2);
+
+        let calls: Cell<u32> = Cell::new(0);
+        assert_eq!(computed_once(&calls), vec!["computed #1", "computed #1"]);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn run_chain_vectors() {
+        use crate::chain::chain_vectors;
+        assert_eq!(chain_vectors(vec![1, 2], vec![3, 4]), [1, 2, 3, 4]);
+        assert_eq!(chain_vectors(vec![], vec![3]), [3]);
+        assert_eq!(chain_vectors(vec![1], vec![]), [1]);
+        assert!(chain_vectors(vec![], vec![]).is_empty());
+    }
+
+    #[test]
+    fn run_repeat_pattern() {
+        use crate::cycle::repeat_pattern;
+        assert_eq!(repeat_pattern(&[1, 2], 5), [1, 2, 1, 2, 1]);
+        assert_eq!(repeat_pattern(&[1, 2, 3], 2), [1, 2]);
+        assert_eq!(repeat_pattern(&[4], 3), [4, 4, 4]);
+        assert!(repeat_pattern(&[1, 2], 0).is_empty());
+        assert!(repeat_pattern(&[], 5).is_empty());
+    }
+
+    #[test]
+    fn run_history_undo_redo() {
+        use crate::history::Document;
+        let mut doc: Document = Document::new();
+        assert!(!doc.undo());
+        assert!(!doc.redo());
+
+        assert!(doc.insert_line(0, "fn main() {"));
+        assert!(doc.insert_line(1, "}"));
+        assert!(doc.insert_line(1, "    println!();"));
+        assert_eq!(
+            doc.replace_line(1, "    println!(\"hi\");"),
+            Some(String::from("    println!();"))
+        );
+        assert!(doc.insert_line(3, "// end"));
+        assert_eq!(doc.remove_line(0), Some(String::from("fn main() {")));
+        assert_eq!(doc.text(), "    println!(\"hi\");\n}\n// end");
+        assert_eq!(doc.history_depth(), (6, 0));
+
+        // undo everything, back to the empty document
+        while doc.undo() {}
+        assert_eq!(doc.text(), "");
+        assert_eq!(doc.history_depth(), (0, 6));
+        assert!(!doc.undo());
+
+        // and redo it all
+        for _ in 0..3 {
+            assert!(doc.redo());
+        }
+        assert_eq!(doc.text(), "fn main() {\n    println!();\n}");
+        while doc.redo() {}
+        assert_eq!(doc.text(), "    println!(\"hi\");\n}\n// end");
+        assert_eq!(doc.history_depth(), (6, 0));
+    }
+
+    #[test]
+    fn run_history_new_edit_clears_redo() {
+        use crate::history::Document;
+        let mut doc: Document = Document::new();
+        doc.insert_line(0, "a");
+        doc.insert_line(1, "b");
+        doc.insert_line(2, "c");
+        assert!(doc.undo());
+        assert!(doc.undo());
+        assert_eq!(doc.history_depth(), (1, 2));
+
+        doc.replace_line(0, "A");
+        assert_eq!(doc.history_depth(), (2, 0));
+        assert!(!doc.redo());
+        assert_eq!(doc.text(), "A");
+        assert!(doc.undo());
+        assert_eq!(doc.text(), "a");
+
+        // edits out of range are refused and not recorded
+        assert!(!doc.insert_line(5, "x"));
+        assert_eq!(doc.remove_line(1), None);
+        assert_eq!(doc.replace_line(1, "x"), None);
+        assert_eq!(doc.history_depth(), (1, 1));
+    }
+
+    #[test]
+    fn run_history_against_snapshots() {
+        use crate::history::Document;
+        // the oracle keeps a copy of the whole document after every edit and a cursor into them
+        let mut snapshots: Vec<Vec<String>> = vec![vec![]];
+        let mut cursor: usize = 0;
+        let mut doc: Document = Document::new();
+
+        let mut seed: u64 = 3;
+        let mut next = |bound: usize| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize % bound
+        };
+        for step in 0..500 {
+            let len: usize = doc.lines().len();
+            let text: String = format!("line {}", step);
+            let edited: bool = match next(6) {
+                0 | 1 => doc.insert_line(next(len + 1), &text),
+                2 if len > 0 => doc.remove_line(next(len)).is_some(),
+                3 if len > 0 => doc.replace_line(next(len), &text).is_some(),
+                4 => {
+                    if doc.undo() {
+                        cursor -= 1;
+                    } else {
+                        assert_eq!(cursor, 0);
+                    }
+                    false
+                }
+                _ => {
+                    if doc.redo() {
+                        cursor += 1;
+                    } else {
+                        assert_eq!(cursor, snapshots.len() - 1);
+                    }
+                    false
+                }
+            };
+            if edited {
+                snapshots.truncate(cursor + 1);
+                snapshots.push(doc.lines().to_vec());
+                cursor += 1;
+            }
+            assert_eq!(doc.lines(), snapshots[cursor].as_slice());
+            assert_eq!(doc.history_depth(), (cursor, snapshots.len() - 1 - cursor));
+        }
+    }
+
+    #[test]
+    fn run_sum_until_negative() {
+        use crate::try_fold::{sum_until_negative, SumError};
+        assert_eq!(sum_until_negative(&[1, 2, 3, 0]), Ok(6));
+        assert_eq!(sum_until_negative(&[]), Ok(0));
+        assert_eq!(
+            sum_until_negative(&[1, 2, -3, 4, -5]),
+            Err(SumError::Negative(-3))
+        );
+        assert_eq!(sum_until_negative(&[-1]), Err(SumError::Negative(-1)));
+        // the overflow that would come after the negative value is never computed
+        assert_eq!(
+            sum_until_negative(&[-2, i32::MAX, i32::MAX]),
+            Err(SumError::Negative(-2))
+        );
+        // without a negative value it is reported
+        assert_eq!(sum_until_negative(&[i32::MAX, 0]), Ok(i32::MAX));
+        assert_eq!(sum_until_negative(&[i32::MAX, 1]), Err(SumError::Overflow));
+        assert_eq!(
+            sum_until_negative(&[i32::MAX, 1, -1]),
+            Err(SumError::Overflow)
+        );
+    }
 }
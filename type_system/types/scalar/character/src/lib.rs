@@ -23,6 +23,87 @@ pub mod character_attribute {
     }
 }
 
+pub mod blocks {
+    //! Unicode groups its code points into named blocks of consecutive values. Since `char`
+    //! implements `PartialOrd`, an inclusive range of chars `'a'..='z'` is a valid pattern, so a
+    //! `match` over ranges classifies a char without any lookup table.
+
+    use std::collections::HashMap;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum UnicodeBlock {
+        BasicLatin,
+        Latin1Supplement,
+        Hiragana,
+        CJKUnifiedIdeographs,
+        PrivateUse,
+        Emoji,
+        Other,
+    }
+
+    /// Classifies a char into one of a curated set of blocks.
+    ///
+    /// ```text
+    /// U+0000  ..= U+007F    Basic Latin
+    /// U+0080  ..= U+00FF    Latin-1 Supplement
+    /// U+3040  ..= U+309F    Hiragana
+    /// U+4E00  ..= U+9FFF    CJK Unified Ideographs
+    /// U+E000  ..= U+F8FF    Private Use Area
+    /// U+1F300 ..= U+1F5FF   Miscellaneous Symbols and Pictographs  ┐
+    /// U+1F600 ..= U+1F64F   Emoticons                              │ Emoji
+    /// U+1F680 ..= U+1F6FF   Transport and Map Symbols              │
+    /// U+1F900 ..= U+1F9FF   Supplemental Symbols and Pictographs   ┘
+    /// ```
+    pub fn block_of(c: char) -> UnicodeBlock {
+        match c {
+            '\u{0000}'..='\u{007F}' => UnicodeBlock::BasicLatin,
+            '\u{0080}'..='\u{00FF}' => UnicodeBlock::Latin1Supplement,
+            '\u{3040}'..='\u{309F}' => UnicodeBlock::Hiragana,
+            '\u{4E00}'..='\u{9FFF}' => UnicodeBlock::CJKUnifiedIdeographs,
+            '\u{E000}'..='\u{F8FF}' => UnicodeBlock::PrivateUse,
+            '\u{1F300}'..='\u{1F5FF}'
+            | '\u{1F600}'..='\u{1F64F}'
+            | '\u{1F680}'..='\u{1F6FF}'
+            | '\u{1F900}'..='\u{1F9FF}' => UnicodeBlock::Emoji,
+            _ => UnicodeBlock::Other,
+        }
+    }
+
+    /// Counts the chars of `s` per block.
+    pub fn blocks_in(s: &str) -> HashMap<UnicodeBlock, usize> {
+        let mut counts: HashMap<UnicodeBlock, usize> = HashMap::new();
+        for c in s.chars() {
+            *counts.entry(block_of(c)).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Returns true if the alphabetic chars of `s` come from more than one of the listed blocks,
+    /// Basic Latin and Latin-1 Supplement counting as one. Non-alphabetic chars such as digits,
+    /// punctuation or emoji are skipped, and so is every char outside the listed blocks: the `ř`
+    /// of "Dvořák" is in Latin Extended-A and doesn't make it mixed, but Greek next to Cyrillic
+    /// isn't detected either.
+    pub fn is_mixed_script(s: &str) -> bool {
+        let mut blocks =
+            s.chars()
+                .filter(|c| c.is_alphabetic())
+                .filter_map(|c| match block_of(c) {
+                    UnicodeBlock::Latin1Supplement => Some(UnicodeBlock::BasicLatin),
+                    UnicodeBlock::Other => None,
+                    block => Some(block),
+                });
+        match blocks.next() {
+            Some(first) => blocks.any(|block| block != first),
+            None => false,
+        }
+    }
+
+    /// Returns the byte offset and value of the first char that is not ASCII.
+    pub fn first_non_ascii(s: &str) -> Option<(usize, char)> {
+        s.char_indices().find(|(_, c)| !c.is_ascii())
+    }
+}
+
 #[cfg(test)]
 mod testing {
     #[test]
@@ -34,4 +115,73 @@ mod testing {
     fn run_character_attribute_len_utf8() {
         crate::character_attribute::len_utf8();
     }
+
+    #[test]
+    fn run_blocks_block_of_boundaries() {
+        use crate::blocks::{block_of, UnicodeBlock::*};
+        let boundaries = [
+            ('\u{0000}', '\u{007F}', BasicLatin),
+            ('\u{0080}', '\u{00FF}', Latin1Supplement),
+            ('\u{3040}', '\u{309F}', Hiragana),
+            ('\u{4E00}', '\u{9FFF}', CJKUnifiedIdeographs),
+            ('\u{E000}', '\u{F8FF}', PrivateUse),
+            ('\u{1F300}', '\u{1F5FF}', Emoji),
+            ('\u{1F600}', '\u{1F64F}', Emoji),
+            ('\u{1F680}', '\u{1F6FF}', Emoji),
+            ('\u{1F900}', '\u{1F9FF}', Emoji),
+        ];
+        for (start, end, block) in boundaries {
+            assert_eq!(block_of(start), block);
+            assert_eq!(block_of(end), block);
+        }
+
+        // just outside the ranges
+        assert_eq!(block_of('\u{0100}'), Other);
+        assert_eq!(block_of('\u{303F}'), Other);
+        assert_eq!(block_of('\u{30A0}'), Other);
+        assert_eq!(block_of('\u{4DFF}'), Other);
+        assert_eq!(block_of('\u{A000}'), Other);
+        assert_eq!(block_of('\u{F900}'), Other);
+        assert_eq!(block_of('\u{1F650}'), Other);
+        assert_eq!(block_of('\u{1F8FF}'), Other);
+        assert_eq!(block_of('\u{1FA00}'), Other);
+    }
+
+    #[test]
+    fn run_blocks_blocks_in() {
+        use crate::blocks::{blocks_in, is_mixed_script, UnicodeBlock};
+        use std::collections::HashMap;
+
+        let mixed: &str = "rust 是一门语言";
+        assert_eq!(
+            blocks_in(mixed),
+            HashMap::from([
+                (UnicodeBlock::BasicLatin, 5),
+                (UnicodeBlock::CJKUnifiedIdeographs, 5)
+            ])
+        );
+        assert!(is_mixed_script(mixed));
+        assert!(!is_mixed_script("café au lait"));
+        assert!(!is_mixed_script("中文"));
+
+        // Latin Extended-A is outside the listed blocks, it is not another script
+        assert!(!is_mixed_script("Dvořák"));
+        assert!(!is_mixed_script("Māori"));
+        assert!(is_mixed_script("Dvořák 是"));
+        // Greek and Cyrillic are both outside too, mixing them goes unnoticed
+        assert!(!is_mixed_script("αβ ДЖ"));
+
+        let emoji: &str = "🔥😀🚀🦀";
+        assert_eq!(blocks_in(emoji), HashMap::from([(UnicodeBlock::Emoji, 4)]));
+        assert!(!is_mixed_script(emoji));
+    }
+
+    #[test]
+    fn run_blocks_first_non_ascii() {
+        use crate::blocks::first_non_ascii;
+        assert_eq!(first_non_ascii("hello, wörld"), Some((8, 'ö')));
+        assert_eq!(first_non_ascii("ab中"), Some((2, '中')));
+        assert_eq!(first_non_ascii("plain ascii"), None);
+        assert_eq!(first_non_ascii(""), None);
+    }
 }
@@ -7,3 +7,226 @@
 //! The default type is `f64` because on modern CPUs it’s roughly the same speed as `f32` but is
 //! capable of more precision. All floating-point types are signed. The `f32` type is a
 //! single-precision float, and `f64` has double precision.
+
+pub mod stats {
+    //! Summary statistics over a slice of `f64`.
+    //!
+    //! `f64` is only `PartialOrd`: `NaN` is not comparable to anything, so a slice containing it
+    //! has no meaningful order, min, max or median. Every function here validates the input first
+    //! and rejects non-finite values, which lets the sorting code use `total_cmp` safely.
+
+    use std::ops::Range;
+
+    #[derive(Debug, PartialEq)]
+    pub enum StatsError {
+        Empty,
+        /// The value at `index` is `NaN` or infinite.
+        NonFinite {
+            index: usize,
+        },
+        ZeroBins,
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct Summary {
+        pub count: usize,
+        pub mean: f64,
+        pub median: f64,
+        /// Divides by `n`, the spread of the data itself.
+        pub population_std_dev: f64,
+        /// Divides by `n - 1`, an estimate for the population the data was sampled from. Undefined
+        /// for a single value.
+        pub sample_std_dev: Option<f64>,
+        pub min: f64,
+        pub max: f64,
+        pub q1: f64,
+        pub q3: f64,
+    }
+
+    fn validate(data: &[f64]) -> Result<(), StatsError> {
+        if data.is_empty() {
+            return Err(StatsError::Empty);
+        }
+        match data.iter().position(|x| !x.is_finite()) {
+            Some(index) => Err(StatsError::NonFinite { index }),
+            None => Ok(()),
+        }
+    }
+
+    fn mean(data: &[f64]) -> f64 {
+        data.iter().sum::<f64>() / data.len() as f64
+    }
+
+    fn population_std_dev(data: &[f64]) -> f64 {
+        let mean: f64 = mean(data);
+        let squares: f64 = data.iter().map(|x| (x - mean).powi(2)).sum();
+        (squares / data.len() as f64).sqrt()
+    }
+
+    /// The `p`-quantile of sorted data, interpolating linearly between the two closest ranks.
+    fn quantile(sorted: &[f64], p: f64) -> f64 {
+        let position: f64 = p * (sorted.len() - 1) as f64;
+        let lower: usize = position.floor() as usize;
+        let upper: usize = position.ceil() as usize;
+        sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+    }
+
+    pub fn summarize(data: &[f64]) -> Result<Summary, StatsError> {
+        validate(data)?;
+        let mut sorted: Vec<f64> = data.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        let count: usize = data.len();
+        let population_std_dev: f64 = population_std_dev(data);
+        Ok(Summary {
+            count,
+            mean: mean(data),
+            median: quantile(&sorted, 0.5),
+            population_std_dev,
+            // the same sum of squares divided by `n - 1` instead of `n`
+            sample_std_dev: (count > 1)
+                .then(|| population_std_dev * (count as f64 / (count - 1) as f64).sqrt()),
+            min: sorted[0],
+            max: sorted[count - 1],
+            q1: quantile(&sorted, 0.25),
+            q3: quantile(&sorted, 0.75),
+        })
+    }
+
+    /// Splits `[min, max]` into `bins` ranges of equal width and counts the values in each. Ranges
+    /// are half-open except the last one, which also holds the max. When every value is equal the
+    /// ranges are empty and all values are counted in the last bin.
+    pub fn histogram_bins(
+        data: &[f64],
+        bins: usize,
+    ) -> Result<Vec<(Range<f64>, usize)>, StatsError> {
+        validate(data)?;
+        if bins == 0 {
+            return Err(StatsError::ZeroBins);
+        }
+        let min: f64 = data.iter().copied().fold(f64::INFINITY, f64::min);
+        let max: f64 = data.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let width: f64 = (max - min) / bins as f64;
+
+        let mut histogram: Vec<(Range<f64>, usize)> = (0..bins)
+            .map(|i| {
+                let start: f64 = min + width * i as f64;
+                let end: f64 = if i + 1 == bins { max } else { start + width };
+                (start..end, 0)
+            })
+            .collect();
+        for &x in data {
+            let index: usize = if width == 0.0 {
+                bins - 1
+            } else {
+                (((x - min) / width) as usize).min(bins - 1)
+            };
+            histogram[index].1 += 1;
+        }
+        Ok(histogram)
+    }
+
+    /// Indices of the values more than `threshold` population standard deviations away from the
+    /// mean. Nothing is an outlier when all values are equal.
+    pub fn zscore_outliers(data: &[f64], threshold: f64) -> Vec<usize> {
+        if data.is_empty() {
+            return vec![];
+        }
+        let mean: f64 = mean(data);
+        let std_dev: f64 = population_std_dev(data);
+        if std_dev == 0.0 {
+            return vec![];
+        }
+        data.iter()
+            .enumerate()
+            .filter(|(_, x)| ((*x - mean) / std_dev).abs() > threshold)
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use crate::stats::{histogram_bins, summarize, zscore_outliers, StatsError, Summary};
+
+    const EPSILON: f64 = 1e-9;
+
+    #[test]
+    fn run_stats_summarize() {
+        let data: [f64; 8] = [5.0, 2.0, 9.0, 4.0, 4.0, 7.0, 4.0, 5.0];
+        let summary: Summary = summarize(&data).unwrap();
+        assert_eq!(summary.count, 8);
+        assert!((summary.mean - 5.0).abs() < EPSILON);
+        assert!((summary.median - 4.5).abs() < EPSILON);
+        assert!((summary.population_std_dev - 2.0).abs() < EPSILON);
+        assert!((summary.sample_std_dev.unwrap() - (32.0f64 / 7.0).sqrt()).abs() < EPSILON);
+        assert_eq!(summary.min, 2.0);
+        assert_eq!(summary.max, 9.0);
+        assert!((summary.q1 - 4.0).abs() < EPSILON);
+        assert!((summary.q3 - 5.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn run_stats_single_element() {
+        assert_eq!(
+            summarize(&[3.5]),
+            Ok(Summary {
+                count: 1,
+                mean: 3.5,
+                median: 3.5,
+                population_std_dev: 0.0,
+                sample_std_dev: None,
+                min: 3.5,
+                max: 3.5,
+                q1: 3.5,
+                q3: 3.5,
+            })
+        );
+    }
+
+    #[test]
+    fn run_stats_all_equal() {
+        let data: [f64; 4] = [1.5; 4];
+        let summary: Summary = summarize(&data).unwrap();
+        assert_eq!(summary.population_std_dev, 0.0);
+        assert_eq!(summary.sample_std_dev, Some(0.0));
+        assert_eq!(
+            histogram_bins(&data, 3),
+            Ok(vec![(1.5..1.5, 0), (1.5..1.5, 0), (1.5..1.5, 4)])
+        );
+        assert!(zscore_outliers(&data, 1.0).is_empty());
+    }
+
+    #[test]
+    fn run_stats_histogram_bins() {
+        let data: [f64; 8] = [5.0, 2.0, 9.0, 4.0, 4.0, 7.0, 4.0, 5.0];
+        let histogram = histogram_bins(&data, 7).unwrap();
+        let counts: Vec<usize> = histogram.iter().map(|(_, count)| *count).collect();
+        assert_eq!(counts, vec![1, 0, 3, 2, 0, 1, 1]);
+        assert_eq!(histogram[0].0, 2.0..3.0);
+        assert_eq!(histogram[6].0, 8.0..9.0);
+    }
+
+    #[test]
+    fn run_stats_errors() {
+        assert_eq!(summarize(&[]), Err(StatsError::Empty));
+        assert_eq!(
+            summarize(&[1.0, 2.0, f64::NAN, f64::INFINITY]),
+            Err(StatsError::NonFinite { index: 2 })
+        );
+        assert_eq!(histogram_bins(&[1.0, 2.0], 0), Err(StatsError::ZeroBins));
+        assert_eq!(
+            histogram_bins(&[f64::NEG_INFINITY], 2),
+            Err(StatsError::NonFinite { index: 0 })
+        );
+    }
+
+    #[test]
+    fn run_stats_zscore_outliers() {
+        let data: [f64; 8] = [5.0, 2.0, 9.0, 4.0, 4.0, 7.0, 4.0, 5.0];
+        // 9 is 2 standard deviations away, 2 exactly 1.5
+        assert_eq!(zscore_outliers(&data, 1.5), vec![2]);
+        assert_eq!(zscore_outliers(&data, 1.4), vec![1, 2]);
+        assert!(zscore_outliers(&[], 1.0).is_empty());
+    }
+}
@@ -29,6 +29,129 @@
 //! When compiling in debug mode, `rust` checks for integer overflow that cause panics. When
 //! compiling in release mode, `rust` doesn't check for integer overflow that cause panics.
 
+/// `usize` is as wide as a pointer, so it can hold any address and index any object: 4 bytes
+/// on 32-bit targets, 8 bytes on 64-bit ones. `isize` has the same width, signed, which is what
+/// pointer offsets use. Code that relies on a particular width should use a fixed-size type like
+/// `u64` instead.
+pub fn pointer_width() {
+    assert_eq!(
+        std::mem::size_of::<usize>(),
+        std::mem::size_of::<*const u8>()
+    );
+    assert_eq!(std::mem::size_of::<isize>(), std::mem::size_of::<usize>());
+    assert_eq!(usize::BITS as usize, std::mem::size_of::<usize>() * 8);
+    println!("usize::BITS = {}", usize::BITS); // 64 on a 64-bit target
+}
+
+pub mod radix_format {
+    //! The literal forms above only change how a number is written in source code, the value is
+    //! the same. At runtime, the format specifier picks a formatting trait from `std::fmt`:
+    //!
+    //! ```text
+    //! Specifier   Trait       Output for 255
+    //! {}          Display     255
+    //! {:b}        Binary      11111111
+    //! {:o}        Octal       377
+    //! {:x}        LowerHex    ff
+    //! {:X}        UpperHex    FF
+    //! ```
+    //!
+    //! All integer types implement these traits. `#` adds the same prefix as the literal form
+    //! (`0b`, `0o`, `0x`), and it counts toward the width, so `{:#010b}` pads with zeros after the
+    //! prefix up to 10 chars in total. Signed values are printed as their two's complement bits.
+
+    pub fn radix_specifiers() {
+        assert_eq!(format!("{:b}", 10), "1010");
+        assert_eq!(format!("{:o}", 64), "100");
+        assert_eq!(format!("{:x}", 255), "ff");
+        assert_eq!(format!("{:X}", 255), "FF");
+        assert_eq!(format!("{:x}", -1i8), "ff");
+    }
+
+    pub fn alternate_forms() {
+        assert_eq!(format!("{:#b}", 10), "0b1010");
+        assert_eq!(format!("{:#o}", 64), "0o100");
+        assert_eq!(format!("{:#x}", 255), "0xff");
+        assert_eq!(format!("{:#X}", 255), "0xFF");
+        assert_eq!(format!("{:#010b}", 5), "0b00000101");
+        // the literal forms round trip through the alternate forms
+        assert_eq!(format!("{:#x}", 0xff), "0xff");
+        assert_eq!(format!("{:#b}", 0b1111_0000), "0b11110000");
+    }
+}
+
+pub mod integer_methods {
+    //! Every integer type has associated constants for its bounds, `i32::MAX` and `i32::MIN`, and
+    //! inherent methods for the common arithmetic: `abs`, `signum` (-1, 0 or 1) and `pow`.
+    //!
+    //! The range of a signed type is asymmetric, `i32::MIN` is -2147483648 but `i32::MAX` is only
+    //! 2147483647, so `i32::MIN.abs()` overflows: it panics in debug and returns `i32::MIN` in
+    //! release. `unsigned_abs` returns the magnitude as the unsigned type of the same size, which
+    //! always fits, and `checked_abs` reports the overflow as `None`.
+
+    pub fn bounds() {
+        assert_eq!(i32::MAX.to_string(), "2147483647");
+        assert_eq!(i32::MIN.to_string(), "-2147483648");
+        assert_eq!(i32::MAX as i64, (1_i64 << 31) - 1);
+        assert_eq!(i32::MIN as i64, -(1_i64 << 31));
+        assert_eq!(u8::MAX.to_string(), "255");
+    }
+
+    pub fn arithmetic() {
+        assert_eq!((-5_i32).abs(), 5);
+        assert_eq!((-3_i32).signum(), -1);
+        assert_eq!(0_i32.signum(), 0);
+        assert_eq!(7_i32.signum(), 1);
+        assert_eq!(2_i32.pow(10), 1024);
+    }
+
+    pub fn abs_of_min() {
+        assert_eq!(i32::MIN.checked_abs(), None);
+        assert_eq!(i32::MIN.wrapping_abs(), i32::MIN);
+        assert_eq!(i32::MIN.unsigned_abs(), 2_147_483_648_u32);
+        assert_eq!((-5_i32).unsigned_abs(), 5_u32);
+    }
+}
+
+pub mod bit_methods {
+    //! Integers expose their bit pattern directly, each method compiles to a single instruction on
+    //! most targets (`popcnt`, `lzcnt`, `rol`, `bswap`...), which makes them the building blocks of
+    //! bitsets, hashing, compression and binary protocols.
+    //!
+    //! - `count_ones` / `count_zeros`: how many bits are set / clear.
+    //! - `leading_zeros` / `trailing_zeros`: zero bits before the highest / after the lowest set
+    //!   bit, so `u8::BITS - x.leading_zeros()` is the bit length of `x`.
+    //! - `rotate_left` / `rotate_right`: shift, and wrap the bits falling off back on the other
+    //!   side, unlike `<<` and `>>` which drop them.
+    //! - `swap_bytes`: reverses the byte order, a no-op on a single byte. `to_be` / `from_be` call
+    //!   it only on little-endian targets.
+    //! - `reverse_bits`: reverses the bit order.
+
+    pub fn counting() {
+        assert_eq!(0b1011u8.count_ones(), 3);
+        assert_eq!(0b1011u8.count_zeros(), 5);
+        assert_eq!(1u8.leading_zeros(), 7);
+        assert_eq!(0u8.leading_zeros(), 8);
+        assert_eq!(0b0110_1000u8.trailing_zeros(), 3);
+        assert_eq!(u8::BITS - 0b0001_0110u8.leading_zeros(), 5);
+    }
+
+    pub fn rotating() {
+        assert_eq!(0b1000_0000u8.rotate_left(1), 1);
+        assert_eq!(0b1000_0000u8 << 1, 0);
+        assert_eq!(1u8.rotate_right(1), 0b1000_0000);
+        assert_eq!(0b1011_0001u8.rotate_left(8), 0b1011_0001);
+    }
+
+    pub fn swapping() {
+        assert_eq!(0xF0u8.swap_bytes(), 0xF0);
+        assert_eq!(0x12F0u16.swap_bytes(), 0xF012);
+        assert_eq!(0x1234_5678u32.swap_bytes(), 0x7856_3412);
+        assert_eq!(0xF0u8.reverse_bits(), 0x0F);
+        assert_eq!(0x1234u16.to_be_bytes(), [0x12, 0x34]);
+    }
+}
+
 #[cfg(test)]
 mod testing {
     #[test]
@@ -46,4 +169,58 @@ mod testing {
         assert_eq!(std::mem::size_of::<usize>(), 8);
         assert_eq!(std::mem::size_of::<isize>(), 8);
     }
+
+    #[test]
+    fn run_radix_format_specifiers() {
+        crate::radix_format::radix_specifiers();
+    }
+
+    #[test]
+    fn run_radix_format_alternate_forms() {
+        crate::radix_format::alternate_forms();
+    }
+
+    #[test]
+    fn run_integer_methods_bounds() {
+        crate::integer_methods::bounds();
+    }
+
+    #[test]
+    fn run_integer_methods_arithmetic() {
+        crate::integer_methods::arithmetic();
+    }
+
+    #[test]
+    fn run_integer_methods_abs_of_min() {
+        crate::integer_methods::abs_of_min();
+    }
+
+    // overflow checks, and so the panic, only exist in debug builds
+    #[test]
+    #[should_panic]
+    #[cfg(debug_assertions)]
+    fn run_integer_methods_abs_of_min_overflows() {
+        let min: i32 = std::hint::black_box(i32::MIN);
+        let _ = min.abs();
+    }
+
+    #[test]
+    fn run_bit_methods_counting() {
+        crate::bit_methods::counting();
+    }
+
+    #[test]
+    fn run_bit_methods_rotating() {
+        crate::bit_methods::rotating();
+    }
+
+    #[test]
+    fn run_bit_methods_swapping() {
+        crate::bit_methods::swapping();
+    }
+
+    #[test]
+    fn run_pointer_width() {
+        crate::pointer_width();
+    }
 }
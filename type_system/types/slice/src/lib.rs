@@ -91,6 +91,121 @@ mod array_slice {
     }
 }
 
+pub mod copy_ops {
+    //! Copying between two regions of the same buffer.
+    //!
+    //! `copy_within` handles overlapping regions itself, like `memmove`. Getting two `&mut` views
+    //! into one buffer is another matter: `&mut buf[a]` and `&mut buf[b]` would borrow `buf`
+    //! mutably twice, which the borrow checker rejects even when the ranges are disjoint, because
+    //! it does not look at index values. `split_at_mut` is the safe way out: it cuts the buffer at
+    //! one index into two non-overlapping halves, each of which is then indexed on its own.
+    //!
+    //! Two ranges overlap exactly when their intersection is non-empty:
+    //!
+    //! ```text
+    //! max(a.start, b.start) < min(a.end, b.end)
+    //! ```
+    //!
+    //! so touching ranges like `0..2` and `2..4` don't overlap, and neither does an empty range.
+
+    use std::ops::Range;
+
+    #[derive(Debug, PartialEq)]
+    pub enum CopyError {
+        /// The range starts after it ends.
+        Reversed {
+            start: usize,
+            end: usize,
+        },
+        /// The range, or the destination of a copy, goes past the end of the buffer. `end` is
+        /// `usize::MAX` when the end of the destination does not fit in a `usize`.
+        OutOfBounds {
+            end: usize,
+            len: usize,
+        },
+        Overlap,
+        LengthMismatch {
+            a: usize,
+            b: usize,
+        },
+    }
+
+    fn check(range: &Range<usize>, len: usize) -> Result<(), CopyError> {
+        if range.start > range.end {
+            return Err(CopyError::Reversed {
+                start: range.start,
+                end: range.end,
+            });
+        }
+        if range.end > len {
+            return Err(CopyError::OutOfBounds {
+                end: range.end,
+                len,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn overlaps(a: &Range<usize>, b: &Range<usize>) -> bool {
+        a.start.max(b.start) < a.end.min(b.end)
+    }
+
+    /// Validates the ranges instead of panicking like `copy_within` does, then delegates to it.
+    pub fn copy_within_checked(
+        buf: &mut [u8],
+        src: Range<usize>,
+        dst: usize,
+    ) -> Result<(), CopyError> {
+        check(&src, buf.len())?;
+        let end: usize = dst.checked_add(src.len()).ok_or(CopyError::OutOfBounds {
+            end: usize::MAX,
+            len: buf.len(),
+        })?;
+        check(&(dst..end), buf.len())?;
+        buf.copy_within(src, dst);
+        Ok(())
+    }
+
+    /// Returns `(&mut buf[a], &mut buf[b])`, the ranges must not overlap. By the elision rules
+    /// both slices borrow from `buf`, the only reference parameter.
+    pub fn copy_nonoverlapping_regions(
+        buf: &mut [u8],
+        a: Range<usize>,
+        b: Range<usize>,
+    ) -> Result<(&mut [u8], &mut [u8]), CopyError> {
+        check(&a, buf.len())?;
+        check(&b, buf.len())?;
+        if overlaps(&a, &b) {
+            return Err(CopyError::Overlap);
+        }
+        if a.end <= b.start {
+            let (left, right) = buf.split_at_mut(b.start);
+            Ok((&mut left[a], &mut right[..b.end - b.start]))
+        } else if b.end <= a.start {
+            let (left, right) = buf.split_at_mut(a.start);
+            Ok((&mut right[..a.end - a.start], &mut left[b]))
+        } else if a.is_empty() {
+            // an empty range lying inside the other one, it needs no part of the buffer
+            Ok((&mut [], &mut buf[b]))
+        } else {
+            Ok((&mut buf[a], &mut []))
+        }
+    }
+
+    /// Exchanges the contents of two equally long, non-overlapping ranges.
+    pub fn swap_regions(buf: &mut [u8], a: Range<usize>, b: Range<usize>) -> Result<(), CopyError> {
+        if a.len() != b.len() {
+            return Err(CopyError::LengthMismatch {
+                a: a.len(),
+                b: b.len(),
+            });
+        }
+        let (x, y) = copy_nonoverlapping_regions(buf, a, b)?;
+        x.swap_with_slice(y);
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod testing {
     #[test]
@@ -130,4 +245,99 @@ mod testing {
     fn run_array_slice_builder() {
         crate::array_slice::builder();
     }
+
+    #[test]
+    fn run_copy_ops_copy_within_checked() {
+        use crate::copy_ops::{copy_within_checked, CopyError};
+        let mut buf: [u8; 6] = [1, 2, 3, 4, 5, 6];
+        // overlapping source and destination are fine, like memmove
+        copy_within_checked(&mut buf, 0..4, 2).unwrap();
+        assert_eq!(buf, [1, 2, 1, 2, 3, 4]);
+
+        // the edges of the buffer
+        copy_within_checked(&mut buf, 4..6, 0).unwrap();
+        assert_eq!(buf, [3, 4, 1, 2, 3, 4]);
+        copy_within_checked(&mut buf, 0..1, 5).unwrap();
+        assert_eq!(buf, [3, 4, 1, 2, 3, 3]);
+        copy_within_checked(&mut buf, 6..6, 6).unwrap();
+
+        assert_eq!(
+            copy_within_checked(&mut buf, 0..2, 5),
+            Err(CopyError::OutOfBounds { end: 7, len: 6 })
+        );
+        assert_eq!(
+            copy_within_checked(&mut buf, 4..7, 0),
+            Err(CopyError::OutOfBounds { end: 7, len: 6 })
+        );
+        let reversed = std::ops::Range { start: 4, end: 2 };
+        assert_eq!(
+            copy_within_checked(&mut buf, reversed, 0),
+            Err(CopyError::Reversed { start: 4, end: 2 })
+        );
+        // `dst + src.len()` would overflow
+        assert_eq!(
+            copy_within_checked(&mut buf, 0..2, usize::MAX),
+            Err(CopyError::OutOfBounds {
+                end: usize::MAX,
+                len: 6
+            })
+        );
+        assert_eq!(buf, [3, 4, 1, 2, 3, 3]);
+    }
+
+    #[test]
+    fn run_copy_ops_nonoverlapping_regions() {
+        use crate::copy_ops::{copy_nonoverlapping_regions, CopyError};
+        let mut buf: [u8; 6] = [0, 1, 2, 3, 4, 5];
+        assert_eq!(
+            copy_nonoverlapping_regions(&mut buf, 0..3, 2..4),
+            Err(CopyError::Overlap)
+        );
+        assert_eq!(
+            copy_nonoverlapping_regions(&mut buf, 1..5, 2..3),
+            Err(CopyError::Overlap)
+        );
+
+        // touching ranges, in either order
+        let (a, b) = copy_nonoverlapping_regions(&mut buf, 0..2, 2..4).unwrap();
+        assert_eq!((&*a, &*b), (&[0, 1][..], &[2, 3][..]));
+        let (a, b) = copy_nonoverlapping_regions(&mut buf, 4..6, 0..4).unwrap();
+        a.copy_from_slice(&b[..2]);
+        assert_eq!(buf, [0, 1, 2, 3, 0, 1]);
+
+        // an empty range never overlaps
+        let (a, b) = copy_nonoverlapping_regions(&mut buf, 3..3, 1..5).unwrap();
+        assert!(a.is_empty());
+        assert_eq!(b.len(), 4);
+
+        let reversed = std::ops::Range { start: 5, end: 1 };
+        assert_eq!(
+            copy_nonoverlapping_regions(&mut buf, 0..1, reversed),
+            Err(CopyError::Reversed { start: 5, end: 1 })
+        );
+    }
+
+    #[test]
+    fn run_copy_ops_swap_regions() {
+        use crate::copy_ops::{swap_regions, CopyError};
+        let original: [u8; 7] = *b"abcdefg";
+        let mut buf: [u8; 7] = original;
+        swap_regions(&mut buf, 0..2, 5..7).unwrap();
+        assert_eq!(&buf, b"fgcdeab");
+        swap_regions(&mut buf, 5..7, 0..2).unwrap();
+        assert_eq!(buf, original);
+
+        swap_regions(&mut buf, 2..4, 4..6).unwrap();
+        assert_eq!(&buf, b"abefcdg");
+
+        assert_eq!(
+            swap_regions(&mut buf, 0..2, 3..6),
+            Err(CopyError::LengthMismatch { a: 2, b: 3 })
+        );
+        assert_eq!(swap_regions(&mut buf, 0..3, 2..5), Err(CopyError::Overlap));
+        assert_eq!(
+            swap_regions(&mut buf, 0..2, 6..8),
+            Err(CopyError::OutOfBounds { end: 8, len: 7 })
+        );
+    }
 }